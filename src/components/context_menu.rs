@@ -0,0 +1,91 @@
+//! Context menu component
+
+use crate::types::XYPosition;
+use dioxus::prelude::*;
+use dioxus_web::WebEventExt;
+
+/// Menu shown at `position` (the `screen_position` of a context menu event),
+/// kept inside the flow bounds. Clicking outside or pressing Escape calls `on_close`.
+#[component]
+pub fn ContextMenu(
+    children: Element,
+    position: XYPosition,
+    #[props(default)] on_close: Option<EventHandler<()>>,
+    #[props(default)] class: Option<String>,
+    #[props(default)] style: Option<String>,
+) -> Element {
+    // Flow bounds (left, top, width, height) and menu size, measured once mounted.
+    let mut bounds = use_signal(|| None::<(f64, f64, f64, f64)>);
+    let mut menu_size = use_signal(|| None::<(f64, f64)>);
+
+    let (left, top) = match *bounds.read() {
+        Some((flow_left, flow_top, flow_width, flow_height)) => {
+            let (width, height) = menu_size.read().unwrap_or((0.0, 0.0));
+            let max_left = (flow_width - width).max(0.0);
+            let max_top = (flow_height - height).max(0.0);
+            (
+                (position.x - flow_left).clamp(0.0, max_left),
+                (position.y - flow_top).clamp(0.0, max_top),
+            )
+        }
+        None => (position.x, position.y),
+    };
+    let visibility = if bounds.read().is_some() {
+        "visible"
+    } else {
+        "hidden"
+    };
+    let class = class.unwrap_or_default();
+    let style = style.unwrap_or_default();
+
+    let close = move || {
+        if let Some(handler) = &on_close {
+            handler.call(());
+        }
+    };
+
+    rsx! {
+        div {
+            class: "dioxus-flow__context-menu-backdrop",
+            onpointerdown: move |evt| {
+                evt.stop_propagation();
+                close();
+            },
+            oncontextmenu: move |evt| {
+                evt.prevent_default();
+                close();
+            },
+        }
+        div {
+            class: "dioxus-flow__context-menu {class}",
+            role: "menu",
+            tabindex: "-1",
+            style: "left: {left}px; top: {top}px; visibility: {visibility}; {style}",
+            onpointerdown: move |evt| evt.stop_propagation(),
+            oncontextmenu: move |evt| evt.prevent_default(),
+            onkeydown: move |evt| {
+                if evt.key() == Key::Escape {
+                    close();
+                }
+            },
+            onmounted: move |evt| {
+                let element: web_sys::Element = evt.as_web_event();
+                let menu = element.get_bounding_client_rect();
+                menu_size.set(Some((menu.width(), menu.height())));
+                let flow = element
+                    .closest(".dioxus-flow")
+                    .ok()
+                    .flatten()
+                    .map(|flow| flow.get_bounding_client_rect());
+                bounds.set(Some(match flow {
+                    Some(flow) => (flow.left(), flow.top(), flow.width(), flow.height()),
+                    None => (0.0, 0.0, f64::INFINITY, f64::INFINITY),
+                }));
+                if let Ok(element) = wasm_bindgen::JsCast::dyn_into::<web_sys::HtmlElement>(element) {
+                    element.focus().ok();
+                }
+            },
+            {children}
+        }
+    }
+}
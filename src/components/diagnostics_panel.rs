@@ -0,0 +1,102 @@
+//! Diagnostics list panel
+
+use crate::state::FlowState;
+use crate::types::{DiagnosticSeverity, DiagnosticTarget, HandleType};
+use dioxus::prelude::*;
+use dioxus::prelude::ReadableExt;
+
+/// Lists the validator diagnostics, most severe first. Clicking an entry
+/// focuses and centers the element it is attached to.
+#[component]
+pub fn DiagnosticsPanel<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    #[props(default)] position: Option<String>,
+    #[props(default)] class: Option<String>,
+    /// Hides diagnostics below this severity
+    #[props(default = DiagnosticSeverity::Info)]
+    min_severity: DiagnosticSeverity,
+    #[props(default)] aria_label: Option<String>,
+    #[props(default)] _marker: std::marker::PhantomData<(N, E)>,
+) -> Element {
+    let state = use_context::<FlowState<N, E>>();
+    let position = position.unwrap_or_else(|| "bottom-left".to_string());
+    let class = class.unwrap_or_default();
+    let aria_label = aria_label.unwrap_or_else(|| "Diagnostics".to_string());
+
+    let diagnostics = state.diagnostics.read().clone();
+    let mut entries: Vec<_> = diagnostics
+        .all()
+        .iter()
+        .filter(|diagnostic| diagnostic.severity >= min_severity)
+        .cloned()
+        .collect();
+    entries.sort_by_key(|diagnostic| std::cmp::Reverse(diagnostic.severity));
+    let summary = [
+        DiagnosticSeverity::Error,
+        DiagnosticSeverity::Warning,
+        DiagnosticSeverity::Info,
+    ]
+    .into_iter()
+    .filter(|&severity| severity >= min_severity)
+    .filter_map(|severity| {
+        let count = diagnostics.count(severity);
+        (count > 0).then(|| format!("{count} {}", severity.as_str()))
+    })
+    .collect::<Vec<_>>()
+    .join(", ");
+    let summary = if summary.is_empty() {
+        "No problems".to_string()
+    } else {
+        summary
+    };
+
+    rsx! {
+        div {
+            class: "dioxus-flow__panel {position}",
+            div {
+                class: "dioxus-flow__diagnostics {class}",
+                "aria-label": "{aria_label}",
+                div { class: "dioxus-flow__diagnostics-summary", "{summary}" }
+                ul {
+                    for (index, diagnostic) in entries.into_iter().enumerate() {
+                        li { key: "{index}",
+                            button {
+                                class: "dioxus-flow__diagnostics-entry {diagnostic.severity.as_str()}",
+                                onclick: {
+                                    let mut state = state.clone();
+                                    let diagnostic = diagnostic.clone();
+                                    move |_| state.focus_diagnostic(&diagnostic)
+                                },
+                                span { class: "dioxus-flow__diagnostics-target", "{target_label(&diagnostic.target)}" }
+                                span { class: "dioxus-flow__diagnostics-message", "{diagnostic.message}" }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+fn target_label(target: &DiagnosticTarget) -> String {
+    match target {
+        DiagnosticTarget::Node(id) => format!("Node {id}"),
+        DiagnosticTarget::Edge(id) => format!("Edge {id}"),
+        DiagnosticTarget::Handle {
+            node_id,
+            handle_id,
+            handle_type,
+        } => {
+            let kind = match handle_type {
+                HandleType::Source => "output",
+                HandleType::Target => "input",
+            };
+            match handle_id {
+                Some(handle_id) => format!("Node {node_id} {kind} {handle_id}"),
+                None => format!("Node {node_id} {kind}"),
+            }
+        }
+    }
+}
@@ -3,18 +3,19 @@
 use crate::components::{FlowProvider, GraphView};
 use crate::state::FlowState;
 use crate::types::{
-    ConnectionLineType, CoordinateExtent, Edge, Node, NodeExtent, PanOnScrollMode, SelectionMode,
-    Viewport, XYPosition,
+    ConnectionLineType, CoordinateExtent, Edge, FlowCommand, HandleType, Node, NodeExtent,
+    PanOnScrollMode, Position, SelectionMode, Viewport, XYPosition,
 };
+use crate::utils::browser::WindowListener;
 use dioxus::prelude::*;
 use dioxus::prelude::{ReadableExt, WritableExt};
+use dioxus_web::WebEventExt;
 use std::collections::HashMap;
 use js_sys::Function;
 use js_sys::Reflect;
 use std::rc::Rc;
 use wasm_bindgen::JsCast;
 use wasm_bindgen::JsValue;
-use wasm_bindgen::closure::Closure;
 
 #[component]
 pub fn DioxusFlow<
@@ -28,6 +29,7 @@ pub fn DioxusFlow<
     #[props(default)] default_edges: Vec<Edge<E>>,
     #[props(default)] node_types: Option<HashMap<String, Component<NodeProps<N, E>>>>,
     #[props(default)] edge_types: Option<HashMap<String, Component<EdgeComponentProps<E>>>>,
+    #[props(default)] marker_types: Option<HashMap<String, Component<MarkerComponentProps>>>,
     #[props(default)] on_nodes_change: Option<EventHandler<Vec<crate::types::NodeChange<N>>>>,
     #[props(default)] on_edges_change: Option<EventHandler<Vec<crate::types::EdgeChange<E>>>>,
     #[props(default)] on_connect: Option<EventHandler<crate::types::Connection>>,
@@ -75,12 +77,23 @@ pub fn DioxusFlow<
     #[props(default = true)] nodes_focusable: bool,
     #[props(default = true)] edges_focusable: bool,
     #[props(default = true)] edges_reconnectable: bool,
+    #[props(default = true)] edge_waypoints_editable: bool,
+    #[props(default = false)] floating_edges: bool,
+    #[props(default = 24.0)] parallel_edge_spacing: f64,
     #[props(default = true)] elements_selectable: bool,
     #[props(default = true)] select_nodes_on_drag: bool,
     #[props(default = false)] only_render_visible_elements: bool,
     #[props(default = 0.2)] visible_area_padding: f64,
     #[props(default = false)] selection_on_drag: bool,
     #[props(default = SelectionMode::Partial)] selection_mode: SelectionMode,
+    #[props(default = false)] select_edges_in_box: bool,
+    /// Highlights the relatives of the hovered or selected elements and dims the rest
+    #[props(default)]
+    highlight_mode: crate::types::HighlightMode,
+    #[props(default)] highlight_trigger: crate::types::HighlightTrigger,
+    /// Run after each change batch; their diagnostics are shown on the canvas
+    #[props(default)]
+    validators: Vec<crate::utils::SharedValidator<N, E>>,
     #[props(default)] node_extent: Option<crate::types::CoordinateExtent>,
     #[props(default)] connection_mode: Option<crate::types::ConnectionMode>,
     #[props(default)] connection_line_type: Option<ConnectionLineType>,
@@ -99,8 +112,12 @@ pub fn DioxusFlow<
     #[props(default = "nowheel".to_string())] no_wheel_class_name: String,
     #[props(default = "nopan".to_string())] no_pan_class_name: String,
     #[props(default)] delete_key_code: Option<Vec<String>>,
+    #[props(default)] keymap: Option<crate::types::Keymap>,
     #[props(default)] selection_key_code: Option<Vec<String>>,
     #[props(default)] multi_selection_key_code: Option<Vec<String>>,
+    #[props(default)] add_selection_key_code: Option<Vec<String>>,
+    #[props(default)] subtract_selection_key_code: Option<Vec<String>>,
+    #[props(default)] toggle_selection_key_code: Option<Vec<String>>,
     #[props(default = true)] elevate_nodes_on_select: bool,
     #[props(default = false)] elevate_edges_on_select: bool,
     #[props(default = crate::types::ZIndexMode::Basic)] z_index_mode: crate::types::ZIndexMode,
@@ -129,6 +146,22 @@ pub fn DioxusFlow<
     #[props(default)] on_edge_double_click: Option<EventHandler<crate::types::EdgeMouseEvent<E>>>,
     #[props(default)] on_edge_mouse_enter: Option<EventHandler<crate::types::EdgeMouseEvent<E>>>,
     #[props(default)] on_edge_mouse_leave: Option<EventHandler<crate::types::EdgeMouseEvent<E>>>,
+    #[props(default)] on_pane_click: Option<EventHandler<crate::types::PaneMouseEvent>>,
+    #[props(default)] on_pane_double_click: Option<EventHandler<crate::types::PaneMouseEvent>>,
+    #[props(default)] on_pane_mouse_move: Option<EventHandler<crate::types::PaneMouseEvent>>,
+    #[props(default)] on_pane_mouse_enter: Option<EventHandler<crate::types::PaneMouseEvent>>,
+    #[props(default)] on_pane_mouse_leave: Option<EventHandler<crate::types::PaneMouseEvent>>,
+    #[props(default)] on_pane_scroll: Option<EventHandler<crate::types::PaneScrollEvent>>,
+    #[props(default)] on_pane_context_menu: Option<EventHandler<crate::types::PaneMouseEvent>>,
+    #[props(default)] on_node_context_menu: Option<EventHandler<crate::types::NodeContextMenuEvent<N>>>,
+    #[props(default)] on_edge_context_menu: Option<EventHandler<crate::types::EdgeContextMenuEvent<E>>>,
+    #[props(default)] on_selection_context_menu: Option<EventHandler<crate::types::SelectionContextMenuEvent<N, E>>>,
+    #[props(default)] on_selection_drag_start: Option<EventHandler<crate::types::SelectionDragEvent<N>>>,
+    #[props(default)] on_selection_drag: Option<EventHandler<crate::types::SelectionDragEvent<N>>>,
+    #[props(default)] on_selection_drag_stop: Option<EventHandler<crate::types::SelectionDragEvent<N>>>,
+    #[props(default)] on_command: Option<EventHandler<crate::types::FlowCommand>>,
+    #[props(default)] nodes_selection_toolbar: Option<Element>,
+    #[props(default)] on_drop: Option<EventHandler<crate::types::FlowDropEvent<N>>>,
     #[props(default)] on_error: Option<crate::types::OnError>,
     #[props(default)] class: Option<String>,
     #[props(default)] style: Option<String>,
@@ -143,6 +176,7 @@ pub fn DioxusFlow<
                 edges,
                 node_types,
                 edge_types,
+                marker_types,
                 on_nodes_change,
                 on_edges_change,
                 on_connect,
@@ -188,12 +222,19 @@ pub fn DioxusFlow<
                 nodes_focusable,
                 edges_focusable,
                 edges_reconnectable,
+                edge_waypoints_editable,
+                floating_edges,
+                parallel_edge_spacing,
                 elements_selectable,
                 select_nodes_on_drag,
                 only_render_visible_elements,
                 visible_area_padding,
                 selection_on_drag,
                 selection_mode,
+                select_edges_in_box,
+                highlight_mode,
+                highlight_trigger,
+                validators,
                 node_extent,
                 connection_mode,
                 connection_line_type,
@@ -210,8 +251,12 @@ pub fn DioxusFlow<
                 no_wheel_class_name,
                 no_pan_class_name,
                 delete_key_code,
+                keymap,
                 selection_key_code,
                 multi_selection_key_code,
+                add_selection_key_code,
+                subtract_selection_key_code,
+                toggle_selection_key_code,
                 elevate_nodes_on_select,
                 elevate_edges_on_select,
                 z_index_mode,
@@ -238,6 +283,22 @@ pub fn DioxusFlow<
                 on_edge_double_click,
                 on_edge_mouse_enter,
                 on_edge_mouse_leave,
+                on_pane_click,
+                on_pane_double_click,
+                on_pane_mouse_move,
+                on_pane_mouse_enter,
+                on_pane_mouse_leave,
+                on_pane_scroll,
+                on_pane_context_menu,
+                on_node_context_menu,
+                on_edge_context_menu,
+                on_selection_context_menu,
+                on_selection_drag_start,
+                on_selection_drag,
+                on_selection_drag_stop,
+                on_command,
+                nodes_selection_toolbar,
+                on_drop,
                 on_error,
                 class,
                 style,
@@ -254,6 +315,7 @@ fn FlowBody<N: Clone + PartialEq + Default + 'static, E: Clone + PartialEq + Def
     #[props(default)] edges: Option<Signal<Vec<Edge<E>>>>,
     #[props(default)] node_types: Option<HashMap<String, Component<NodeProps<N, E>>>>,
     #[props(default)] edge_types: Option<HashMap<String, Component<EdgeComponentProps<E>>>>,
+    #[props(default)] marker_types: Option<HashMap<String, Component<MarkerComponentProps>>>,
     #[props(default)] on_nodes_change: Option<EventHandler<Vec<crate::types::NodeChange<N>>>>,
     #[props(default)] on_edges_change: Option<EventHandler<Vec<crate::types::EdgeChange<E>>>>,
     #[props(default)] on_connect: Option<EventHandler<crate::types::Connection>>,
@@ -301,12 +363,23 @@ fn FlowBody<N: Clone + PartialEq + Default + 'static, E: Clone + PartialEq + Def
     #[props(default = true)] nodes_focusable: bool,
     #[props(default = true)] edges_focusable: bool,
     #[props(default = true)] edges_reconnectable: bool,
+    #[props(default = true)] edge_waypoints_editable: bool,
+    #[props(default = false)] floating_edges: bool,
+    #[props(default = 24.0)] parallel_edge_spacing: f64,
     #[props(default = true)] elements_selectable: bool,
     #[props(default = true)] select_nodes_on_drag: bool,
     #[props(default = false)] only_render_visible_elements: bool,
     #[props(default = 0.2)] visible_area_padding: f64,
     #[props(default = false)] selection_on_drag: bool,
     #[props(default = SelectionMode::Partial)] selection_mode: SelectionMode,
+    #[props(default = false)] select_edges_in_box: bool,
+    /// Highlights the relatives of the hovered or selected elements and dims the rest
+    #[props(default)]
+    highlight_mode: crate::types::HighlightMode,
+    #[props(default)] highlight_trigger: crate::types::HighlightTrigger,
+    /// Run after each change batch; their diagnostics are shown on the canvas
+    #[props(default)]
+    validators: Vec<crate::utils::SharedValidator<N, E>>,
     #[props(default)] node_extent: Option<crate::types::CoordinateExtent>,
     #[props(default)] connection_mode: Option<crate::types::ConnectionMode>,
     #[props(default)] connection_line_type: Option<ConnectionLineType>,
@@ -325,8 +398,12 @@ fn FlowBody<N: Clone + PartialEq + Default + 'static, E: Clone + PartialEq + Def
     #[props(default = "nowheel".to_string())] no_wheel_class_name: String,
     #[props(default = "nopan".to_string())] no_pan_class_name: String,
     #[props(default)] delete_key_code: Option<Vec<String>>,
+    #[props(default)] keymap: Option<crate::types::Keymap>,
     #[props(default)] selection_key_code: Option<Vec<String>>,
     #[props(default)] multi_selection_key_code: Option<Vec<String>>,
+    #[props(default)] add_selection_key_code: Option<Vec<String>>,
+    #[props(default)] subtract_selection_key_code: Option<Vec<String>>,
+    #[props(default)] toggle_selection_key_code: Option<Vec<String>>,
     #[props(default = true)] elevate_nodes_on_select: bool,
     #[props(default = false)] elevate_edges_on_select: bool,
     #[props(default = crate::types::ZIndexMode::Basic)] z_index_mode: crate::types::ZIndexMode,
@@ -355,6 +432,22 @@ fn FlowBody<N: Clone + PartialEq + Default + 'static, E: Clone + PartialEq + Def
     #[props(default)] on_edge_double_click: Option<EventHandler<crate::types::EdgeMouseEvent<E>>>,
     #[props(default)] on_edge_mouse_enter: Option<EventHandler<crate::types::EdgeMouseEvent<E>>>,
     #[props(default)] on_edge_mouse_leave: Option<EventHandler<crate::types::EdgeMouseEvent<E>>>,
+    #[props(default)] on_pane_click: Option<EventHandler<crate::types::PaneMouseEvent>>,
+    #[props(default)] on_pane_double_click: Option<EventHandler<crate::types::PaneMouseEvent>>,
+    #[props(default)] on_pane_mouse_move: Option<EventHandler<crate::types::PaneMouseEvent>>,
+    #[props(default)] on_pane_mouse_enter: Option<EventHandler<crate::types::PaneMouseEvent>>,
+    #[props(default)] on_pane_mouse_leave: Option<EventHandler<crate::types::PaneMouseEvent>>,
+    #[props(default)] on_pane_scroll: Option<EventHandler<crate::types::PaneScrollEvent>>,
+    #[props(default)] on_pane_context_menu: Option<EventHandler<crate::types::PaneMouseEvent>>,
+    #[props(default)] on_node_context_menu: Option<EventHandler<crate::types::NodeContextMenuEvent<N>>>,
+    #[props(default)] on_edge_context_menu: Option<EventHandler<crate::types::EdgeContextMenuEvent<E>>>,
+    #[props(default)] on_selection_context_menu: Option<EventHandler<crate::types::SelectionContextMenuEvent<N, E>>>,
+    #[props(default)] on_selection_drag_start: Option<EventHandler<crate::types::SelectionDragEvent<N>>>,
+    #[props(default)] on_selection_drag: Option<EventHandler<crate::types::SelectionDragEvent<N>>>,
+    #[props(default)] on_selection_drag_stop: Option<EventHandler<crate::types::SelectionDragEvent<N>>>,
+    #[props(default)] on_command: Option<EventHandler<crate::types::FlowCommand>>,
+    #[props(default)] nodes_selection_toolbar: Option<Element>,
+    #[props(default)] on_drop: Option<EventHandler<crate::types::FlowDropEvent<N>>>,
     #[props(default)] on_error: Option<crate::types::OnError>,
     #[props(default)] class: Option<String>,
     #[props(default)] style: Option<String>,
@@ -398,6 +491,9 @@ fn FlowBody<N: Clone + PartialEq + Default + 'static, E: Clone + PartialEq + Def
         state_config.nodes_focusable.set(nodes_focusable);
         state_config.edges_focusable.set(edges_focusable);
         state_config.edges_reconnectable.set(edges_reconnectable);
+        state_config.edge_waypoints_editable.set(edge_waypoints_editable);
+        state_config.floating_edges.set(floating_edges);
+        state_config.parallel_edge_spacing.set(parallel_edge_spacing);
         state_config.elements_selectable.set(elements_selectable);
         state_config.select_nodes_on_drag.set(select_nodes_on_drag);
         state_config
@@ -406,6 +502,12 @@ fn FlowBody<N: Clone + PartialEq + Default + 'static, E: Clone + PartialEq + Def
         state_config.visible_area_padding.set(visible_area_padding);
         state_config.selection_on_drag.set(selection_on_drag);
         state_config.selection_mode.set(selection_mode);
+        state_config.select_edges_in_box.set(select_edges_in_box);
+        state_config.highlight_mode.set(highlight_mode);
+        state_config.highlight_trigger.set(highlight_trigger);
+        if *state_config.validators.peek() != validators {
+            state_config.validators.set(validators.clone());
+        }
         state_config.node_extent.set(node_extent);
         if let Some(mode) = connection_mode {
             state_config.connection_mode.set(mode);
@@ -433,6 +535,26 @@ fn FlowBody<N: Clone + PartialEq + Default + 'static, E: Clone + PartialEq + Def
             .set(no_wheel_class_name.clone());
         state_config.no_pan_class_name.set(no_pan_class_name.clone());
         state_config.on_connect_start.set(on_connect_start.clone());
+        state_config.on_pane_click.set(on_pane_click);
+        state_config.on_pane_double_click.set(on_pane_double_click);
+        state_config.on_pane_mouse_move.set(on_pane_mouse_move);
+        state_config.on_pane_mouse_enter.set(on_pane_mouse_enter);
+        state_config.on_pane_mouse_leave.set(on_pane_mouse_leave);
+        state_config.on_pane_scroll.set(on_pane_scroll);
+        state_config.on_pane_context_menu.set(on_pane_context_menu);
+        state_config.on_node_context_menu.set(on_node_context_menu);
+        state_config.on_edge_context_menu.set(on_edge_context_menu);
+        state_config.on_selection_context_menu.set(on_selection_context_menu);
+        state_config.on_selection_drag_start.set(on_selection_drag_start);
+        state_config.on_selection_drag.set(on_selection_drag);
+        state_config.on_selection_drag_stop.set(on_selection_drag_stop);
+        state_config.on_command.set(on_command);
+        let mut resolved_keymap = keymap.clone().unwrap_or_default();
+        if let Some(keys) = &delete_key_code {
+            resolved_keymap =
+                resolved_keymap.with_command_keys(FlowCommand::DeleteSelection, keys);
+        }
+        state_config.keymap.set(resolved_keymap);
         state_config.on_connect_end.set(on_connect_end.clone());
         state_config.on_error.set(on_error);
         state_config.on_viewport_change.set(on_viewport_change.clone());
@@ -449,6 +571,28 @@ fn FlowBody<N: Clone + PartialEq + Default + 'static, E: Clone + PartialEq + Def
         }
     });
 
+    let mut state_highlight = state.clone();
+    use_effect(move || {
+        let highlight = state_highlight.compute_highlight();
+        if *state_highlight.active_highlight.peek() != highlight {
+            state_highlight.active_highlight.set(highlight);
+        }
+    });
+
+    let mut state_validate = state.clone();
+    use_effect(move || {
+        // Positions change on every frame of a drag; validate once it ends
+        if state_validate.node_drag.read().is_some()
+            || state_validate.waypoint_drag.read().is_some()
+        {
+            return;
+        }
+        let diagnostics = state_validate.validate();
+        if *state_validate.diagnostics.peek() != diagnostics {
+            state_validate.diagnostics.set(diagnostics);
+        }
+    });
+
     let mut state_sync = state.clone();
     use_effect(move || {
         if let Some(nodes_signal) = &nodes {
@@ -515,7 +659,7 @@ fn FlowBody<N: Clone + PartialEq + Default + 'static, E: Clone + PartialEq + Def
             crate::types::ColorMode::Dark => is_dark_mode.set(true),
             crate::types::ColorMode::Light => is_dark_mode.set(false),
             crate::types::ColorMode::System => {
-                if let Some(window) = web_sys::window() {
+                if let Some(window) = crate::utils::browser::window() {
                     let func = js_sys::Reflect::get(&window, &JsValue::from_str("matchMedia"))
                         .ok()
                         .and_then(|value| value.dyn_into::<js_sys::Function>().ok());
@@ -548,98 +692,6 @@ fn FlowBody<N: Clone + PartialEq + Default + 'static, E: Clone + PartialEq + Def
         flow_class.push_str(" dioxus-flow--dark");
     }
 
-    let delete_keys = if disable_keyboard_a11y {
-        Vec::new()
-    } else {
-        delete_key_code
-            .unwrap_or_else(|| vec!["Backspace".to_string(), "Delete".to_string()])
-    };
-    let delete_pressed = crate::hooks::use_key_press_multi(delete_keys);
-    let mut delete_latched = use_signal(|| false);
-    let mut state_delete = state.clone();
-    let on_nodes_change_delete = on_nodes_change.clone();
-    let on_edges_change_delete = on_edges_change.clone();
-    use_effect(move || {
-        if disable_keyboard_a11y {
-            return;
-        }
-        let pressed = *delete_pressed.read();
-        if pressed && !*delete_latched.read() {
-            let selected_nodes: Vec<Node<N>> = state_delete
-                .nodes
-                .read()
-                .iter()
-                .filter(|n| n.selected && n.deletable.unwrap_or(true))
-                .cloned()
-                .collect();
-            let selected_node_ids: std::collections::HashSet<&str> =
-                selected_nodes.iter().map(|n| n.id.as_str()).collect();
-            let mut selected_edge_ids: std::collections::HashSet<String> = state_delete
-                .edges
-                .read()
-                .iter()
-                .filter(|e| e.selected && e.deletable.unwrap_or(true))
-                .map(|e| e.id.clone())
-                .collect();
-            let selected_edges: Vec<Edge<E>> = {
-                let edges = state_delete.edges.read();
-                for edge in edges.iter() {
-                    if selected_node_ids.contains(edge.source.as_str())
-                        || selected_node_ids.contains(edge.target.as_str())
-                    {
-                        selected_edge_ids.insert(edge.id.clone());
-                    }
-                }
-                edges
-                    .iter()
-                    .filter(|e| selected_edge_ids.contains(&e.id))
-                    .cloned()
-                    .collect()
-            };
-
-            if let Some(check) = on_before_delete {
-                let event = crate::types::BeforeDeleteEvent {
-                    nodes: selected_nodes.clone(),
-                    edges: selected_edges.clone(),
-                };
-                if !check(&event) {
-                    delete_latched.set(true);
-                    return;
-                }
-            }
-
-            if let Some(handler) = &on_nodes_delete {
-                handler.call(selected_nodes.clone());
-            }
-            if let Some(handler) = &on_edges_delete {
-                handler.call(selected_edges.clone());
-            }
-
-            let node_changes: Vec<crate::types::NodeChange<N>> = selected_nodes
-                .iter()
-                .map(|n| crate::types::NodeChange::remove(n.id.clone()))
-                .collect();
-            let edge_changes: Vec<crate::types::EdgeChange<E>> = selected_edges
-                .iter()
-                .map(|e| crate::types::EdgeChange::remove(e.id.clone()))
-                .collect();
-
-            if let Some(handler) = &on_nodes_change_delete {
-                handler.call(node_changes);
-            } else {
-                state_delete.apply_node_changes(node_changes);
-            }
-            if let Some(handler) = &on_edges_change_delete {
-                handler.call(edge_changes);
-            } else {
-                state_delete.apply_edge_changes(edge_changes);
-            }
-            delete_latched.set(true);
-        } else if !pressed && *delete_latched.read() {
-            delete_latched.set(false);
-        }
-    });
-
     let selection_keys = if disable_keyboard_a11y {
         Vec::new()
     } else {
@@ -675,6 +727,35 @@ fn FlowBody<N: Clone + PartialEq + Default + 'static, E: Clone + PartialEq + Def
             .set(*multi_pressed.read());
     });
 
+    // Keys that pick how a selection box combines with the current selection
+    let operation_keys = |keys: Option<Vec<String>>, default: &[&str]| {
+        if disable_keyboard_a11y {
+            Vec::new()
+        } else {
+            keys.unwrap_or_else(|| default.iter().map(|key| key.to_string()).collect())
+        }
+    };
+    let add_pressed =
+        crate::hooks::use_key_press_multi(operation_keys(add_selection_key_code, &["Shift"]));
+    let subtract_pressed =
+        crate::hooks::use_key_press_multi(operation_keys(subtract_selection_key_code, &["Alt"]));
+    let toggle_pressed = crate::hooks::use_key_press_multi(operation_keys(
+        toggle_selection_key_code,
+        &["Control", "Meta"],
+    ));
+    let mut state_operation = state.clone();
+    use_effect(move || {
+        state_operation
+            .add_selection_key_pressed
+            .set(*add_pressed.read());
+        state_operation
+            .subtract_selection_key_pressed
+            .set(*subtract_pressed.read());
+        state_operation
+            .toggle_selection_key_pressed
+            .set(*toggle_pressed.read());
+    });
+
     let pan_keys = pan_activation_key_code.unwrap_or_else(|| vec![" ".to_string(), "Space".to_string()]);
     let pan_pressed = crate::hooks::use_key_press_multi(pan_keys);
     let mut state_pan_key = state.clone();
@@ -694,107 +775,74 @@ fn FlowBody<N: Clone + PartialEq + Default + 'static, E: Clone + PartialEq + Def
             .set(*zoom_pressed.read());
     });
 
+    // Keys are read from the keymap in `FlowState` so prop changes apply to the
+    // listener, which is only registered once.
+    let mut flow_element = use_signal(|| None::<web_sys::Element>);
     let state_keyboard = state.clone();
-    let on_nodes_change_keyboard = on_nodes_change.clone();
-    let on_edges_change_keyboard = on_edges_change.clone();
+    let key_press = use_hook(move || {
+        let handlers = CommandHandlers {
+            on_before_delete,
+            on_nodes_delete,
+            on_edges_delete,
+            on_nodes_change,
+            on_edges_change,
+            on_connect,
+        };
+        KeyPressDispatcher(Rc::new(
+            move |key: &str, modifiers: crate::types::KeyModifiers, in_input: bool, repeat: bool| {
+                if disable_keyboard_a11y {
+                    return false;
+                }
+                dispatch_key_press(
+                    &mut state_keyboard.clone(),
+                    key,
+                    modifiers,
+                    in_input,
+                    repeat,
+                    &handlers,
+                )
+            },
+        ))
+    });
+    use_context_provider(|| key_press.clone());
+    let state_keyboard_scope = state.clone();
     let _keyboard_listener = use_hook(move || {
-        let mut state_keyboard_event = state_keyboard.clone();
-        let on_nodes_change_keyboard = on_nodes_change_keyboard.clone();
-        let on_edges_change_keyboard = on_edges_change_keyboard.clone();
         Rc::new(WindowListener::new(
             "keydown",
             move |evt: web_sys::KeyboardEvent| {
-                if disable_keyboard_a11y {
-                    return;
-                }
                 if evt.default_prevented() {
                     return;
                 }
-                if let Some(target) = evt
+                let target = evt
                     .target()
-                    .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
-                {
+                    .and_then(|t| t.dyn_into::<web_sys::Element>().ok());
+                let in_input = target.as_ref().is_some_and(|target| {
                     let tag = target.tag_name().to_uppercase();
-                    if tag == "INPUT"
+                    tag == "INPUT"
                         || tag == "TEXTAREA"
                         || tag == "SELECT"
                         || target.has_attribute("contenteditable")
-                    {
-                        return;
-                    }
-                }
-
-                let key = evt.key();
-                let step = if evt.shift_key() { 10.0 } else { 1.0 };
-                let mut dx = 0.0;
-                let mut dy = 0.0;
-                match key.as_str() {
-                    "ArrowUp" => dy = -step,
-                    "ArrowDown" => dy = step,
-                    "ArrowLeft" => dx = -step,
-                    "ArrowRight" => dx = step,
-                    "Tab" => {
-                        evt.prevent_default();
-                        focus_next_element(&mut state_keyboard_event, evt.shift_key());
-                        return;
-                    }
-                    " " | "Enter" => {
-                        toggle_focused_selection(
-                            &mut state_keyboard_event,
-                            &on_nodes_change_keyboard,
-                            &on_edges_change_keyboard,
-                        );
-                        evt.prevent_default();
-                        return;
-                    }
-                    "a" | "A" => {
-                        if evt.meta_key() || evt.ctrl_key() {
-                            state_keyboard_event.select_all();
-                            evt.prevent_default();
-                        }
-                        return;
-                    }
-                    _ => return,
-                }
-
-                if dx == 0.0 && dy == 0.0 {
-                    return;
-                }
-                if !*state_keyboard_event.nodes_draggable.read() {
-                    return;
-                }
-
-                let selected = state_keyboard_event.get_selected_nodes();
-                if selected.is_empty() {
-                    return;
-                }
-
-                let snap = *state_keyboard_event.snap_to_grid.read();
-                let grid = *state_keyboard_event.snap_grid.read();
-                let mut changes = Vec::new();
-                for node in selected.iter() {
-                    let mut next = XYPosition {
-                        x: node.position.x + dx,
-                        y: node.position.y + dy,
+                });
+                let scope = state_keyboard_scope.keymap.read().scope;
+                if scope == crate::types::KeymapScope::Flow {
+                    let inside = match (flow_element.read().as_ref(), target.as_ref()) {
+                        (Some(flow), Some(target)) => flow.contains(Some(target)),
+                        _ => false,
                     };
-                    if snap {
-                        next.x = (next.x / grid.0).round() * grid.0;
-                        next.y = (next.y / grid.1).round() * grid.1;
+                    if !inside {
+                        return;
                     }
-                    next = clamp_keyboard_position(&state_keyboard_event, node, next);
-                    changes.push(crate::types::NodeChange::Position {
-                        id: node.id.clone(),
-                        position: Some(next),
-                        dragging: false,
-                    });
                 }
 
-                if let Some(handler) = &on_nodes_change_keyboard {
-                    handler.call(changes);
-                } else {
-                    state_keyboard_event.apply_node_changes(changes);
+                let modifiers = crate::types::KeyModifiers {
+                    ctrl: evt.ctrl_key(),
+                    shift: evt.shift_key(),
+                    alt: evt.alt_key(),
+                    meta: evt.meta_key(),
+                };
+                if (key_press.0)(&evt.key(), modifiers, in_input, evt.repeat()) {
+                    evt.prevent_default();
                 }
-                evt.prevent_default();
             },
         ))
     });
@@ -805,14 +853,64 @@ fn FlowBody<N: Clone + PartialEq + Default + 'static, E: Clone + PartialEq + Def
         .as_ref()
         .and_then(|config| config.attribution.clone());
 
+    let on_drag_over = move |evt: DragEvent| {
+        if on_drop.is_some() {
+            evt.prevent_default();
+            evt.data().data_transfer().set_drop_effect("move");
+        }
+    };
+
+    let state_drop = state.clone();
+    let on_html_drop = move |evt: DragEvent| {
+        let Some(handler) = &on_drop else {
+            return;
+        };
+        evt.prevent_default();
+        let transfer = evt.data().data_transfer();
+        let payload = transfer
+            .get_data(crate::types::DRAG_DATA_FORMAT)
+            .filter(|data| !data.is_empty())
+            .or_else(|| transfer.get_data("text/plain"));
+        let coords = evt.data().client_coordinates();
+        handler.call(flow_drop_event(
+            &state_drop,
+            XYPosition::new(coords.x, coords.y),
+            payload,
+        ));
+    };
+
+    let state_pointer_drop = state.clone();
+    let on_pointer_drop = move |evt: PointerEvent| {
+        let Some(handler) = &on_drop else {
+            return;
+        };
+        let Some(payload) = crate::components::take_pointer_drag() else {
+            return;
+        };
+        let coords = evt.data.client_coordinates();
+        handler.call(flow_drop_event(
+            &state_pointer_drop,
+            XYPosition::new(coords.x, coords.y),
+            Some(payload),
+        ));
+    };
+
     rsx! {
         div {
             class: "{flow_class}",
             style: "{style}",
+            onmounted: move |evt| {
+                let element: web_sys::Element = evt.as_web_event();
+                flow_element.set(Some(element));
+            },
+            ondragover: on_drag_over,
+            ondrop: on_html_drop,
+            onpointerup: on_pointer_drop,
 
             GraphView {
                 node_types,
                 edge_types,
+                marker_types,
                 on_nodes_change,
                 on_edges_change,
                 on_connect,
@@ -836,6 +934,7 @@ fn FlowBody<N: Clone + PartialEq + Default + 'static, E: Clone + PartialEq + Def
                 on_edge_double_click,
                 on_edge_mouse_enter,
                 on_edge_mouse_leave,
+                nodes_selection_toolbar,
             }
 
             if show_attribution {
@@ -845,47 +944,373 @@ fn FlowBody<N: Clone + PartialEq + Default + 'static, E: Clone + PartialEq + Def
                 }
             }
 
+            crate::components::LiveRegion::<N, E> {}
+
             {children}
         }
     }
 }
 
-struct WindowListener {
-    event_type: String,
-    closure: Option<Closure<dyn FnMut(web_sys::KeyboardEvent)>>,
+fn flow_drop_event<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    state: &FlowState<N, E>,
+    screen_position: XYPosition,
+    payload: Option<String>,
+) -> crate::types::FlowDropEvent<N> {
+    let position = state.screen_to_flow_position(screen_position);
+    let parent_node = state.get_group_node_at_position(position);
+    let position_in_parent = parent_node.as_ref().and_then(|parent| {
+        let parent_abs = state.node_lookup.read().get(&parent.id)?.position_absolute;
+        Some(position - parent_abs)
+    });
+    crate::types::FlowDropEvent {
+        payload,
+        flow_position: state.snap_position(position),
+        screen_position,
+        parent_node,
+        position_in_parent: position_in_parent.map(|p| state.snap_position(p)),
+    }
 }
 
-impl WindowListener {
-    fn new(event_type: &str, handler: impl FnMut(web_sys::KeyboardEvent) + 'static) -> Self {
-        let Some(window) = web_sys::window() else {
-            return Self {
-                event_type: event_type.to_string(),
-                closure: None,
-            };
+/// Runs the keymap for a key press on the flow: key, modifiers, whether an
+/// input has focus and whether the key repeats. Returns whether a command
+/// consumed it. Provided as context by `DioxusFlow` to its window listener and
+/// the test harness.
+#[derive(Clone)]
+pub(crate) struct KeyPressDispatcher(pub(crate) Rc<KeyPressFn>);
+
+type KeyPressFn = dyn Fn(&str, crate::types::KeyModifiers, bool, bool) -> bool;
+
+/// Flow callbacks keymap commands report through
+pub(crate) struct CommandHandlers<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+> {
+    pub(crate) on_before_delete: Option<crate::types::OnBeforeDelete<N, E>>,
+    pub(crate) on_nodes_delete: Option<EventHandler<Vec<Node<N>>>>,
+    pub(crate) on_edges_delete: Option<EventHandler<Vec<Edge<E>>>>,
+    pub(crate) on_nodes_change: Option<EventHandler<Vec<crate::types::NodeChange<N>>>>,
+    pub(crate) on_edges_change: Option<EventHandler<Vec<crate::types::EdgeChange<E>>>>,
+    pub(crate) on_connect: Option<EventHandler<crate::types::Connection>>,
+}
+
+/// Runs the commands bound to a key press until one consumes it; returns
+/// whether one did
+pub(crate) fn dispatch_key_press<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    state: &mut FlowState<N, E>,
+    key: &str,
+    modifiers: crate::types::KeyModifiers,
+    in_input: bool,
+    repeat: bool,
+    handlers: &CommandHandlers<N, E>,
+) -> bool {
+    let connecting = {
+        let connection = state.connection.read();
+        connection.in_progress && connection.keyboard
+    };
+    let commands: Vec<FlowCommand> = state
+        .keymap
+        .read()
+        .commands_for(key, modifiers, in_input, connecting)
+        .into_iter()
+        .cloned()
+        .collect();
+    for command in commands {
+        let is_move = matches!(command, FlowCommand::MoveSelection { .. });
+        if repeat && !is_move {
+            return false;
+        }
+        if run_command(state, &command, handlers) {
+            let handler = *state.on_command.read();
+            if let Some(handler) = handler {
+                handler.call(command);
+            }
+            return true;
+        }
+    }
+    false
+}
+
+/// Runs a keymap command; returns whether the key press was consumed
+fn run_command<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    state: &mut FlowState<N, E>,
+    command: &FlowCommand,
+    handlers: &CommandHandlers<N, E>,
+) -> bool {
+    let CommandHandlers {
+        on_before_delete,
+        on_nodes_delete,
+        on_edges_delete,
+        on_nodes_change,
+        on_edges_change,
+        on_connect,
+    } = handlers;
+    match command {
+        FlowCommand::MoveSelection { dx, dy } => move_selection(state, *dx, *dy, on_nodes_change),
+        FlowCommand::SelectAll => {
+            state.select_all();
+            true
+        }
+        FlowCommand::DeleteSelection => {
+            delete_selection(
+                state,
+                *on_before_delete,
+                on_nodes_delete,
+                on_edges_delete,
+                on_nodes_change,
+                on_edges_change,
+            );
+            true
+        }
+        FlowCommand::ZoomIn => {
+            state.zoom_in(None);
+            true
+        }
+        FlowCommand::ZoomOut => {
+            state.zoom_out(None);
+            true
+        }
+        FlowCommand::FitView => {
+            state.fit_view(None);
+            true
+        }
+        FlowCommand::FocusNext | FlowCommand::FocusPrevious => {
+            focus_next_element(state, *command == FlowCommand::FocusPrevious);
+            true
+        }
+        FlowCommand::FocusDirection(direction) => focus_direction(state, *direction),
+        FlowCommand::ToggleFocusedSelection => {
+            toggle_focused_selection(state, on_nodes_change, on_edges_change);
+            true
+        }
+        FlowCommand::StartConnection => start_connection_from_focused_handle(state),
+        FlowCommand::NextConnectionTarget | FlowCommand::PreviousConnectionTarget => {
+            state.cycle_connection_target(*command == FlowCommand::NextConnectionTarget);
+            true
+        }
+        FlowCommand::ConfirmConnection => {
+            if let Some(connection) = state.finish_connection() {
+                if let Some(handler) = on_connect {
+                    handler.call(connection);
+                } else {
+                    let edge = crate::state::connection_to_edge::<E>(&connection, None);
+                    state.apply_edge_changes(vec![crate::types::EdgeChange::Add { edge }]);
+                }
+            }
+            true
+        }
+        FlowCommand::CancelConnection => {
+            state.cancel_connection();
+            true
+        }
+        FlowCommand::Custom(_) => true,
+    }
+}
+
+/// Starts a keyboard connection when DOM focus is on a connectable handle
+fn start_connection_from_focused_handle<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    state: &mut FlowState<N, E>,
+) -> bool {
+    if !*state.nodes_connectable.read() {
+        return false;
+    }
+    let Some(handle) = crate::utils::browser::window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.active_element())
+    else {
+        return false;
+    };
+    if !handle
+        .matches(".dioxus-flow__handle.connectable")
+        .unwrap_or(false)
+    {
+        return false;
+    }
+    let Some(node_id) = handle.get_attribute("data-node-id") else {
+        return false;
+    };
+    let handle_id = handle
+        .get_attribute("data-handle-id")
+        .filter(|id| !id.is_empty());
+    let handle_type = if handle
+        .matches(".dioxus-flow__handle-source")
+        .unwrap_or(false)
+    {
+        HandleType::Source
+    } else {
+        HandleType::Target
+    };
+    let position = match handle.get_attribute("data-handle-pos").as_deref() {
+        Some("Left") => Position::Left,
+        Some("Right") => Position::Right,
+        Some("Top") => Position::Top,
+        _ => Position::Bottom,
+    };
+    state.start_keyboard_connection(node_id, handle_id, handle_type, position);
+    true
+}
+
+/// Moves the selected nodes by `dx`/`dy`; returns false when nothing can move
+fn move_selection<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    state: &mut FlowState<N, E>,
+    dx: f64,
+    dy: f64,
+    on_nodes_change: &Option<EventHandler<Vec<crate::types::NodeChange<N>>>>,
+) -> bool {
+    if !*state.nodes_draggable.read() {
+        return false;
+    }
+    let selected = state.get_selected_nodes();
+    if selected.is_empty() {
+        return false;
+    }
+
+    let snap = *state.snap_to_grid.read();
+    let grid = *state.snap_grid.read();
+    let mut changes = Vec::new();
+    for node in selected.iter() {
+        let mut next = XYPosition {
+            x: node.position.x + dx,
+            y: node.position.y + dy,
         };
-        let closure = Closure::wrap(Box::new(handler) as Box<dyn FnMut(web_sys::KeyboardEvent)>);
-        window
-            .add_event_listener_with_callback(event_type, closure.as_ref().unchecked_ref())
-            .ok();
-        Self {
-            event_type: event_type.to_string(),
-            closure: Some(closure),
+        if snap {
+            next.x = (next.x / grid.0).round() * grid.0;
+            next.y = (next.y / grid.1).round() * grid.1;
         }
+        next = clamp_keyboard_position(state, node, next);
+        changes.push(crate::types::NodeChange::Position {
+            id: node.id.clone(),
+            position: Some(next),
+            dragging: false,
+        });
     }
+
+    let config = state.aria_label_config.peek().clone();
+    let moved = match selected.as_slice() {
+        [node] => state.node_announcement_label(&node.id),
+        _ => format!("{} nodes", selected.len()),
+    };
+    if let Some(crate::types::NodeChange::Position {
+        position: Some(position),
+        ..
+    }) = changes.first()
+    {
+        let message = crate::types::AriaLabelConfig::fill(
+            &config.node_moved,
+            crate::types::AriaLabelConfig::DEFAULT_NODE_MOVED,
+            &[
+                ("node", moved),
+                ("x", format!("{:.0}", position.x)),
+                ("y", format!("{:.0}", position.y)),
+            ],
+        );
+        state.announce(message);
+    }
+
+    if let Some(handler) = on_nodes_change {
+        handler.call(changes);
+    } else {
+        state.apply_node_changes(changes);
+    }
+    true
 }
 
-impl Drop for WindowListener {
-    fn drop(&mut self) {
-        if let Some(window) = web_sys::window() {
-            if let Some(closure) = &self.closure {
-                window
-                    .remove_event_listener_with_callback(
-                        &self.event_type,
-                        closure.as_ref().unchecked_ref(),
-                    )
-                    .ok();
+/// Deletes the deletable selected nodes and edges, plus the edges attached to those nodes
+fn delete_selection<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    state: &mut FlowState<N, E>,
+    on_before_delete: Option<crate::types::OnBeforeDelete<N, E>>,
+    on_nodes_delete: &Option<EventHandler<Vec<Node<N>>>>,
+    on_edges_delete: &Option<EventHandler<Vec<Edge<E>>>>,
+    on_nodes_change: &Option<EventHandler<Vec<crate::types::NodeChange<N>>>>,
+    on_edges_change: &Option<EventHandler<Vec<crate::types::EdgeChange<E>>>>,
+) {
+    let selected_nodes: Vec<Node<N>> = state
+        .nodes
+        .read()
+        .iter()
+        .filter(|n| n.selected && n.deletable.unwrap_or(true))
+        .cloned()
+        .collect();
+    let selected_node_ids: std::collections::HashSet<&str> =
+        selected_nodes.iter().map(|n| n.id.as_str()).collect();
+    let mut selected_edge_ids: std::collections::HashSet<String> = state
+        .edges
+        .read()
+        .iter()
+        .filter(|e| e.selected && e.deletable.unwrap_or(true))
+        .map(|e| e.id.clone())
+        .collect();
+    let selected_edges: Vec<Edge<E>> = {
+        let edges = state.edges.read();
+        for edge in edges.iter() {
+            if selected_node_ids.contains(edge.source.as_str())
+                || selected_node_ids.contains(edge.target.as_str())
+            {
+                selected_edge_ids.insert(edge.id.clone());
             }
         }
+        edges
+            .iter()
+            .filter(|e| selected_edge_ids.contains(&e.id))
+            .cloned()
+            .collect()
+    };
+    if selected_nodes.is_empty() && selected_edges.is_empty() {
+        return;
+    }
+
+    if let Some(check) = on_before_delete {
+        let event = crate::types::BeforeDeleteEvent {
+            nodes: selected_nodes.clone(),
+            edges: selected_edges.clone(),
+        };
+        if !check(&event) {
+            return;
+        }
+    }
+
+    if let Some(handler) = on_nodes_delete {
+        handler.call(selected_nodes.clone());
+    }
+    if let Some(handler) = on_edges_delete {
+        handler.call(selected_edges.clone());
+    }
+
+    let node_changes: Vec<crate::types::NodeChange<N>> = selected_nodes
+        .iter()
+        .map(|n| crate::types::NodeChange::remove(n.id.clone()))
+        .collect();
+    let edge_changes: Vec<crate::types::EdgeChange<E>> = selected_edges
+        .iter()
+        .map(|e| crate::types::EdgeChange::remove(e.id.clone()))
+        .collect();
+
+    if let Some(handler) = on_nodes_change {
+        handler.call(node_changes);
+    } else {
+        state.apply_node_changes(node_changes);
+    }
+    if let Some(handler) = on_edges_change {
+        handler.call(edge_changes);
+    } else {
+        state.apply_edge_changes(edge_changes);
     }
 }
 
@@ -977,6 +1402,20 @@ fn focus_next_element<
     if focusable.is_empty() {
         return;
     }
+    let within_node = if nodes_enabled && *state.nodes_connectable.read() {
+        focused_node_handle(reverse)
+    } else {
+        None
+    };
+    match within_node {
+        Some((_, Some(handle))) => {
+            focus_dom_element(&handle);
+            return;
+        }
+        // Leaving the handles continues from their node
+        Some((node_id, None)) => state.focused_node_id.set(Some(node_id)),
+        None => {}
+    }
     let current_node = state.focused_node_id.read().clone();
     let current_edge = state.focused_edge_id.read().clone();
     let next_index = current_node
@@ -1011,20 +1450,192 @@ fn focus_next_element<
         state.focused_node_id.set(None);
     }
 
-    if let Some(window) = web_sys::window() {
-        if let Some(document) = window.document() {
-            let selector = if is_node {
-                format!("[data-id=\"{}\"]", next_id.replace('\"', "\\\""))
+    if let Some(window) = crate::utils::browser::window()
+        && let Some(document) = window.document()
+    {
+        let selector = if is_node {
+            format!("[data-id=\"{}\"]", next_id.replace('\"', "\\\""))
+        } else {
+            format!("[data-edge-id=\"{}\"]", next_id.replace('\"', "\\\""))
+        };
+        if let Ok(Some(element)) = document.query_selector(&selector) {
+            focus_dom_element(&element);
+        }
+    }
+}
+
+/// Moves focus from the focused node towards `direction`; returns false when
+/// no node is focused
+fn focus_direction<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    state: &mut FlowState<N, E>,
+    direction: Position,
+) -> bool {
+    if !*state.nodes_focusable.read() {
+        return false;
+    }
+    let Some(current) = state.focused_node_id.read().clone() else {
+        return false;
+    };
+    let navigation = state.keymap.read().focus_navigation;
+    let target = match navigation {
+        crate::types::FocusNavigation::Spatial => spatial_focus_target(state, &current, direction),
+        crate::types::FocusNavigation::Graph => graph_focus_target(state, &current, direction),
+    };
+    if let Some(target) = target {
+        state.focused_node_id.set(Some(target.clone()));
+        state.focused_edge_id.set(None);
+        if *state.auto_pan_on_node_focus.read() {
+            state.ensure_node_visible(&target);
+        }
+        let element = crate::utils::browser::window()
+            .and_then(|window| window.document())
+            .and_then(|document| {
+                let selector = format!("[data-id=\"{}\"]", target.replace('\"', "\\\""));
+                document.query_selector(&selector).ok().flatten()
+            });
+        if let Some(element) = element {
+            focus_dom_element(&element);
+        }
+    }
+    // Consumed even without a target so Alt+Left does not navigate the page back
+    true
+}
+
+/// Centers of the nodes keyboard focus can move to
+fn focusable_node_centers<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    state: &FlowState<N, E>,
+) -> HashMap<String, XYPosition> {
+    state
+        .node_lookup
+        .read()
+        .iter()
+        .filter(|(_, internal)| !internal.node.hidden && internal.node.focusable.unwrap_or(true))
+        .map(|(id, internal)| (id.clone(), FlowState::<N, E>::node_flow_center(internal)))
+        .collect()
+}
+
+/// Nearest node whose center lies in a 90° cone around `direction`, preferring
+/// nodes close to the cone's axis
+fn spatial_focus_target<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    state: &FlowState<N, E>,
+    current: &str,
+    direction: Position,
+) -> Option<String> {
+    let centers = focusable_node_centers(state);
+    let origin = *centers.get(current)?;
+    centers
+        .iter()
+        .filter(|(id, _)| id.as_str() != current)
+        .filter_map(|(id, center)| {
+            let dx = center.x - origin.x;
+            let dy = center.y - origin.y;
+            let (along, across) = match direction {
+                Position::Left => (-dx, dy.abs()),
+                Position::Right => (dx, dy.abs()),
+                Position::Top => (-dy, dx.abs()),
+                Position::Bottom => (dy, dx.abs()),
+            };
+            (along > 0.0 && across <= along).then_some((id, along + 2.0 * across))
+        })
+        .min_by(|a, b| a.1.total_cmp(&b.1).then_with(|| a.0.cmp(b.0)))
+        .map(|(id, _)| id.clone())
+}
+
+/// Right/left go to the first outgoer/incomer and remember its siblings;
+/// up/down cycle through those siblings
+fn graph_focus_target<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    state: &mut FlowState<N, E>,
+    current: &str,
+    direction: Position,
+) -> Option<String> {
+    let centers = focusable_node_centers(state);
+    let neighbors = |outgoing: bool, of: &str| -> Vec<String> {
+        let mut ids: Vec<String> = Vec::new();
+        for edge in state.edges.read().iter().filter(|edge| !edge.hidden) {
+            let (from, to) = if outgoing {
+                (&edge.source, &edge.target)
             } else {
-                format!("[data-edge-id=\"{}\"]", next_id.replace('\"', "\\\""))
+                (&edge.target, &edge.source)
             };
-            if let Ok(Some(element)) = document.query_selector(&selector) {
-                focus_dom_element(&element);
+            if from == of && centers.contains_key(to) && !ids.contains(to) {
+                ids.push(to.clone());
+            }
+        }
+        ids.sort_by(|a, b| {
+            let (a, b) = (centers[a], centers[b]);
+            a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x))
+        });
+        ids
+    };
+
+    match direction {
+        Position::Right | Position::Left => {
+            let siblings = neighbors(direction == Position::Right, current);
+            let target = siblings.first().cloned();
+            if target.is_some() {
+                state.focus_siblings.set(siblings);
             }
+            target
+        }
+        Position::Top | Position::Bottom => {
+            let mut siblings = state.focus_siblings.read().clone();
+            if !siblings.iter().any(|id| id == current) {
+                // Focus moved some other way: use the outgoers of the first incomer
+                siblings = neighbors(false, current)
+                    .first()
+                    .map(|parent| neighbors(true, parent))
+                    .unwrap_or_default();
+            }
+            let index = siblings.iter().position(|id| id == current)?;
+            let len = siblings.len();
+            let next = if direction == Position::Bottom {
+                (index + 1) % len
+            } else {
+                (index + len - 1) % len
+            };
+            let target = siblings[next].clone();
+            state.focus_siblings.set(siblings);
+            (target != current).then_some(target)
         }
     }
 }
 
+/// Tab order inside the node holding DOM focus: the node, then its connectable
+/// handles. Returns the node id and the element to focus next, or `None` when
+/// focus leaves the node.
+fn focused_node_handle(reverse: bool) -> Option<(String, Option<web_sys::Element>)> {
+    let active = crate::utils::browser::window()?.document()?.active_element()?;
+    let node = active.closest(".dioxus-flow__node").ok()??;
+    let node_id = node.get_attribute("data-id")?;
+    let list = node
+        .query_selector_all(".dioxus-flow__handle.connectable")
+        .ok()?;
+    let handles: Vec<web_sys::Element> = (0..list.length())
+        .filter_map(|index| list.item(index))
+        .filter_map(|item| item.dyn_into::<web_sys::Element>().ok())
+        .collect();
+    let next = match handles.iter().position(|handle| *handle == active) {
+        Some(0) if reverse => Some(node),
+        Some(index) if reverse => handles.get(index - 1).cloned(),
+        Some(index) => handles.get(index + 1).cloned(),
+        None if !reverse && active == node => handles.first().cloned(),
+        None => None,
+    };
+    Some((node_id, next))
+}
+
 fn toggle_focused_selection<
     N: Clone + PartialEq + Default + 'static,
     E: Clone + PartialEq + Default + 'static,
@@ -1177,10 +1788,29 @@ pub struct NodeProps<
     pub connectable: bool,
     #[props(default)]
     pub selectable: bool,
+    /// Part of the active highlight
+    #[props(default)]
+    pub highlighted: bool,
+    /// Outside the active highlight
+    #[props(default)]
+    pub dimmed: bool,
+    /// Validator diagnostics attached to this element
+    #[props(default)]
+    pub diagnostics: Vec<crate::types::Diagnostic>,
     #[props(default)]
     pub _marker: std::marker::PhantomData<E>,
 }
 
+/// Props passed to custom marker components, which render the whole `marker` element
+#[derive(Clone, PartialEq, Props)]
+pub struct MarkerComponentProps {
+    /// Id the rendered `marker` element must use
+    pub id: String,
+    pub marker: crate::types::EdgeMarker,
+    /// Resolved marker color
+    pub color: String,
+}
+
 /// Props passed to custom edge components
 #[derive(Clone, PartialEq, Props)]
 pub struct EdgeComponentProps<T: Clone + PartialEq + Default + 'static> {
@@ -1191,4 +1821,19 @@ pub struct EdgeComponentProps<T: Clone + PartialEq + Default + 'static> {
     pub target_y: f64,
     pub source_position: crate::types::Position,
     pub target_position: crate::types::Position,
+    /// Sideways offset when several edges connect the same nodes
+    #[props(default)]
+    pub parallel_offset: f64,
+    /// Part of the active highlight
+    #[props(default)]
+    pub highlighted: bool,
+    /// Outside the active highlight
+    #[props(default)]
+    pub dimmed: bool,
+    /// Validator diagnostics attached to this element
+    #[props(default)]
+    pub diagnostics: Vec<crate::types::Diagnostic>,
+    /// Label placements after overlapping labels were moved apart, for `EdgeLabelRenderer`
+    #[props(default)]
+    pub label_positions: Vec<crate::types::EdgeLabelPosition>,
 }
@@ -0,0 +1,117 @@
+//! Drag source for external palettes
+
+use crate::types::DRAG_DATA_FORMAT;
+use crate::utils::browser::WindowListener;
+use dioxus::prelude::dioxus_elements::input_data::MouseButton;
+use dioxus::prelude::*;
+use dioxus_web::WebEventExt;
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+
+thread_local! {
+    static POINTER_DRAG: RefCell<Option<String>> = const { RefCell::new(None) };
+}
+
+/// Take the payload of a pointer-based drag that is currently in progress.
+pub(crate) fn take_pointer_drag() -> Option<String> {
+    POINTER_DRAG.with(|drag| drag.borrow_mut().take())
+}
+
+fn set_pointer_drag(payload: Option<String>) {
+    POINTER_DRAG.with(|drag| *drag.borrow_mut() = payload);
+}
+
+/// Element that can be dragged onto a `DioxusFlow` to trigger its `on_drop`.
+///
+/// Works with HTML5 drag and drop and with plain pointer drags (e.g. touch).
+#[component]
+pub fn DragSource(
+    /// Payload handed to `FlowDropEvent::payload`, usually JSON
+    payload: String,
+    #[props(default)] class: Option<String>,
+    #[props(default)] style: Option<String>,
+    #[props(default)] disabled: bool,
+    children: Element,
+) -> Element {
+    let class = class.unwrap_or_default();
+    let style = style.unwrap_or_default();
+    let mut dragging = use_signal(|| false);
+
+    use_hook(move || {
+        let mut dragging_up = dragging;
+        let mut dragging_cancel = dragging;
+        let up = WindowListener::new("pointerup", move |_| {
+            if *dragging_up.peek() {
+                set_pointer_drag(None);
+                dragging_up.set(false);
+            }
+        });
+        let cancel = WindowListener::new("pointercancel", move |_| {
+            if *dragging_cancel.peek() {
+                set_pointer_drag(None);
+                dragging_cancel.set(false);
+            }
+        });
+        struct Cleanup {
+            _up: WindowListener<web_sys::PointerEvent>,
+            _cancel: WindowListener<web_sys::PointerEvent>,
+        }
+
+        Rc::new(Cleanup {
+            _up: up,
+            _cancel: cancel,
+        })
+    });
+
+    let payload_pointer = payload.clone();
+    let on_pointer_down = move |evt: PointerEvent| {
+        if disabled || evt.data.trigger_button() != Some(MouseButton::Primary) {
+            return;
+        }
+        // Touch pointers are implicitly captured; release so `pointerup`
+        // reaches the flow the pointer ends up over.
+        let event: web_sys::PointerEvent = evt.as_web_event();
+        if let Some(target) = event
+            .target()
+            .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
+        {
+            target.release_pointer_capture(event.pointer_id()).ok();
+        }
+        set_pointer_drag(Some(payload_pointer.clone()));
+        dragging.set(true);
+    };
+
+    let payload_drag = payload.clone();
+    let on_drag_start = move |evt: DragEvent| {
+        if disabled {
+            evt.prevent_default();
+            return;
+        }
+        // The browser drag takes over from the pointer drag.
+        set_pointer_drag(None);
+        let transfer = evt.data().data_transfer();
+        transfer.set_data(DRAG_DATA_FORMAT, &payload_drag).ok();
+        transfer.set_data("text/plain", &payload_drag).ok();
+        transfer.set_effect_allowed("move");
+        dragging.set(true);
+    };
+
+    let on_drag_end = move |_evt: DragEvent| {
+        dragging.set(false);
+    };
+
+    let dragging_class = if *dragging.read() { "dragging" } else { "" };
+
+    rsx! {
+        div {
+            class: "dioxus-flow__drag-source {dragging_class} {class}",
+            style: "{style}",
+            draggable: if disabled { "false" } else { "true" },
+            onpointerdown: on_pointer_down,
+            ondragstart: on_drag_start,
+            ondragend: on_drag_end,
+            {children}
+        }
+    }
+}
@@ -1,20 +1,56 @@
 //! Edge label renderer component
 
+use dioxus::prelude::ReadableExt;
 use dioxus::prelude::*;
 
+use crate::state::FlowState;
+use crate::types::{EdgeLabelPosition, XYPosition};
+
+/// HTML overlay for edge labels. Custom edges can pass the `label_positions`
+/// from their `EdgeComponentProps` to render the edge's `label` and `labels`.
 #[component]
 pub fn EdgeLabelRenderer<
     N: Clone + PartialEq + Default + 'static,
     E: Clone + PartialEq + Default + 'static,
 >(
     children: Element,
+    #[props(default)] label_positions: Vec<EdgeLabelPosition>,
     #[props(default = true)] _no_scale: bool,
     #[props(default)] _marker: std::marker::PhantomData<(N, E)>,
 ) -> Element {
+    let state = use_context::<FlowState<N, E>>();
+    let zoom = state.viewport.read().zoom;
+    let labels: Vec<(String, String, String)> = label_positions
+        .into_iter()
+        .map(|position| {
+            let screen = state.flow_to_screen_position(XYPosition::new(position.x, position.y));
+            let style = format!(
+                "position: absolute; transform: translate({}px, {}px) translate(-50%, -50%) scale({}) rotate({}deg);",
+                screen.x, screen.y, zoom, position.angle
+            );
+            let key = position
+                .index
+                .map(|index| index.to_string())
+                .unwrap_or_else(|| "main".to_string());
+            (key, style, position.text)
+        })
+        .collect();
+
     rsx! {
         div {
             class: "dioxus-flow__edgelabel-renderer",
-            div { class: "dioxus-flow__edge-labels", {children} }
+            div {
+                class: "dioxus-flow__edge-labels",
+                for (key, style, text) in labels {
+                    div {
+                        key: "{key}",
+                        class: "dioxus-flow__edge-label-html",
+                        style: "{style}",
+                        "{text}"
+                    }
+                }
+                {children}
+            }
         }
     }
 }
@@ -1,13 +1,19 @@
 //! Edge renderer component
 
-use crate::components::EdgeComponentProps;
+use crate::components::{EdgeComponentProps, MarkerComponentProps};
 use crate::state::FlowState;
+use crate::state::{EdgePulse, WaypointDragState};
 use crate::types::{
-    Edge, EdgeMarker, HandleBound, HandleBounds, HandleType, MarkerType, Position,
-    ReconnectableValue, XYPosition,
+    AnimationDirection, Edge, EdgeAnimation, EdgeLabelPosition, EdgeMarker, EdgePathResult,
+    HandleBound, HandleBounds, HandleType, MarkerType, Position, Rect, ReconnectableValue,
+    WaypointStyle, XYPosition,
 };
 use crate::utils::{
-    get_bezier_path, get_simple_bezier_path, get_smooth_step_path, get_step_path, get_straight_path,
+    EdgeGeometry, get_bezier_path, get_catmull_rom_path, get_edge_label_positions,
+    get_floating_edge_params, get_offset_bezier_path, get_offset_straight_path,
+    get_orthogonal_path, get_parallel_edge_offsets, get_polyline_path, get_rect_intersection,
+    get_self_loop_path, get_simple_bezier_path, get_smooth_step_path, get_step_path,
+    get_straight_path, resolve_label_collisions,
 };
 use dioxus::prelude::dioxus_elements::input_data::MouseButton;
 use dioxus::prelude::*;
@@ -21,6 +27,7 @@ pub fn EdgeRenderer<
     E: Clone + PartialEq + Default + 'static,
 >(
     #[props(default)] edge_types: Option<HashMap<String, Component<EdgeComponentProps<E>>>>,
+    #[props(default)] marker_types: Option<HashMap<String, Component<MarkerComponentProps>>>,
     #[props(default)] on_nodes_change: Option<EventHandler<Vec<crate::types::NodeChange<N>>>>,
     #[props(default)] on_edges_change: Option<EventHandler<Vec<crate::types::EdgeChange<E>>>>,
     #[props(default)] on_connect: Option<EventHandler<crate::types::Connection>>,
@@ -49,6 +56,9 @@ pub fn EdgeRenderer<
         };
         let z_index_mode = *state_visible.z_index_mode.read();
         let elevate = *state_visible.elevate_edges_on_select.read();
+        let floating_edges = *state_visible.floating_edges.read();
+        let offsets =
+            get_parallel_edge_offsets(&edges, *state_visible.parallel_edge_spacing.read());
         let mut items: Vec<EdgeRender<E>> = edges
             .iter()
             .filter_map(|edge| {
@@ -57,37 +67,15 @@ pub fn EdgeRenderer<
                         return None;
                     }
                 }
-                let source_node = nodes.get(&edge.source)?;
-                let target_node = nodes.get(&edge.target)?;
-
-                let source_pos = source_node.node.source_position.unwrap_or(Position::Right);
-                let target_pos = target_node.node.target_position.unwrap_or(Position::Left);
-
-                let (source_x, source_y) = handle_position_for_edge(
-                    &source_node,
-                    HandleType::Source,
-                    edge.source_handle.as_deref(),
-                    source_pos,
-                );
-                let (target_x, target_y) = handle_position_for_edge(
-                    &target_node,
-                    HandleType::Target,
-                    edge.target_handle.as_deref(),
-                    target_pos,
-                );
-
-                Some(EdgeRender {
-                    edge: edge.clone(),
-                    source_x,
-                    source_y,
-                    target_x,
-                    target_y,
-                    source_pos,
-                    target_pos,
-                })
+                let parallel_offset = offsets.get(&edge.id).copied().unwrap_or(0.0);
+                edge_render_item(edge, &nodes, parallel_offset, floating_edges)
             })
             .collect();
 
+        for item in items.iter_mut() {
+            apply_marker_insets(item);
+        }
+
         items.sort_by_key(|item| {
             let base = item.edge.z_index.unwrap_or(0);
             if elevate && item.edge.selected && z_index_mode != crate::types::ZIndexMode::Manual {
@@ -99,44 +87,49 @@ pub fn EdgeRenderer<
         items
     });
     let render_edges = render_edges_memo.read();
+    let pulses = state.edge_pulses.read().clone();
 
     let mut marker_defs: Vec<(String, EdgeMarker)> = Vec::new();
     let mut marker_ids: HashMap<String, String> = HashMap::new();
     let default_marker_color = state.default_marker_color.read().clone();
     for item in render_edges.iter() {
-        if let Some(marker) = item.edge.marker_start.clone() {
-            let marker = if marker.color.is_none() {
-                if let Some(color) = default_marker_color.clone() {
-                    let mut m = marker;
-                    m.color = Some(color);
-                    m
-                } else {
-                    marker
-                }
-            } else {
-                marker
-            };
+        if let Some(marker) = item.edge.marker_start.as_ref() {
+            let marker = placed_marker(marker, default_marker_color.as_ref(), true);
             resolve_marker_id(&marker, &mut marker_ids, &mut marker_defs);
         }
-        if let Some(marker) = item.edge.marker_end.clone() {
-            let marker = if marker.color.is_none() {
-                if let Some(color) = default_marker_color.clone() {
-                    let mut m = marker;
-                    m.color = Some(color);
-                    m
-                } else {
-                    marker
-                }
-            } else {
-                marker
-            };
+        if let Some(marker) = item.edge.marker_end.as_ref() {
+            let marker = placed_marker(marker, default_marker_color.as_ref(), false);
             resolve_marker_id(&marker, &mut marker_ids, &mut marker_defs);
         }
     }
 
+    let is_custom = |edge: &Edge<E>| {
+        edge.edge_type
+            .as_ref()
+            .is_some_and(|t| edge_types.as_ref().is_some_and(|map| map.contains_key(t)))
+    };
+    let path_results: Vec<Option<EdgePathResult>> = render_edges
+        .iter()
+        .map(|item| (!is_custom(&item.edge)).then(|| edge_path_for_type(item)))
+        .collect();
+    // Labels of custom edges are placed along the built-in path for their
+    // endpoints so they take part in collision resolution too.
+    let mut label_positions: Vec<EdgeLabelPosition> = render_edges
+        .iter()
+        .zip(&path_results)
+        .flat_map(|(item, path)| match path {
+            Some(path) => get_edge_label_positions(&item.edge, path),
+            None => get_edge_label_positions(&item.edge, &edge_path_for_type(item)),
+        })
+        .collect();
+    resolve_label_collisions(&mut label_positions);
+    let active_highlight = state.active_highlight.read().clone();
+    let diagnostics = state.diagnostics.read().clone();
+
     let edge_elements: Vec<Element> = render_edges
         .iter()
-        .map(|item| {
+        .zip(path_results)
+        .map(|(item, path_result)| {
             let edge = &item.edge;
             let source_x = item.source_x;
             let source_y = item.source_y;
@@ -150,6 +143,23 @@ pub fn EdgeRenderer<
                 .as_ref()
                 .and_then(|t| edge_types.as_ref().and_then(|map| map.get(t)))
                 .cloned();
+            let (highlighted, dimmed) = match &active_highlight {
+                Some(highlight) => {
+                    let highlighted = highlight.contains_edge(&edge.id);
+                    (highlighted, !highlighted)
+                }
+                None => (false, false),
+            };
+            let edge_diagnostics: Vec<crate::types::Diagnostic> =
+                diagnostics.for_edge(&edge.id).into_iter().cloned().collect();
+            let diagnostic_severity = crate::types::max_severity(&edge_diagnostics);
+            let diagnostic_title = (!edge_diagnostics.is_empty()).then(|| {
+                let messages: Vec<&str> = edge_diagnostics
+                    .iter()
+                    .map(|diagnostic| diagnostic.message.as_str())
+                    .collect();
+                messages.join("\n")
+            });
 
             if let Some(component) = custom {
                 component(EdgeComponentProps {
@@ -160,18 +170,26 @@ pub fn EdgeRenderer<
                     target_y,
                     source_position: source_pos,
                     target_position: target_pos,
+                    parallel_offset: item.parallel_offset,
+                    highlighted,
+                    dimmed,
+                    diagnostics: edge_diagnostics,
+                    label_positions: label_positions
+                        .iter()
+                        .filter(|position| position.edge_id == edge.id)
+                        .cloned()
+                        .collect(),
                 })
             } else {
-                let animated = edge.animated;
-                let path_result = edge_path_for_type(
-                    edge,
-                    source_x,
-                    source_y,
-                    target_x,
-                    target_y,
-                    source_pos,
-                    target_pos,
-                );
+                let animation = edge.resolved_animation();
+                let animated = animation.as_ref().is_some_and(|animation| animation.dashes);
+                let path_result = path_result.unwrap_or_else(|| edge_path_for_type(item));
+                let edge_pulses: Vec<EdgePulse> = pulses
+                    .iter()
+                    .filter(|pulse| pulse.edge_id == edge.id)
+                    .cloned()
+                    .collect();
+                let motion = edge_motion(&path_result.geometry, animation.as_ref(), &edge_pulses);
                 let base_class = match (edge.selected, animated) {
                     (true, true) => "dioxus-flow__edge-path animated",
                     (true, false) => "dioxus-flow__edge-path",
@@ -183,7 +201,18 @@ pub fn EdgeRenderer<
                 } else {
                     base_class.to_string()
                 };
-                let style = edge.style.clone().unwrap_or_default();
+                let mut style = edge.style.clone().unwrap_or_default();
+                if let Some(animation) = animation.as_ref().filter(|animation| animation.dashes) {
+                    // The dash keyframes shift the pattern by 10 units per cycle.
+                    let duration = 10.0 / animation.speed.max(f64::EPSILON);
+                    let direction = match animation.direction {
+                        AnimationDirection::Forward => "normal",
+                        AnimationDirection::Reverse => "reverse",
+                    };
+                    style = format!(
+                        "{style};animation-duration:{duration}s;animation-direction:{direction}"
+                    );
+                }
                 let edge_id = edge.id.clone();
                 let edge_id_for_select = edge_id.clone();
                 let edge_selectable = edge.selectable.unwrap_or(true);
@@ -198,6 +227,21 @@ pub fn EdgeRenderer<
                 } else {
                     "dioxus-flow__edge"
                 };
+                let mut edge_class = if animated {
+                    format!("{edge_class} animated")
+                } else {
+                    edge_class.to_string()
+                };
+                if highlighted {
+                    edge_class.push_str(" highlighted");
+                }
+                if dimmed {
+                    edge_class.push_str(" dimmed");
+                }
+                if let Some(severity) = diagnostic_severity {
+                    edge_class.push_str(" diagnostic-");
+                    edge_class.push_str(severity.as_str());
+                }
                 let edge_selected = edge.selected;
                 let mut state_select = state.clone();
                 let on_edges_change_select = on_edges_change.clone();
@@ -257,33 +301,39 @@ pub fn EdgeRenderer<
                     .edge
                     .marker_start
                     .as_ref()
-                    .and_then(|marker| marker_id_for(marker, &marker_ids))
+                    .map(|marker| placed_marker(marker, default_marker_color.as_ref(), true))
+                    .and_then(|marker| marker_id_for(&marker, &marker_ids))
                     .map(|id| format!("url(#{})", id))
                     .unwrap_or_default();
                 let marker_end_attr = item
                     .edge
                     .marker_end
                     .as_ref()
-                    .and_then(|marker| marker_id_for(marker, &marker_ids))
+                    .map(|marker| placed_marker(marker, default_marker_color.as_ref(), false))
+                    .and_then(|marker| marker_id_for(&marker, &marker_ids))
                     .map(|id| format!("url(#{})", id))
                     .unwrap_or_default();
-                let label = edge.label.clone();
                 let show_label_bg = edge.label_show_bg.unwrap_or(false);
                 let label_style = edge.label_style.clone().unwrap_or_default();
                 let label_bg_style = edge.label_bg_style.clone().unwrap_or_default();
-                let label_padding = edge.label_bg_padding.unwrap_or((6.0, 4.0));
                 let label_radius = edge.label_bg_border_radius.unwrap_or(0.0);
                 let label_x = path_result.label_x;
                 let label_y = path_result.label_y;
-                let label_metrics = label.as_ref().map(|text: &String| {
-                    let text_len = text.chars().count() as f64;
-                    let text_width = text_len * 6.0;
-                    let text_height = 14.0;
-                    let bg_width = text_width + label_padding.0 * 2.0;
-                    let bg_height = text_height + label_padding.1 * 2.0;
-                    (bg_width, bg_height)
-                });
-                let (bg_width, bg_height) = label_metrics.unwrap_or((0.0, 0.0));
+                let edge_labels: Vec<(EdgeLabelPosition, String, String)> = label_positions
+                    .iter()
+                    .filter(|position| position.edge_id == edge.id)
+                    .map(|position| {
+                        let extra = position.index.and_then(|index| edge.labels.get(index));
+                        let class = extra
+                            .and_then(|label| label.class_name.clone())
+                            .unwrap_or_default();
+                        let style = match extra.and_then(|label| label.style.as_ref()) {
+                            Some(style) => format!("{label_style};{style}"),
+                            None => label_style.clone(),
+                        };
+                        (position.clone(), class, style)
+                    })
+                    .collect();
                 let reconnectable = edge.reconnectable.unwrap_or(ReconnectableValue::True);
                 let edges_reconnectable = *state.edges_reconnectable.read();
                 let allow_reconnect_source = edges_reconnectable
@@ -396,6 +446,20 @@ pub fn EdgeRenderer<
                     }
                 };
 
+                let show_waypoints = edge.selected && *state.edge_waypoints_editable.read();
+                let waypoints: Vec<(usize, XYPosition)> =
+                    edge.points.iter().copied().enumerate().collect();
+                let insert_points: Vec<(usize, XYPosition)> = if edge.points.is_empty() {
+                    vec![(0, XYPosition::new(label_x, label_y))]
+                } else {
+                    waypoint_route(edge, source_x, source_y, target_x, target_y)
+                        .windows(2)
+                        .enumerate()
+                        .map(|(index, pair)| (index, (pair[0] + pair[1]) * 0.5))
+                        .collect()
+                };
+                let edge_points = edge.points.clone();
+
                 let edge_click = edge.clone();
                 let on_click = move |_| {
                     if let Some(handler) = &on_edge_click {
@@ -413,7 +477,11 @@ pub fn EdgeRenderer<
                     }
                 };
                 let edge_enter = edge.clone();
+                let mut state_enter = state.clone();
                 let on_mouse_enter = move |_| {
+                    if *state_enter.highlight_mode.peek() != crate::types::HighlightMode::None {
+                        state_enter.hovered_edge_id.set(Some(edge_enter.id.clone()));
+                    }
                     if let Some(handler) = &on_edge_mouse_enter {
                         handler.call(crate::types::EdgeMouseEvent {
                             edge: edge_enter.clone(),
@@ -421,13 +489,44 @@ pub fn EdgeRenderer<
                     }
                 };
                 let edge_leave = edge.clone();
+                let mut state_leave = state.clone();
                 let on_mouse_leave = move |_| {
+                    if state_leave.hovered_edge_id.peek().as_ref() == Some(&edge_leave.id) {
+                        state_leave.hovered_edge_id.set(None);
+                    }
                     if let Some(handler) = &on_edge_mouse_leave {
                         handler.call(crate::types::EdgeMouseEvent {
                             edge: edge_leave.clone(),
                         });
                     }
                 };
+                let edge_context = edge.clone();
+                let state_context = state.clone();
+                let on_context_menu = move |evt: MouseEvent| {
+                    let coords = evt.data.client_coordinates();
+                    let screen_position = XYPosition::new(coords.x, coords.y);
+                    // Right-clicking part of a multi-selection opens the selection menu.
+                    if edge_context.selected {
+                        let handler = *state_context.on_selection_context_menu.read();
+                        let event = state_context.selection_context_menu_event(screen_position);
+                        if let (Some(handler), Some(event)) = (handler, event) {
+                            evt.prevent_default();
+                            evt.stop_propagation();
+                            handler.call(event);
+                            return;
+                        }
+                    }
+                    let Some(handler) = *state_context.on_edge_context_menu.read() else {
+                        return;
+                    };
+                    evt.prevent_default();
+                    evt.stop_propagation();
+                    handler.call(crate::types::EdgeContextMenuEvent {
+                        edge: edge_context.clone(),
+                        screen_position,
+                        flow_position: state_context.screen_to_flow_position(screen_position),
+                    });
+                };
 
                 rsx! {
                     g {
@@ -435,11 +534,13 @@ pub fn EdgeRenderer<
                         role: if edge_selectable { "button" } else { "group" },
                         tabindex: "{edge_tab_index}",
                         "aria-label": "{edge_aria_label}",
+                        "aria-describedby": "{state.edge_description_id()}",
                         "data-edge-id": "{edge_id}",
                         onclick: on_click,
                         ondoubleclick: on_double_click,
                         onmouseenter: on_mouse_enter,
                         onmouseleave: on_mouse_leave,
+                        oncontextmenu: on_context_menu,
                         onfocus: {
                             let mut state_focus = state.clone();
                             let edge_id = edge_id_for_focus.clone();
@@ -457,6 +558,9 @@ pub fn EdgeRenderer<
                                 }
                             }
                         },
+                        if let Some(diagnostic_title) = diagnostic_title {
+                            title { "{diagnostic_title}" }
+                        }
                         path {
                             class: "{class}",
                             style: "{style}",
@@ -464,6 +568,32 @@ pub fn EdgeRenderer<
                             marker_start: "{marker_start_attr}",
                             marker_end: "{marker_end_attr}",
                         }
+                        for particle in motion.particles {
+                            circle {
+                                class: "dioxus-flow__edge-particle",
+                                r: "{particle.radius}",
+                                fill: "{particle.color}",
+                                animateMotion {
+                                    "path": "{path_result.path}",
+                                    "dur": "{particle.duration}s",
+                                    "begin": "{particle.begin}s",
+                                    "repeatCount": "indefinite",
+                                    "calcMode": "linear",
+                                    "keyPoints": "{particle.key_points}",
+                                    "keyTimes": "0;1",
+                                }
+                            }
+                        }
+                        for pulse in motion.pulses {
+                            circle {
+                                key: "pulse-{pulse.id}",
+                                class: "dioxus-flow__edge-pulse",
+                                cx: "{pulse.x}",
+                                cy: "{pulse.y}",
+                                r: "{pulse.radius}",
+                                fill: "{pulse.color}",
+                            }
+                        }
                         path {
                             class: "dioxus-flow__edge-interaction",
                             d: "{path_result.path}",
@@ -488,29 +618,111 @@ pub fn EdgeRenderer<
                                 onpointerdown: on_reconnect_target,
                             }
                         }
-                        if let Some(text) = label {
+                        if show_waypoints {
+                            for (index, point) in insert_points {
+                                circle {
+                                    key: "insert-{index}",
+                                    class: "dioxus-flow__edge-waypoint-insert",
+                                    cx: "{point.x}",
+                                    cy: "{point.y}",
+                                    r: "4",
+                                    onpointerdown: {
+                                        let mut state_insert = state.clone();
+                                        let edge_id = edge_id.clone();
+                                        let points = edge_points.clone();
+                                        move |evt: PointerEvent| {
+                                            if evt.data.trigger_button() != Some(MouseButton::Primary) {
+                                                return;
+                                            }
+                                            evt.stop_propagation();
+                                            let mut points = points.clone();
+                                            points.insert(index, point);
+                                            apply_edge_changes(
+                                                &mut state_insert,
+                                                &on_edges_change,
+                                                vec![crate::types::EdgeChange::Points {
+                                                    id: edge_id.clone(),
+                                                    points,
+                                                }],
+                                            );
+                                            state_insert.waypoint_drag.set(Some(WaypointDragState {
+                                                edge_id: edge_id.clone(),
+                                                index,
+                                            }));
+                                        }
+                                    },
+                                }
+                            }
+                            for (index, point) in waypoints {
+                                circle {
+                                    key: "waypoint-{index}",
+                                    class: "dioxus-flow__edge-waypoint",
+                                    cx: "{point.x}",
+                                    cy: "{point.y}",
+                                    r: "5",
+                                    onpointerdown: {
+                                        let mut state_drag = state.clone();
+                                        let edge_id = edge_id.clone();
+                                        move |evt: PointerEvent| {
+                                            if evt.data.trigger_button() != Some(MouseButton::Primary) {
+                                                return;
+                                            }
+                                            evt.stop_propagation();
+                                            state_drag.waypoint_drag.set(Some(WaypointDragState {
+                                                edge_id: edge_id.clone(),
+                                                index,
+                                            }));
+                                        }
+                                    },
+                                    ondoubleclick: {
+                                        let mut state_remove = state.clone();
+                                        let edge_id = edge_id.clone();
+                                        let points = edge_points.clone();
+                                        move |evt: MouseEvent| {
+                                            evt.stop_propagation();
+                                            if index >= points.len() {
+                                                return;
+                                            }
+                                            let mut points = points.clone();
+                                            points.remove(index);
+                                            apply_edge_changes(
+                                                &mut state_remove,
+                                                &on_edges_change,
+                                                vec![crate::types::EdgeChange::Points {
+                                                    id: edge_id.clone(),
+                                                    points,
+                                                }],
+                                            );
+                                        }
+                                    },
+                                }
+                            }
+                        }
+                        for (position, label_class, text_style) in edge_labels {
                             g {
-                                class: "dioxus-flow__edge-label",
+                                key: "label-{position.index.map(|index| index as i64).unwrap_or(-1)}",
+                                class: "dioxus-flow__edge-label {label_class}",
+                                transform: "rotate({position.angle} {position.x} {position.y})",
                                 if show_label_bg {
                                     rect {
-                                        x: "{label_x - bg_width / 2.0}",
-                                        y: "{label_y - bg_height / 2.0}",
+                                        x: "{position.x - position.width / 2.0}",
+                                        y: "{position.y - position.height / 2.0}",
                                         rx: "{label_radius}",
                                         ry: "{label_radius}",
-                                        width: "{bg_width}",
-                                        height: "{bg_height}",
+                                        width: "{position.width}",
+                                        height: "{position.height}",
                                         class: "dioxus-flow__edge-label-bg",
                                         style: "{label_bg_style}",
                                     }
                                 }
                                 text {
-                                    x: "{label_x}",
-                                    y: "{label_y}",
+                                    x: "{position.x}",
+                                    y: "{position.y}",
                                     text_anchor: "middle",
                                     dominant_baseline: "middle",
                                     class: "dioxus-flow__edge-label-text",
-                                    style: "{label_style}",
-                                    "{text}"
+                                    style: "{text_style}",
+                                    "{position.text}"
                                 }
                             }
                         }
@@ -528,7 +740,7 @@ pub fn EdgeRenderer<
             if !marker_defs.is_empty() {
                 defs {
                     for (id, marker) in marker_defs {
-                        EdgeMarkerDef { id, marker }
+                        EdgeMarkerDef { id, marker, marker_types: marker_types.clone() }
                     }
                 }
             }
@@ -548,17 +760,184 @@ struct EdgeRender<E: Clone + PartialEq + Default> {
     target_y: f64,
     source_pos: Position,
     target_pos: Position,
+    parallel_offset: f64,
 }
 
-fn edge_path_for_type<E: Clone + PartialEq + Default>(
+/// Paths of the rendered edges in flow coordinates, as the edge renderer draws them
+pub(crate) fn edge_path_results<N, E>(state: &FlowState<N, E>) -> Vec<(Edge<E>, EdgePathResult)>
+where
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+{
+    let edges = state.edges.read();
+    let nodes = state.node_lookup.read();
+    let floating_edges = *state.floating_edges.read();
+    let offsets = get_parallel_edge_offsets(&edges, *state.parallel_edge_spacing.read());
+    edges
+        .iter()
+        .filter(|edge| !edge.hidden)
+        .filter_map(|edge| {
+            let parallel_offset = offsets.get(&edge.id).copied().unwrap_or(0.0);
+            let mut item = edge_render_item(edge, &nodes, parallel_offset, floating_edges)?;
+            apply_marker_insets(&mut item);
+            let path = edge_path_for_type(&item);
+            Some((item.edge, path))
+        })
+        .collect()
+}
+
+fn edge_render_item<N: Clone + PartialEq + Default, E: Clone + PartialEq + Default>(
     edge: &Edge<E>,
-    source_x: f64,
-    source_y: f64,
-    target_x: f64,
-    target_y: f64,
-    source_position: Position,
-    target_position: Position,
+    nodes: &HashMap<String, crate::types::InternalNode<N>>,
+    parallel_offset: f64,
+    floating_edges: bool,
+) -> Option<EdgeRender<E>> {
+    let source_node = nodes.get(&edge.source)?;
+    let target_node = nodes.get(&edge.target)?;
+
+    if edge.source == edge.target && edge.points.is_empty() {
+        let position = edge.loop_position.unwrap_or(Position::Top);
+        let (source, target) = self_loop_anchors(source_node, position, parallel_offset);
+        return Some(EdgeRender {
+            edge: edge.clone(),
+            source_x: source.x,
+            source_y: source.y,
+            target_x: target.x,
+            target_y: target.y,
+            source_pos: position,
+            target_pos: position,
+            parallel_offset,
+        });
+    }
+
+    if edge.floating.unwrap_or(floating_edges) && edge.source != edge.target {
+        let source_rect = Rect::from_position_and_dimensions(
+            source_node.position_absolute,
+            source_node.dimensions,
+        );
+        let target_rect = Rect::from_position_and_dimensions(
+            target_node.position_absolute,
+            target_node.dimensions,
+        );
+        // With waypoints, aim each end at its nearest waypoint instead.
+        let (source, source_pos, target, target_pos) =
+            match (edge.points.first(), edge.points.last()) {
+                (Some(first), Some(last)) => {
+                    let (source, source_pos) = get_rect_intersection(&source_rect, *first);
+                    let (target, target_pos) = get_rect_intersection(&target_rect, *last);
+                    (source, source_pos, target, target_pos)
+                }
+                _ => {
+                    let params = get_floating_edge_params(&source_rect, &target_rect);
+                    (
+                        XYPosition::new(params.source_x, params.source_y),
+                        params.source_position,
+                        XYPosition::new(params.target_x, params.target_y),
+                        params.target_position,
+                    )
+                }
+            };
+        return Some(EdgeRender {
+            edge: edge.clone(),
+            source_x: source.x,
+            source_y: source.y,
+            target_x: target.x,
+            target_y: target.y,
+            source_pos,
+            target_pos,
+            parallel_offset,
+        });
+    }
+
+    let source_pos = source_node.node.source_position.unwrap_or(Position::Right);
+    let target_pos = target_node.node.target_position.unwrap_or(Position::Left);
+
+    let (source_x, source_y) = handle_position_for_edge(
+        source_node,
+        HandleType::Source,
+        edge.source_handle.as_deref(),
+        source_pos,
+    );
+    let (target_x, target_y) = handle_position_for_edge(
+        target_node,
+        HandleType::Target,
+        edge.target_handle.as_deref(),
+        target_pos,
+    );
+
+    Some(EdgeRender {
+        edge: edge.clone(),
+        source_x,
+        source_y,
+        target_x,
+        target_y,
+        source_pos,
+        target_pos,
+        parallel_offset,
+    })
+}
+
+fn edge_path_for_type<E: Clone + PartialEq + Default>(
+    item: &EdgeRender<E>,
 ) -> crate::types::EdgePathResult {
+    let edge = &item.edge;
+    let (mut source_x, mut source_y) = (item.source_x, item.source_y);
+    let (mut target_x, mut target_y) = (item.target_x, item.target_y);
+    let source_position = item.source_pos;
+    let target_position = item.target_pos;
+    let offset = item.parallel_offset;
+
+    if !edge.points.is_empty() {
+        let route = waypoint_route(edge, source_x, source_y, target_x, target_y);
+        return match edge.resolved_waypoint_style() {
+            WaypointStyle::Polyline => get_polyline_path(&route),
+            WaypointStyle::Orthogonal => {
+                let border_radius = (edge.edge_type.as_deref() == Some("step")).then_some(0.0);
+                get_orthogonal_path(&route, source_position, border_radius)
+            }
+            WaypointStyle::CatmullRom => get_catmull_rom_path(&route, None),
+        };
+    }
+
+    if edge.source == edge.target {
+        return get_self_loop_path(
+            source_x,
+            source_y,
+            target_x,
+            target_y,
+            source_position,
+            offset,
+        );
+    }
+
+    if offset != 0.0 {
+        match edge.edge_type.as_deref() {
+            Some("straight") => {
+                return get_offset_straight_path(source_x, source_y, target_x, target_y, offset);
+            }
+            Some("step") | Some("smoothstep") => {
+                // Keep step edges axis-aligned by sliding both ends along their node side.
+                let normal_x = -(target_y - source_y);
+                let normal_y = target_x - source_x;
+                (source_x, source_y) =
+                    shift_along_side(source_x, source_y, source_position, normal_x, normal_y, offset);
+                (target_x, target_y) =
+                    shift_along_side(target_x, target_y, target_position, normal_x, normal_y, offset);
+            }
+            _ => {
+                return get_offset_bezier_path(
+                    source_x,
+                    source_y,
+                    target_x,
+                    target_y,
+                    source_position,
+                    target_position,
+                    offset,
+                );
+            }
+        }
+    }
+
     match edge.edge_type.as_deref() {
         Some("straight") => get_straight_path(source_x, source_y, target_x, target_y),
         Some("step") => get_step_path(
@@ -601,6 +980,151 @@ fn edge_path_for_type<E: Clone + PartialEq + Default>(
     }
 }
 
+#[derive(Clone, PartialEq, Default)]
+struct EdgeMotion {
+    particles: Vec<EdgeParticle>,
+    pulses: Vec<PulseMark>,
+}
+
+#[derive(Clone, PartialEq)]
+struct EdgeParticle {
+    radius: f64,
+    color: String,
+    duration: f64,
+    begin: f64,
+    key_points: &'static str,
+}
+
+#[derive(Clone, PartialEq)]
+struct PulseMark {
+    id: u64,
+    x: f64,
+    y: f64,
+    radius: f64,
+    color: String,
+}
+
+/// Travelling dots and pulse tokens for an edge path
+fn edge_motion(
+    geometry: &EdgeGeometry,
+    animation: Option<&EdgeAnimation>,
+    pulses: &[EdgePulse],
+) -> EdgeMotion {
+    let particle_count = animation.map(|animation| animation.particles).unwrap_or(0);
+    if particle_count == 0 && pulses.is_empty() {
+        return EdgeMotion::default();
+    }
+    let mut motion = EdgeMotion::default();
+
+    if let Some(animation) = animation.filter(|_| particle_count > 0) {
+        let duration = (geometry.length() / animation.speed.max(f64::EPSILON)).max(0.1);
+        let key_points = match animation.direction {
+            AnimationDirection::Forward => "0;1",
+            AnimationDirection::Reverse => "1;0",
+        };
+        let color = animation
+            .particle_color
+            .clone()
+            .unwrap_or_else(|| "var(--df-edge-color-selected)".to_string());
+        motion.particles = (0..particle_count)
+            .map(|index| EdgeParticle {
+                radius: animation.particle_radius,
+                color: color.clone(),
+                duration,
+                // Negative begin offsets spread the dots evenly along the path.
+                begin: -(duration * f64::from(index) / f64::from(particle_count)),
+                key_points,
+            })
+            .collect();
+    }
+
+    motion.pulses = pulses
+        .iter()
+        .filter_map(|pulse| {
+            let t = match pulse.options.direction {
+                AnimationDirection::Forward => pulse.progress,
+                AnimationDirection::Reverse => 1.0 - pulse.progress,
+            };
+            let point = geometry.point_at(t)?;
+            Some(PulseMark {
+                id: pulse.id,
+                x: point.x,
+                y: point.y,
+                radius: pulse.options.radius,
+                color: pulse
+                    .options
+                    .color
+                    .clone()
+                    .unwrap_or_else(|| "var(--df-edge-color-selected)".to_string()),
+            })
+        })
+        .collect();
+    motion
+}
+
+fn shift_along_side(
+    x: f64,
+    y: f64,
+    position: Position,
+    normal_x: f64,
+    normal_y: f64,
+    offset: f64,
+) -> (f64, f64) {
+    if position.is_horizontal() {
+        (x, y + offset * normal_y.signum())
+    } else {
+        (x + offset * normal_x.signum(), y)
+    }
+}
+
+/// Start and end points of a self-loop on `position` side of a node.
+fn self_loop_anchors<N: Clone + PartialEq + Default>(
+    node: &crate::types::InternalNode<N>,
+    position: Position,
+    offset: f64,
+) -> (XYPosition, XYPosition) {
+    let rect = Rect::from_position_and_dimensions(node.position_absolute, node.dimensions);
+    let center = rect.center();
+    let side_length = if position.is_horizontal() {
+        rect.height
+    } else {
+        rect.width
+    };
+    let spread = (side_length / 4.0).min(12.0 + offset / 4.0);
+    match position {
+        Position::Top => (
+            XYPosition::new(center.x - spread, rect.y),
+            XYPosition::new(center.x + spread, rect.y),
+        ),
+        Position::Right => (
+            XYPosition::new(rect.x + rect.width, center.y - spread),
+            XYPosition::new(rect.x + rect.width, center.y + spread),
+        ),
+        Position::Bottom => (
+            XYPosition::new(center.x + spread, rect.y + rect.height),
+            XYPosition::new(center.x - spread, rect.y + rect.height),
+        ),
+        Position::Left => (
+            XYPosition::new(rect.x, center.y + spread),
+            XYPosition::new(rect.x, center.y - spread),
+        ),
+    }
+}
+
+fn waypoint_route<E: Clone + PartialEq + Default>(
+    edge: &Edge<E>,
+    source_x: f64,
+    source_y: f64,
+    target_x: f64,
+    target_y: f64,
+) -> Vec<XYPosition> {
+    let mut route = Vec::with_capacity(edge.points.len() + 2);
+    route.push(XYPosition::new(source_x, source_y));
+    route.extend(edge.points.iter().copied());
+    route.push(XYPosition::new(target_x, target_y));
+    route
+}
+
 fn node_handle_position_internal<N: Clone + PartialEq + Default>(
     node: &crate::types::InternalNode<N>,
     position: Position,
@@ -671,15 +1195,97 @@ fn select_handle<'a>(
 }
 
 fn marker_key(marker: &EdgeMarker) -> String {
-    let marker_type = match marker.marker_type {
-        MarkerType::Arrow => "arrow",
-        MarkerType::ArrowClosed => "arrow-closed",
-    };
+    let marker_type = marker.marker_type.key();
     let color = marker.color.clone().unwrap_or_default();
     let width = marker.width.unwrap_or(0.0);
     let height = marker.height.unwrap_or(0.0);
     let stroke_width = marker.stroke_width.unwrap_or(0.0);
-    format!("{marker_type}:{color}:{width}:{height}:{stroke_width}")
+    let units = marker.marker_units.clone().unwrap_or_default();
+    let orient = marker.orient.clone().unwrap_or_default();
+    let custom_type = match &marker.marker_type {
+        MarkerType::Custom(name) => name.as_str(),
+        _ => "",
+    };
+    format!(
+        "{marker_type}:{custom_type}:{color}:{width}:{height}:{stroke_width}:{units}:{orient}"
+    )
+}
+
+/// Marker as drawn at one end of an edge, with the default color and the
+/// orientation for that end filled in. Built-in shapes point along +x, so a
+/// start marker is reversed to face its node.
+fn placed_marker(marker: &EdgeMarker, default_color: Option<&String>, at_start: bool) -> EdgeMarker {
+    let mut marker = marker.clone();
+    if marker.color.is_none() {
+        marker.color = default_color.cloned();
+    }
+    if marker.orient.is_none() {
+        let orient = if at_start { "auto-start-reverse" } else { "auto" };
+        marker.orient = Some(orient.to_string());
+    }
+    marker
+}
+
+/// Pulls the line ends back so filled markers sit in front of the line
+/// instead of having it run through them.
+fn apply_marker_insets<E: Clone + PartialEq + Default>(item: &mut EdgeRender<E>) {
+    let start_inset = item
+        .edge
+        .marker_start
+        .as_ref()
+        .map(EdgeMarker::line_inset)
+        .unwrap_or(0.0);
+    let end_inset = item
+        .edge
+        .marker_end
+        .as_ref()
+        .map(EdgeMarker::line_inset)
+        .unwrap_or(0.0);
+    if start_inset == 0.0 && end_inset == 0.0 {
+        return;
+    }
+
+    let source = XYPosition::new(item.source_x, item.source_y);
+    let target = XYPosition::new(item.target_x, item.target_y);
+    // Straight and waypoint paths leave their ends along the next segment,
+    // everything else leaves perpendicular to the handle side.
+    let along_segment =
+        item.edge.edge_type.as_deref() == Some("straight") || !item.edge.points.is_empty();
+    let (source_dir, target_dir) = if along_segment {
+        let after_source = item.edge.points.first().copied().unwrap_or(target);
+        let before_target = item.edge.points.last().copied().unwrap_or(source);
+        (
+            unit_vector(after_source - source),
+            unit_vector(before_target - target),
+        )
+    } else {
+        (side_normal(item.source_pos), side_normal(item.target_pos))
+    };
+
+    let source = source + source_dir * start_inset;
+    let target = target + target_dir * end_inset;
+    item.source_x = source.x;
+    item.source_y = source.y;
+    item.target_x = target.x;
+    item.target_y = target.y;
+}
+
+fn unit_vector(vector: XYPosition) -> XYPosition {
+    let length = vector.x.hypot(vector.y);
+    if length == 0.0 {
+        XYPosition::new(0.0, 0.0)
+    } else {
+        vector * (1.0 / length)
+    }
+}
+
+fn side_normal(position: Position) -> XYPosition {
+    match position {
+        Position::Top => XYPosition::new(0.0, -1.0),
+        Position::Right => XYPosition::new(1.0, 0.0),
+        Position::Bottom => XYPosition::new(0.0, 1.0),
+        Position::Left => XYPosition::new(-1.0, 0.0),
+    }
 }
 
 fn resolve_marker_id(
@@ -739,33 +1345,134 @@ fn apply_edge_changes<
 }
 
 #[component]
-fn EdgeMarkerDef(id: String, marker: EdgeMarker) -> Element {
-    let (path, view_box) = match marker.marker_type {
-        MarkerType::Arrow => ("M0,0 L10,5 L0,10", "0 0 10 10"),
-        MarkerType::ArrowClosed => ("M0,0 L10,5 L0,10 z", "0 0 10 10"),
-    };
+fn EdgeMarkerDef(
+    id: String,
+    marker: EdgeMarker,
+    marker_types: Option<HashMap<String, Component<MarkerComponentProps>>>,
+) -> Element {
     let color = marker
         .color
+        .clone()
         .unwrap_or_else(|| "var(--df-edge-color)".to_string());
-    let width = marker.width.unwrap_or(12.0);
-    let height = marker.height.unwrap_or(12.0);
+
+    let Some(shape) = marker.marker_type.shape() else {
+        let MarkerType::Custom(name) = &marker.marker_type else {
+            return rsx! {};
+        };
+        let Some(component) = marker_types.as_ref().and_then(|map| map.get(name)).copied() else {
+            return rsx! {};
+        };
+        return component(MarkerComponentProps { id, marker, color });
+    };
+
+    let (width, height) = marker.size();
     let stroke_width = marker.stroke_width.unwrap_or(1.0);
+    let orient = marker
+        .orient
+        .clone()
+        .unwrap_or_else(|| "auto".to_string());
+    let marker_units = marker
+        .marker_units
+        .clone()
+        .unwrap_or_else(|| "strokeWidth".to_string());
+    let fill = if shape.filled { color.as_str() } else { "none" };
     rsx! {
         marker {
             id: "{id}",
+            class: "dioxus-flow__marker",
             marker_width: "{width}",
             marker_height: "{height}",
-            ref_x: "10",
+            ref_x: "{shape.ref_x}",
             ref_y: "5",
-            orient: "auto",
-            marker_units: "strokeWidth",
-            view_box: "{view_box}",
+            orient: "{orient}",
+            marker_units: "{marker_units}",
+            view_box: "0 0 10 10",
             path {
-                d: "{path}",
-                fill: "{color}",
+                d: "{shape.path}",
+                fill: "{fill}",
                 stroke: "{color}",
                 stroke_width: "{stroke_width}",
+                stroke_linecap: "round",
+                stroke_linejoin: "round",
+            }
+            if let Some((cx, r)) = shape.ring {
+                circle {
+                    cx: "{cx}",
+                    cy: "5",
+                    r: "{r}",
+                    fill: "var(--df-background-color)",
+                    stroke: "{color}",
+                    stroke_width: "{stroke_width}",
+                }
             }
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn built_in_markers() -> Vec<EdgeMarker> {
+        vec![
+            EdgeMarker::arrow(),
+            EdgeMarker::arrow_closed(),
+            EdgeMarker::circle(),
+            EdgeMarker::diamond(),
+            EdgeMarker::bar(),
+            EdgeMarker::crows_foot_one(),
+            EdgeMarker::crows_foot_many(),
+            EdgeMarker::crows_foot_zero_or_one(),
+            EdgeMarker::crows_foot_zero_or_many(),
+        ]
+    }
+
+    fn render(edge: Edge<()>, source_pos: Position, target_pos: Position) -> EdgeRender<()> {
+        EdgeRender {
+            edge,
+            source_x: 0.0,
+            source_y: 0.0,
+            target_x: 100.0,
+            target_y: 0.0,
+            source_pos,
+            target_pos,
+            parallel_offset: 0.0,
+        }
+    }
+
+    #[test]
+    fn start_markers_are_reversed_to_face_their_node() {
+        for marker in built_in_markers() {
+            let start = placed_marker(&marker, None, true);
+            let end = placed_marker(&marker, None, false);
+            assert_eq!(start.orient.as_deref(), Some("auto-start-reverse"));
+            assert_eq!(end.orient.as_deref(), Some("auto"));
+            assert_ne!(marker_key(&start), marker_key(&end));
+        }
+
+        let fixed = EdgeMarker::arrow().with_orient("90");
+        assert_eq!(placed_marker(&fixed, None, true).orient.as_deref(), Some("90"));
+    }
+
+    #[test]
+    fn start_and_end_insets_are_symmetric() {
+        for marker in built_in_markers() {
+            let mut edge = Edge::new("e", "a", "b").with_type("straight");
+            edge.marker_start = Some(marker.clone());
+            edge.marker_end = Some(marker.clone());
+            let mut item = render(edge, Position::Right, Position::Left);
+            apply_marker_insets(&mut item);
+            let inset = marker.line_inset();
+            assert!((item.source_x - inset).abs() < 1e-9);
+            assert!((item.target_x - (100.0 - inset)).abs() < 1e-9);
+
+            let mut edge = Edge::new("e", "a", "b");
+            edge.marker_start = Some(marker.clone());
+            edge.marker_end = Some(marker.clone());
+            let mut item = render(edge, Position::Right, Position::Left);
+            apply_marker_insets(&mut item);
+            assert!((item.source_x - inset).abs() < 1e-9);
+            assert!((item.target_x - (100.0 - inset)).abs() < 1e-9);
+        }
+    }
+}
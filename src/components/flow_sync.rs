@@ -0,0 +1,243 @@
+//! Collaborative editing component
+
+use crate::state::FlowState;
+use crate::sync::{PeerPresence, SharedTransport, SyncEngine, SyncMessage};
+use crate::types::{EdgeChange, NodeChange, XYPosition};
+use crate::utils::browser::WindowListener;
+use dioxus::prelude::*;
+use dioxus::prelude::{ReadableExt, WritableExt};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Minimum time between cursor presence updates
+const CURSOR_THROTTLE_MS: f64 = 50.0;
+
+/// Syncs the flow with other peers over `transport` and shows their cursors and
+/// selections. Place it inside `DioxusFlow`. Local edits are detected from the
+/// flow's nodes and edges, so every way of changing them is synced.
+#[component]
+pub fn FlowSync<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    transport: SharedTransport<N, E>,
+    /// Unique id of this peer
+    peer_id: String,
+    #[props(default)] name: Option<String>,
+    /// CSS color other peers see this peer in
+    #[props(default)]
+    color: Option<String>,
+    #[props(default = true)] show_presence: bool,
+    /// Receives remote node changes instead of applying them to the flow
+    #[props(default)]
+    on_remote_nodes_change: Option<EventHandler<Vec<NodeChange<N>>>>,
+    #[props(default)] on_remote_edges_change: Option<EventHandler<Vec<EdgeChange<E>>>>,
+) -> Element {
+    let state = use_context::<FlowState<N, E>>();
+    let engine = use_hook(|| Rc::new(RefCell::new(SyncEngine::<N, E>::new(peer_id.clone()))));
+    let peers = use_signal(HashMap::<String, PeerPresence>::new);
+    let mut cursor = use_signal(|| None::<XYPosition>);
+
+    let identity = PeerPresence {
+        peer: peer_id.clone(),
+        name: name.clone().unwrap_or_else(|| peer_id.clone()),
+        color: color.clone().unwrap_or_else(|| "#ff0072".to_string()),
+        ..Default::default()
+    };
+    let mut local_identity = use_signal(|| identity.clone());
+    if *local_identity.peek() != identity {
+        local_identity.set(identity);
+    }
+    // Last presence sent, replayed to peers that join later
+    let mut sent_presence = use_signal(|| None::<PeerPresence>);
+
+    let transport_receive = transport.clone();
+    let engine_receive = engine.clone();
+    let state_receive = state.clone();
+    use_hook(move || {
+        let transport = transport_receive.clone();
+        let reply = transport_receive.clone();
+        transport.0.set_receiver(Rc::new(move |message| {
+            let mut state = state_receive.clone();
+            let mut peers = peers;
+            match message {
+                SyncMessage::Operations(operations) => {
+                    // Send pending local edits first so they are not overwritten
+                    let (local, (node_changes, edge_changes)) = {
+                        let nodes = state.nodes.peek().clone();
+                        let edges = state.edges.peek().clone();
+                        let mut engine = engine_receive.borrow_mut();
+                        let local = engine.local_operations(&nodes, &edges);
+                        (local, engine.apply_remote(operations, &nodes, &edges))
+                    };
+                    if !local.is_empty() {
+                        reply.0.send(SyncMessage::Operations(local));
+                    }
+                    if !node_changes.is_empty() {
+                        if let Some(handler) = on_remote_nodes_change {
+                            handler.call(node_changes);
+                        } else {
+                            state.apply_node_changes(node_changes);
+                        }
+                    }
+                    if !edge_changes.is_empty() {
+                        if let Some(handler) = on_remote_edges_change {
+                            handler.call(edge_changes);
+                        } else {
+                            state.apply_edge_changes(edge_changes);
+                        }
+                    }
+                }
+                SyncMessage::Presence(presence) => {
+                    let joined = !peers.peek().contains_key(&presence.peer);
+                    peers.write().insert(presence.peer.clone(), presence);
+                    if joined {
+                        let snapshot = engine_receive.borrow().snapshot();
+                        reply.0.send(SyncMessage::Operations(snapshot));
+                        let presence = sent_presence
+                            .peek()
+                            .clone()
+                            .unwrap_or_else(|| local_identity.peek().clone());
+                        reply.0.send(SyncMessage::Presence(presence));
+                    }
+                }
+                SyncMessage::Leave { peer } => {
+                    peers.write().remove(&peer);
+                }
+            }
+        }));
+    });
+
+    let transport_local = transport.clone();
+    let engine_local = engine.clone();
+    let state_local = state.clone();
+    use_effect(move || {
+        let nodes = state_local.nodes.read();
+        let edges = state_local.edges.read();
+        let operations = engine_local.borrow_mut().local_operations(&nodes, &edges);
+        drop(nodes);
+        drop(edges);
+        if !operations.is_empty() {
+            transport_local.0.send(SyncMessage::Operations(operations));
+        }
+    });
+
+    let transport_presence = transport.clone();
+    let state_presence = state.clone();
+    use_effect(move || {
+        let selected_nodes: Vec<String> = state_presence
+            .nodes
+            .read()
+            .iter()
+            .filter(|node| node.selected)
+            .map(|node| node.id.clone())
+            .collect();
+        let selected_edges: Vec<String> = state_presence
+            .edges
+            .read()
+            .iter()
+            .filter(|edge| edge.selected)
+            .map(|edge| edge.id.clone())
+            .collect();
+        let presence = PeerPresence {
+            cursor: *cursor.read(),
+            selected_nodes,
+            selected_edges,
+            ..local_identity.read().clone()
+        };
+        if sent_presence.peek().as_ref() != Some(&presence) {
+            sent_presence.set(Some(presence.clone()));
+            transport_presence.0.send(SyncMessage::Presence(presence));
+        }
+    });
+
+    let state_pointer = state.clone();
+    let _pointer_listener = use_hook(move || {
+        let mut last_sent = 0.0;
+        Rc::new(WindowListener::new(
+            "pointermove",
+            move |evt: web_sys::PointerEvent| {
+                let now = crate::utils::browser::now();
+                if now - last_sent < CURSOR_THROTTLE_MS {
+                    return;
+                }
+                last_sent = now;
+                let screen = XYPosition::new(evt.client_x() as f64, evt.client_y() as f64);
+                cursor.set(Some(state_pointer.screen_to_flow_position(screen)));
+            },
+        ))
+    });
+
+    let transport_leave = transport.clone();
+    let peer_leave = peer_id.clone();
+    use_drop(move || {
+        transport_leave
+            .0
+            .send(SyncMessage::Leave { peer: peer_leave });
+    });
+
+    if !show_presence {
+        return rsx! {};
+    }
+
+    let zoom = state.viewport.read().zoom;
+    let mut remote: Vec<PeerPresence> = peers.read().values().cloned().collect();
+    remote.sort_by(|a, b| a.peer.cmp(&b.peer));
+    let node_lookup = state.node_lookup.read();
+    let selections: Vec<(String, String)> = remote
+        .iter()
+        .flat_map(|peer| {
+            peer.selected_nodes
+                .iter()
+                .filter_map(|id| node_lookup.get(id))
+                .filter(|internal| !internal.node.hidden)
+                .map(|internal| {
+                    let origin = state.flow_to_screen_position(internal.position_absolute);
+                    let style = format!(
+                        "transform: translate({}px, {}px); width: {}px; height: {}px; border-color: {};",
+                        origin.x,
+                        origin.y,
+                        internal.dimensions.width * zoom,
+                        internal.dimensions.height * zoom,
+                        peer.color
+                    );
+                    (format!("{}-{}", peer.peer, internal.node.id), style)
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+    drop(node_lookup);
+    let cursors: Vec<(String, String, String, String)> = remote
+        .iter()
+        .filter_map(|peer| {
+            let position = state.flow_to_screen_position(peer.cursor?);
+            Some((
+                peer.peer.clone(),
+                format!("transform: translate({}px, {}px);", position.x, position.y),
+                peer.color.clone(),
+                peer.name.clone(),
+            ))
+        })
+        .collect();
+
+    rsx! {
+        div { class: "dioxus-flow__remote-presence",
+            for (key, style) in selections {
+                div { key: "{key}", class: "dioxus-flow__remote-selection", style: "{style}" }
+            }
+            for (peer, style, color, name) in cursors {
+                div { key: "{peer}", class: "dioxus-flow__remote-cursor", style: "{style}",
+                    svg { width: "16", height: "16", view_box: "0 0 16 16",
+                        path { d: "M0 0 L0 12 L4 9 L7 15 L9 14 L6 8 L11 8 Z", fill: "{color}" }
+                    }
+                    span {
+                        class: "dioxus-flow__remote-cursor-label",
+                        style: "background-color: {color};",
+                        "{name}"
+                    }
+                }
+            }
+        }
+    }
+}
@@ -1,10 +1,13 @@
 //! Graph view component
 
-use crate::components::{EdgeRenderer, NodeRenderer, PanZoomPane, SelectionListener};
+use crate::components::{
+    EdgeRenderer, NodeRenderer, NodesSelection, PanZoomPane, SelectionListener,
+};
 use crate::state::FlowState;
-use crate::types::{HandleBound, HandleBounds, HandleType, Position};
+use crate::types::{HandleBound, HandleBounds, HandleType, Position, Rect, XYPosition};
 use crate::utils::{
-    get_bezier_path, get_simple_bezier_path, get_smooth_step_path, get_step_path, get_straight_path,
+    get_bezier_path, get_floating_edge_params, get_rect_intersection, get_simple_bezier_path,
+    get_smooth_step_path, get_step_path, get_straight_path,
 };
 use dioxus::prelude::ReadableExt;
 use dioxus::prelude::*;
@@ -22,6 +25,9 @@ pub fn GraphView<
     #[props(default)] edge_types: Option<
         HashMap<String, Component<crate::components::EdgeComponentProps<E>>>,
     >,
+    #[props(default)] marker_types: Option<
+        HashMap<String, Component<crate::components::MarkerComponentProps>>,
+    >,
     #[props(default)] on_nodes_change: Option<EventHandler<Vec<crate::types::NodeChange<N>>>>,
     #[props(default)] on_edges_change: Option<EventHandler<Vec<crate::types::EdgeChange<E>>>>,
     #[props(default)] on_connect: Option<EventHandler<crate::types::Connection>>,
@@ -49,6 +55,7 @@ pub fn GraphView<
     #[props(default)] on_edge_double_click: Option<EventHandler<crate::types::EdgeMouseEvent<E>>>,
     #[props(default)] on_edge_mouse_enter: Option<EventHandler<crate::types::EdgeMouseEvent<E>>>,
     #[props(default)] on_edge_mouse_leave: Option<EventHandler<crate::types::EdgeMouseEvent<E>>>,
+    #[props(default)] nodes_selection_toolbar: Option<Element>,
 ) -> Element {
     let state = use_context::<FlowState<N, E>>();
 
@@ -75,7 +82,7 @@ pub fn GraphView<
             return;
         }
         last_handle_bounds_zoom.set(zoom);
-        let Some(window) = web_sys::window() else {
+        let Some(window) = crate::utils::browser::window() else {
             return;
         };
         let Some(document) = window.document() else {
@@ -112,6 +119,18 @@ pub fn GraphView<
         )
     });
 
+    let lasso_path = state.user_selection_polygon.read().as_ref().map(|points| {
+        let mut path = String::new();
+        for (index, point) in points.iter().enumerate() {
+            let command = if index == 0 { 'M' } else { 'L' };
+            let x = point.x * viewport.zoom + viewport.x;
+            let y = point.y * viewport.zoom + viewport.y;
+            path.push_str(&format!("{command}{x},{y} "));
+        }
+        path.push('Z');
+        path
+    });
+
     rsx! {
         PanZoomPane::<N, E> {
             on_move,
@@ -135,6 +154,7 @@ pub fn GraphView<
 
                 EdgeRenderer::<N, E> {
                     edge_types,
+                    marker_types,
                     on_nodes_change,
                     on_edges_change,
                     on_connect,
@@ -156,11 +176,17 @@ pub fn GraphView<
                     on_node_mouse_enter,
                     on_node_mouse_leave,
                 }
+                NodesSelection::<N, E> { toolbar: nodes_selection_toolbar }
                 div { class: "dioxus-flow__viewport-portal" }
             }
 
             if state.user_selection_active.read().clone() {
-                if let Some(style) = selection_style {
+                if let Some(path) = lasso_path {
+                    svg {
+                        class: "dioxus-flow__selection-lasso",
+                        path { d: "{path}" }
+                    }
+                } else if let Some(style) = selection_style {
                     div {
                         class: "dioxus-flow__selection",
                         style: "{style}",
@@ -173,6 +199,40 @@ pub fn GraphView<
     }
 }
 
+/// Anchor the connection line to the source node boundary, and to the target
+/// node boundary while hovering a target.
+fn floating_connection_points<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    state: &FlowState<N, E>,
+    from_node: &crate::types::InternalNode<N>,
+    to_node_id: Option<&str>,
+    to: XYPosition,
+) -> (f64, f64, Position, XYPosition, Position) {
+    let from_rect =
+        Rect::from_position_and_dimensions(from_node.position_absolute, from_node.dimensions);
+    let to_rect = to_node_id
+        .filter(|id| *id != from_node.node.id)
+        .and_then(|id| {
+            state.node_lookup.read().get(id).map(|internal| {
+                Rect::from_position_and_dimensions(internal.position_absolute, internal.dimensions)
+            })
+        });
+    if let Some(to_rect) = to_rect {
+        let params = get_floating_edge_params(&from_rect, &to_rect);
+        return (
+            params.source_x,
+            params.source_y,
+            params.source_position,
+            XYPosition::new(params.target_x, params.target_y),
+            params.target_position,
+        );
+    }
+    let (from, from_pos) = get_rect_intersection(&from_rect, to);
+    (from.x, from.y, from_pos, to, from_pos.opposite())
+}
+
 fn connection_line_element<
     N: Clone + PartialEq + Default + 'static,
     E: Clone + PartialEq + Default + 'static,
@@ -223,6 +283,11 @@ fn connection_line_element<
     } else {
         from_pos
     };
+    let (from_x, from_y, from_pos, to, to_position) = if *state.floating_edges.read() {
+        floating_connection_points(state, &node, connection.to_node.as_deref(), to)
+    } else {
+        (from_x, from_y, from_pos, to, to_position)
+    };
     let is_valid = connection.is_valid;
     let status_class = if is_valid {
         "dioxus-flow__connection valid"
@@ -76,6 +76,20 @@ pub fn Handle<
         class.push_str(" connectionindicator");
     }
 
+    let diagnostic_title = {
+        let diagnostics = state.diagnostics.read();
+        let handle_diagnostics = diagnostics.for_handle(&node_id, id.as_deref(), handle_type);
+        if let Some(severity) = crate::types::max_severity(handle_diagnostics.iter().copied()) {
+            class.push_str(" diagnostic-");
+            class.push_str(severity.as_str());
+        }
+        let messages: Vec<&str> = handle_diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.message.as_str())
+            .collect();
+        (!messages.is_empty()).then(|| messages.join("\n"))
+    };
+
     let node_id_attr = node_id.clone();
     let handle_id_attr = id.clone();
     let node_id_down = node_id.clone();
@@ -216,22 +230,14 @@ pub fn Handle<
         connection.clear_target();
         state_leave.connection.set(connection);
     };
-
-    let mut state_up = state.clone();
-    let on_pointer_up = move |_evt: PointerEvent| {
-        let mut connection = state_up.connection.read().clone();
-        if let Some(_conn) = connection.end() {
-            state_up.connection.set(connection);
-            // emit connect event if provided via state? handled in PanZoomPane end
-        } else {
-            state_up.connection.set(connection);
-        }
-    };
+    // No pointerup handler: the release bubbles to the pane, which completes the connection
 
     let aria_label = match handle_type {
         HandleType::Source => "source handle",
         HandleType::Target => "target handle",
     };
+    // Reachable with Tab from its node (see `FlowCommand::FocusNext`), not in page order
+    let tab_index = if is_connectable { Some("-1") } else { None };
 
     rsx! {
         div {
@@ -240,10 +246,11 @@ pub fn Handle<
             "data-handle-id": "{handle_id_attr.clone().unwrap_or_default()}",
             "data-handle-pos": "{position:?}",
             "aria-label": "{aria_label}",
+            tabindex: tab_index,
+            title: diagnostic_title,
             onpointerdown: on_pointer_down,
             onpointerenter: on_pointer_enter,
             onpointerleave: on_pointer_leave,
-            onpointerup: on_pointer_up,
         }
     }
 }
@@ -0,0 +1,177 @@
+//! Screen reader live region
+
+use crate::state::FlowState;
+use crate::types::AriaLabelConfig;
+use dioxus::prelude::*;
+use dioxus::prelude::{ReadableExt, WritableExt};
+use std::collections::{HashMap, HashSet};
+
+/// What the live region reports changes of
+#[derive(Clone, PartialEq, Default)]
+struct LiveSnapshot {
+    selected_nodes: HashSet<String>,
+    selected_edges: HashSet<String>,
+    nodes: HashSet<String>,
+    /// Edge id to source and target node
+    edges: HashMap<String, (String, String)>,
+    /// Zoom in percent
+    zoom: i64,
+}
+
+/// ARIA live region announcing selection changes, connections, deletions and
+/// zoom changes, plus the usage hints focused nodes and edges are described by.
+/// Other messages are sent with [`FlowState::announce`].
+#[component]
+pub fn LiveRegion<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    #[props(default)] _marker: std::marker::PhantomData<(N, E)>,
+) -> Element {
+    let state = use_context::<FlowState<N, E>>();
+
+    let state_snapshot = state.clone();
+    let snapshot = use_memo(move || {
+        let nodes = state_snapshot.nodes.read();
+        let edges = state_snapshot.edges.read();
+        LiveSnapshot {
+            selected_nodes: nodes
+                .iter()
+                .filter(|node| node.selected)
+                .map(|node| node.id.clone())
+                .collect(),
+            selected_edges: edges
+                .iter()
+                .filter(|edge| edge.selected)
+                .map(|edge| edge.id.clone())
+                .collect(),
+            nodes: nodes.iter().map(|node| node.id.clone()).collect(),
+            edges: edges
+                .iter()
+                .map(|edge| (edge.id.clone(), (edge.source.clone(), edge.target.clone())))
+                .collect(),
+            zoom: (state_snapshot.viewport.read().zoom * 100.0).round() as i64,
+        }
+    });
+
+    let mut previous = use_signal(|| None::<LiveSnapshot>);
+    let mut state_announce = state.clone();
+    use_effect(move || {
+        let current = snapshot();
+        let before = previous.peek().clone();
+        previous.set(Some(current.clone()));
+        let Some(before) = before else {
+            return;
+        };
+        if let Some(message) = describe_change(&state_announce, &before, &current) {
+            state_announce.announce(message);
+        }
+    });
+
+    let config = state.aria_label_config.read().clone();
+    let node_description = config
+        .node_description
+        .clone()
+        .unwrap_or_else(|| AriaLabelConfig::DEFAULT_NODE_DESCRIPTION.to_string());
+    let edge_description = config
+        .edge_description
+        .clone()
+        .unwrap_or_else(|| AriaLabelConfig::DEFAULT_EDGE_DESCRIPTION.to_string());
+    let message = state.live_message.read().clone();
+
+    rsx! {
+        div {
+            id: "{state.node_description_id()}",
+            class: "dioxus-flow__a11y-description",
+            "{node_description}"
+        }
+        div {
+            id: "{state.edge_description_id()}",
+            class: "dioxus-flow__a11y-description",
+            "{edge_description}"
+        }
+        div {
+            class: "dioxus-flow__a11y-live",
+            "aria-live": "polite",
+            "aria-atomic": "true",
+            "{message}"
+        }
+    }
+}
+
+/// Most relevant message for a change: deletions, then connections, then
+/// selection, then zoom
+fn describe_change<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    state: &FlowState<N, E>,
+    before: &LiveSnapshot,
+    after: &LiveSnapshot,
+) -> Option<String> {
+    let config = state.aria_label_config.peek().clone();
+    let removed_nodes = before.nodes.difference(&after.nodes).count();
+    let removed_edges: Vec<&(String, String)> = before
+        .edges
+        .iter()
+        .filter(|(id, _)| !after.edges.contains_key(*id))
+        .map(|(_, ends)| ends)
+        .collect();
+    let added_edges: Vec<&(String, String)> = after
+        .edges
+        .iter()
+        .filter(|(id, _)| !before.edges.contains_key(*id))
+        .map(|(_, ends)| ends)
+        .collect();
+    let endpoints = |(source, target): &(String, String)| {
+        vec![
+            ("source", state.node_announcement_label(source)),
+            ("target", state.node_announcement_label(target)),
+        ]
+    };
+
+    if removed_nodes > 0 || removed_edges.len() > 1 {
+        return Some(AriaLabelConfig::fill(
+            &config.elements_deleted,
+            AriaLabelConfig::DEFAULT_ELEMENTS_DELETED,
+            &[
+                ("nodes", removed_nodes.to_string()),
+                ("edges", removed_edges.len().to_string()),
+            ],
+        ));
+    }
+    if let Some(ends) = added_edges.first() {
+        return Some(AriaLabelConfig::fill(
+            &config.connection_created,
+            AriaLabelConfig::DEFAULT_CONNECTION_CREATED,
+            &endpoints(ends),
+        ));
+    }
+    if let Some(ends) = removed_edges.first() {
+        return Some(AriaLabelConfig::fill(
+            &config.connection_removed,
+            AriaLabelConfig::DEFAULT_CONNECTION_REMOVED,
+            &endpoints(ends),
+        ));
+    }
+    if before.selected_nodes != after.selected_nodes
+        || before.selected_edges != after.selected_edges
+    {
+        return Some(AriaLabelConfig::fill(
+            &config.selection_changed,
+            AriaLabelConfig::DEFAULT_SELECTION_CHANGED,
+            &[
+                ("nodes", after.selected_nodes.len().to_string()),
+                ("edges", after.selected_edges.len().to_string()),
+            ],
+        ));
+    }
+    if before.zoom != after.zoom {
+        return Some(AriaLabelConfig::fill(
+            &config.zoom_changed,
+            AriaLabelConfig::DEFAULT_ZOOM_CHANGED,
+            &[("zoom", after.zoom.to_string())],
+        ));
+    }
+    None
+}
@@ -3,41 +3,55 @@
 mod background;
 mod attribution;
 mod base_edge;
+mod context_menu;
 mod controls;
+mod diagnostics_panel;
 mod dioxus_flow;
+mod drag_source;
 mod edge_label_renderer;
 mod edge_renderer;
 mod edge_toolbar;
 mod flow_provider;
+mod flow_sync;
 mod graph_view;
 mod handle;
+mod live_region;
 mod minimap;
 mod node_renderer;
 mod node_resizer;
 mod node_toolbar;
 mod nodes;
+mod nodes_selection;
 mod pan_zoom_pane;
 mod panel;
+mod search_panel;
 mod selection_listener;
 mod store_updater;
 
 pub use background::*;
 pub use attribution::*;
 pub use base_edge::*;
+pub use context_menu::*;
 pub use controls::*;
+pub use diagnostics_panel::*;
 pub use dioxus_flow::*;
+pub use drag_source::*;
 pub use edge_label_renderer::*;
 pub use edge_renderer::*;
 pub use edge_toolbar::*;
 pub use flow_provider::*;
+pub use flow_sync::*;
 pub use graph_view::*;
 pub use handle::*;
+pub use live_region::*;
 pub use minimap::*;
 pub use node_renderer::*;
 pub use node_resizer::*;
 pub use node_toolbar::*;
 pub use nodes::*;
+pub use nodes_selection::*;
 pub use pan_zoom_pane::*;
 pub use panel::*;
+pub use search_panel::*;
 pub use selection_listener::*;
 pub use store_updater::*;
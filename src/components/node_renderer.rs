@@ -116,6 +116,21 @@ fn NodeWrapper<
     }
 
     let selected = node.selected;
+    let (highlighted, dimmed) = match state.active_highlight.read().as_ref() {
+        Some(highlight) => {
+            let highlighted = highlight.contains_node(&node.id);
+            (highlighted, !highlighted)
+        }
+        None => (false, false),
+    };
+    let diagnostics: Vec<crate::types::Diagnostic> = state
+        .diagnostics
+        .read()
+        .for_node(&node.id)
+        .into_iter()
+        .cloned()
+        .collect();
+    let diagnostic_severity = crate::types::max_severity(&diagnostics);
     let draggable = node.draggable.unwrap_or(true);
     let connectable = node.connectable.unwrap_or(true);
     let selectable = node.selectable.unwrap_or(true);
@@ -129,12 +144,14 @@ fn NodeWrapper<
             return;
         }
         evt.stop_propagation();
-
-        let web_evt = evt.data.as_web_event();
-        if let Some(target) = web_evt
-            .target()
-            .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
-        {
+        state_down.nodes_selection_active.set(false);
+
+        let target = evt
+            .data
+            .try_as_web_event()
+            .and_then(|web_evt| web_evt.target())
+            .and_then(|t| t.dyn_into::<web_sys::Element>().ok());
+        if let Some(target) = &target {
             let no_drag_class = state_down.no_drag_class_name.read().clone();
             if !no_drag_class.is_empty()
                 && target.closest(&format!(".{}", no_drag_class)).ok().flatten().is_some()
@@ -144,11 +161,7 @@ fn NodeWrapper<
         }
 
         if let Some(selector) = &drag_handle_selector {
-            let web_evt = evt.data.as_web_event();
-            let Some(target) = web_evt
-                .target()
-                .and_then(|t| t.dyn_into::<web_sys::Element>().ok())
-            else {
+            let Some(target) = &target else {
                 return;
             };
             if target.closest(selector).ok().flatten().is_none() {
@@ -225,6 +238,7 @@ fn NodeWrapper<
             start_pointer,
             nodes: drag_positions,
             started: false,
+            selection: false,
         }));
 
         if let Some(handler) = &on_node_drag_start {
@@ -252,7 +266,11 @@ fn NodeWrapper<
         }
     };
     let node_enter = node.clone();
+    let mut state_enter = state.clone();
     let on_mouse_enter = move |_| {
+        if *state_enter.highlight_mode.peek() != crate::types::HighlightMode::None {
+            state_enter.hovered_node_id.set(Some(node_enter.id.clone()));
+        }
         if let Some(handler) = &on_node_mouse_enter {
             handler.call(crate::types::NodeMouseEvent {
                 node: node_enter.clone(),
@@ -260,13 +278,44 @@ fn NodeWrapper<
         }
     };
     let node_leave = node.clone();
+    let mut state_leave = state.clone();
     let on_mouse_leave = move |_| {
+        if state_leave.hovered_node_id.peek().as_ref() == Some(&node_leave.id) {
+            state_leave.hovered_node_id.set(None);
+        }
         if let Some(handler) = &on_node_mouse_leave {
             handler.call(crate::types::NodeMouseEvent {
                 node: node_leave.clone(),
             });
         }
     };
+    let node_context = node.clone();
+    let state_context = state.clone();
+    let on_context_menu = move |evt: MouseEvent| {
+        let coords = evt.data.client_coordinates();
+        let screen_position = XYPosition::new(coords.x, coords.y);
+        // Right-clicking part of a multi-selection opens the selection menu.
+        if node_context.selected {
+            let handler = *state_context.on_selection_context_menu.read();
+            let event = state_context.selection_context_menu_event(screen_position);
+            if let (Some(handler), Some(event)) = (handler, event) {
+                evt.prevent_default();
+                evt.stop_propagation();
+                handler.call(event);
+                return;
+            }
+        }
+        let Some(handler) = *state_context.on_node_context_menu.read() else {
+            return;
+        };
+        evt.prevent_default();
+        evt.stop_propagation();
+        handler.call(crate::types::NodeContextMenuEvent {
+            node: node_context.clone(),
+            screen_position,
+            flow_position: state_context.screen_to_flow_position(screen_position),
+        });
+    };
 
     let node_component = node
         .node_type
@@ -281,6 +330,9 @@ fn NodeWrapper<
             draggable,
             connectable,
             selectable,
+            highlighted,
+            dimmed,
+            diagnostics: diagnostics.clone(),
             _marker: std::marker::PhantomData,
         })
     } else {
@@ -304,6 +356,23 @@ fn NodeWrapper<
     if selected {
         base_class.push_str(" selected");
     }
+    if highlighted {
+        base_class.push_str(" highlighted");
+    }
+    if dimmed {
+        base_class.push_str(" dimmed");
+    }
+    if let Some(severity) = diagnostic_severity {
+        base_class.push_str(" diagnostic-");
+        base_class.push_str(severity.as_str());
+    }
+    let diagnostic_badge = diagnostic_severity.map(|severity| {
+        let messages: Vec<&str> = diagnostics
+            .iter()
+            .map(|diagnostic| diagnostic.message.as_str())
+            .collect();
+        (severity.as_str(), diagnostics.len(), messages.join("\n"))
+    });
     let class = if let Some(extra) = &node.class_name {
         format!("{} {}", base_class, extra)
     } else {
@@ -327,6 +396,7 @@ fn NodeWrapper<
             style: "{style}",
             "data-id": "{node.id}",
             "aria-label": "{aria_label}",
+            "aria-describedby": "{state.node_description_id()}",
             role: "group",
             tabindex: "{tab_index}",
             onfocus: {
@@ -351,6 +421,7 @@ fn NodeWrapper<
             ondoubleclick: on_double_click,
             onmouseenter: on_mouse_enter,
             onmouseleave: on_mouse_leave,
+            oncontextmenu: on_context_menu,
             onmounted: move |evt| {
                 if resize_observer.read().is_some() {
                     return;
@@ -401,6 +472,13 @@ fn NodeWrapper<
                 }
             },
             {content}
+            if let Some((severity, count, messages)) = diagnostic_badge {
+                div {
+                    class: "dioxus-flow__diagnostic-badge {severity}",
+                    title: "{messages}",
+                    "{count}"
+                }
+            }
         }
     }
 }
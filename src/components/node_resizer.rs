@@ -473,7 +473,7 @@ fn update_handle_bounds_from_dom<
     state: &mut FlowState<N, E>,
     node_id: &str,
 ) {
-    let Some(window) = web_sys::window() else {
+    let Some(window) = crate::utils::browser::window() else {
         return;
     };
     let Some(document) = window.document() else {
@@ -0,0 +1,135 @@
+//! Nodes selection component
+
+use crate::components::{KeyPressDispatcher, NodeToolbar};
+use crate::state::{FlowState, NodeDragState};
+use crate::types::{KeyModifiers, Node, SelectionDragEvent, XYPosition};
+use crate::utils::{get_internal_nodes_bounds, get_nodes_bounds};
+use dioxus::prelude::dioxus_elements::input_data::MouseButton;
+use dioxus::prelude::*;
+use dioxus::prelude::{InteractionLocation, PointerInteraction, ReadableExt, WritableExt};
+
+/// Box around a multi-node selection made with a selection drag. Dragging it
+/// moves every selected node, and `toolbar` is shown above it in a `NodeToolbar`.
+#[component]
+pub fn NodesSelection<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    #[props(default)] toolbar: Option<Element>,
+    #[props(default)] _marker: std::marker::PhantomData<(N, E)>,
+) -> Element {
+    let state = use_context::<FlowState<N, E>>();
+    let key_press = try_use_context::<KeyPressDispatcher>();
+
+    let selected: Vec<_> = state
+        .node_lookup
+        .read()
+        .values()
+        .filter(|internal| internal.node.selected && !internal.node.hidden)
+        .cloned()
+        .collect();
+    if !*state.nodes_selection_active.read() || selected.len() < 2 {
+        return rsx! {};
+    }
+
+    let bounds = get_internal_nodes_bounds(selected.iter().cloned());
+    let node_ids: Vec<String> = selected
+        .iter()
+        .map(|internal| internal.node.id.clone())
+        .collect();
+    let label = format!("{} selected nodes", node_ids.len());
+    let style = format!(
+        "transform: translate({}px, {}px); width: {}px; height: {}px;",
+        bounds.x, bounds.y, bounds.width, bounds.height
+    );
+
+    let mut state_down = state.clone();
+    let on_pointer_down = move |evt: PointerEvent| {
+        if evt.data.trigger_button() != Some(MouseButton::Primary) {
+            return;
+        }
+        evt.stop_propagation();
+        if !*state_down.nodes_draggable.read() {
+            return;
+        }
+        let drag_nodes: Vec<Node<N>> = state_down
+            .get_selected_nodes()
+            .into_iter()
+            .filter(|node| node.draggable.unwrap_or(true))
+            .collect();
+        let Some(origin) = drag_nodes.first() else {
+            return;
+        };
+        let coords = evt.data.client_coordinates();
+        let start_pointer = state_down.screen_to_flow_position(XYPosition::new(coords.x, coords.y));
+        state_down.node_drag.set(Some(NodeDragState {
+            origin_node_id: origin.id.clone(),
+            start_pointer,
+            nodes: drag_nodes
+                .iter()
+                .map(|node| (node.id.clone(), node.position))
+                .collect(),
+            started: false,
+            selection: true,
+        }));
+        let handler = *state_down.on_selection_drag_start.read();
+        if let Some(handler) = handler {
+            handler.call(SelectionDragEvent {
+                bounds: get_nodes_bounds(&drag_nodes),
+                nodes: drag_nodes,
+            });
+        }
+    };
+
+    let state_menu = state.clone();
+    let on_context_menu = move |evt: MouseEvent| {
+        evt.prevent_default();
+        evt.stop_propagation();
+        let handler = *state_menu.on_selection_context_menu.read();
+        if let Some(handler) = handler {
+            let coords = evt.data.client_coordinates();
+            let screen_position = XYPosition::new(coords.x, coords.y);
+            if let Some(event) = state_menu.selection_context_menu_event(screen_position) {
+                handler.call(event);
+            }
+        }
+    };
+
+    // Arrow keys on the focused box run the keymap's move selection command
+    let on_key_down = move |evt: KeyboardEvent| {
+        let key = evt.key().to_string();
+        if !key.starts_with("Arrow") {
+            return;
+        }
+        let Some(key_press) = key_press.as_ref() else {
+            return;
+        };
+        let modifiers = evt.modifiers();
+        let modifiers = KeyModifiers {
+            ctrl: modifiers.ctrl(),
+            shift: modifiers.shift(),
+            alt: modifiers.alt(),
+            meta: modifiers.meta(),
+        };
+        if (key_press.0)(&key, modifiers, false, evt.is_auto_repeating()) {
+            evt.prevent_default();
+            evt.stop_propagation();
+        }
+    };
+
+    rsx! {
+        div {
+            class: "dioxus-flow__nodesselection",
+            style: "{style}",
+            tabindex: "0",
+            role: "group",
+            "aria-label": "{label}",
+            onpointerdown: on_pointer_down,
+            oncontextmenu: on_context_menu,
+            onkeydown: on_key_down,
+        }
+        if let Some(toolbar) = toolbar {
+            NodeToolbar::<N, E> { node_ids, is_visible: true, {toolbar} }
+        }
+    }
+}
@@ -1,9 +1,13 @@
 //! Pan and zoom pane component
 
+use crate::components::edge_path_results;
 use crate::state::FlowState;
 use crate::types::{
-    ConnectionMode, CoordinateExtent, HandleType, NodeExtent, Rect, SelectionMode, Viewport,
-    XYPosition,
+    ConnectionMode, CoordinateExtent, HandleType, Node, NodeExtent, Rect, SelectionMode,
+    SelectionOperation, Viewport, XYPosition,
+};
+use crate::utils::{
+    get_nodes_bounds, get_polygon_bounds, polygon_contains_rect, polygon_intersects_rect,
 };
 use dioxus::prelude::dioxus_elements::geometry::WheelDelta;
 use dioxus::prelude::dioxus_elements::input_data::MouseButton;
@@ -23,6 +27,77 @@ struct PinchState {
     center: XYPosition,
 }
 
+/// Pointer travel still treated as a click on the pane
+const PANE_CLICK_TOLERANCE: f64 = 3.0;
+/// Screen distance in pixels between recorded lasso points
+const LASSO_MIN_POINT_DISTANCE: f64 = 4.0;
+
+/// Whether an event hit the empty pane rather than a node, edge or overlay
+fn is_pane_target(target: Option<web_sys::EventTarget>) -> bool {
+    target
+        .and_then(|target| target.dyn_into::<web_sys::Element>().ok())
+        .is_none_or(|element| {
+            element
+                .closest(".dioxus-flow__node, .dioxus-flow__edge, .dioxus-flow__nodesselection")
+                .ok()
+                .flatten()
+                .is_none()
+        })
+}
+
+fn pane_mouse_event<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    state: &FlowState<N, E>,
+    evt: &MouseEvent,
+) -> crate::types::PaneMouseEvent {
+    let coords = evt.data.client_coordinates();
+    let screen_position = XYPosition::new(coords.x, coords.y);
+    crate::types::PaneMouseEvent {
+        screen_position,
+        flow_position: state.screen_to_flow_position(screen_position),
+    }
+}
+
+/// Operation picked by the held selection modifier keys; subtract wins over toggle over add.
+/// The pointer modifiers pick the same way as the default keys (Alt subtracts, Ctrl or
+/// Meta toggles, Shift adds), so they still apply with keyboard handling disabled.
+fn box_selection_operation<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    state: &FlowState<N, E>,
+    modifiers: Modifiers,
+) -> SelectionOperation {
+    if *state.subtract_selection_key_pressed.read() || modifiers.alt() {
+        SelectionOperation::Subtract
+    } else if *state.toggle_selection_key_pressed.read() || modifiers.ctrl() || modifiers.meta() {
+        SelectionOperation::Toggle
+    } else if *state.add_selection_key_pressed.read()
+        || *state.multi_selection_key_pressed.read()
+        || modifiers.shift()
+    {
+        SelectionOperation::Add
+    } else {
+        SelectionOperation::Replace
+    }
+}
+
+fn selection_drag_event<N: Clone + PartialEq + Default>(
+    drag_state: &crate::state::NodeDragState,
+    nodes: Vec<Node<N>>,
+) -> crate::types::SelectionDragEvent<N> {
+    let nodes: Vec<Node<N>> = nodes
+        .into_iter()
+        .filter(|node| drag_state.nodes.iter().any(|(id, _)| *id == node.id))
+        .collect();
+    crate::types::SelectionDragEvent {
+        bounds: get_nodes_bounds(&nodes),
+        nodes,
+    }
+}
+
 fn pinch_metrics(pointers: &HashMap<i32, XYPosition>) -> Option<(f64, XYPosition)> {
     let mut iter = pointers.values();
     let first = iter.next()?;
@@ -58,18 +133,19 @@ pub fn PanZoomPane<
     let mut pan_start = use_signal(|| None::<(f64, f64)>);
     let mut start_viewport = use_signal(|| Viewport::identity());
     let mut selection_start = use_signal(|| None::<XYPosition>);
-    let mut selection_additive = use_signal(|| false);
+    let mut selection_operation_signal = use_signal(SelectionOperation::default);
     let mut initialized_size = use_signal(|| false);
     let mut active_pointers = use_signal(HashMap::<i32, XYPosition>::new);
     let mut pinch_state = use_signal(|| None::<PinchState>);
     let mut pane_rect = use_signal(|| None::<web_sys::DomRect>);
+    let mut pane_press = use_signal(|| None::<XYPosition>);
 
     let mut state_size = state.clone();
     use_effect(move || {
         if *initialized_size.read() {
             return;
         }
-        if let Some(window) = web_sys::window() {
+        if let Some(window) = crate::utils::browser::window() {
             if let Ok(width) = window.inner_width() {
                 if let Some(width) = width.as_f64() {
                     state_size.width.set(width);
@@ -101,6 +177,9 @@ pub fn PanZoomPane<
         }
         let trigger_button = evt.data.trigger_button();
         let is_primary = trigger_button == Some(MouseButton::Primary);
+        if is_primary {
+            state_down.nodes_selection_active.set(false);
+        }
         let button_code = trigger_button
             .map(|b| match b {
                 MouseButton::Primary => 0,
@@ -130,20 +209,18 @@ pub fn PanZoomPane<
         }
         let coords = evt.data.client_coordinates();
         let flow_pos = state_down.screen_to_flow_position(XYPosition::new(coords.x, coords.y));
+        pane_press.set(is_primary.then(|| XYPosition::new(coords.x, coords.y)));
 
+        let operation = box_selection_operation(&state_down, modifiers);
         let selection_enabled = is_primary
             && (*state_down.selection_on_drag.read()
             || *state_down.selection_key_pressed.read()
-            || modifiers.shift())
+            || modifiers.shift()
+            || operation != SelectionOperation::Replace)
             && !*state_down.pan_activation_key_pressed.read();
         if selection_enabled && *state_down.elements_selectable.read() {
             selection_start.set(Some(flow_pos));
-            selection_additive.set(
-                *state_down.multi_selection_key_pressed.read()
-                    || modifiers.shift()
-                    || modifiers.meta()
-                    || modifiers.ctrl(),
-            );
+            selection_operation_signal.set(operation);
             state_down.user_selection_active.set(true);
             state_down.user_selection_rect.set(Some(Rect {
                 x: flow_pos.x,
@@ -151,9 +228,12 @@ pub fn PanZoomPane<
                 width: 0.0,
                 height: 0.0,
             }));
+            if state_down.selection_mode.read().is_lasso() {
+                state_down.user_selection_polygon.set(Some(vec![flow_pos]));
+            }
             state_down
                 .multi_selection_active
-                .set(*selection_additive.read());
+                .set(operation != SelectionOperation::Replace);
             if let Some(handler) = &on_selection_start {
                 handler.call(crate::types::SelectionStartEvent {
                     position: flow_pos,
@@ -235,7 +315,36 @@ pub fn PanZoomPane<
             }
         }
 
-        if state_move.connection.read().in_progress {
+        let waypoint_drag = state_move.waypoint_drag.read().clone();
+        if let Some(drag) = waypoint_drag {
+            let coords = evt.data.client_coordinates();
+            let flow_pos = state_move.screen_to_flow_position(XYPosition::new(coords.x, coords.y));
+            let flow_pos = state_move.snap_position(flow_pos);
+            let points = state_move
+                .edge_lookup
+                .read()
+                .get(&drag.edge_id)
+                .map(|edge| edge.points.clone())
+                .filter(|points| drag.index < points.len() && points[drag.index] != flow_pos);
+            if let Some(mut points) = points {
+                points[drag.index] = flow_pos;
+                apply_edge_changes(
+                    &mut state_move,
+                    &on_edges_change,
+                    vec![crate::types::EdgeChange::Points {
+                        id: drag.edge_id.clone(),
+                        points,
+                    }],
+                );
+            }
+            return;
+        }
+
+        let pointer_connection = {
+            let connection = state_move.connection.read();
+            connection.in_progress && !connection.keyboard
+        };
+        if pointer_connection {
             let coords = evt.data.client_coordinates();
             let screen_pos = XYPosition::new(coords.x, coords.y);
             let flow_pos = state_move.screen_to_flow_position(screen_pos);
@@ -302,19 +411,21 @@ pub fn PanZoomPane<
 
             let next_nodes =
                 apply_node_changes_with_next(&mut state_move, &on_nodes_change, changes);
-            if let Some(handler) = &on_node_drag {
-                if let Some(origin_id) = state_move
-                    .node_drag
-                    .read()
-                    .as_ref()
-                    .map(|drag| drag.origin_node_id.clone())
-                {
-                    if let Some(origin) = next_nodes.iter().find(|n| n.id == origin_id).cloned() {
-                        handler.call(crate::types::NodeDragEvent {
-                            node: origin,
-                            nodes: next_nodes,
-                        });
-                    }
+            if drag_state.selection {
+                let handler = *state_move.on_selection_drag.read();
+                if let Some(handler) = handler {
+                    handler.call(selection_drag_event(&drag_state, next_nodes));
+                }
+            } else if let Some(handler) = &on_node_drag {
+                let origin = next_nodes
+                    .iter()
+                    .find(|n| n.id == drag_state.origin_node_id)
+                    .cloned();
+                if let Some(origin) = origin {
+                    handler.call(crate::types::NodeDragEvent {
+                        node: origin,
+                        nodes: next_nodes,
+                    });
                 }
             }
             if *state_move.auto_pan_on_node_drag.read() {
@@ -329,6 +440,23 @@ pub fn PanZoomPane<
         if let Some(start) = *selection_start.read() {
             let coords = evt.data.client_coordinates();
             let flow_pos = state_move.screen_to_flow_position(XYPosition::new(coords.x, coords.y));
+            if state_move.user_selection_polygon.read().is_some() {
+                let zoom = state_move.viewport.read().zoom.max(0.0001);
+                let mut points = state_move
+                    .user_selection_polygon
+                    .read()
+                    .clone()
+                    .unwrap_or_default();
+                let far_enough = points.last().is_none_or(|last| {
+                    last.distance_to(&flow_pos) * zoom >= LASSO_MIN_POINT_DISTANCE
+                });
+                if far_enough {
+                    points.push(flow_pos);
+                    state_move.user_selection_rect.set(get_polygon_bounds(&points));
+                    state_move.user_selection_polygon.set(Some(points));
+                }
+                return;
+            }
             let (min_x, max_x) = if flow_pos.x < start.x {
                 (flow_pos.x, start.x)
             } else {
@@ -369,7 +497,7 @@ pub fn PanZoomPane<
     let mut state_up = state.clone();
     let mut pan_start_up = pan_start.clone();
     let mut selection_start_up = selection_start.clone();
-    let mut selection_additive_up = selection_additive.clone();
+    let mut selection_operation_up = selection_operation_signal;
     let on_pointer_up = move |evt: PointerEvent| {
         if evt.data.pointer_type() == "touch" {
             let pointer_id = evt.data.pointer_id();
@@ -390,14 +518,14 @@ pub fn PanZoomPane<
             &on_selection_end,
             &mut pan_start_up,
             &mut selection_start_up,
-            &mut selection_additive_up,
+            &mut selection_operation_up,
         );
     };
 
     let mut state_leave = state.clone();
     let mut pan_start_leave = pan_start.clone();
     let mut selection_start_leave = selection_start.clone();
-    let mut selection_additive_leave = selection_additive.clone();
+    let mut selection_operation_leave = selection_operation_signal;
     let on_pointer_leave = move |evt: PointerEvent| {
         if evt.data.pointer_type() == "touch" {
             let pointer_id = evt.data.pointer_id();
@@ -418,7 +546,7 @@ pub fn PanZoomPane<
             &on_selection_end,
             &mut pan_start_leave,
             &mut selection_start_leave,
-            &mut selection_additive_leave,
+            &mut selection_operation_leave,
         );
     };
 
@@ -452,6 +580,15 @@ pub fn PanZoomPane<
             return;
         }
         let coords = evt.data.client_coordinates();
+        if let Some(handler) = *state_wheel.on_pane_scroll.read() {
+            let screen_position = XYPosition::new(coords.x, coords.y);
+            handler.call(crate::types::PaneScrollEvent {
+                screen_position,
+                flow_position: state_wheel.screen_to_flow_position(screen_position),
+                delta_x,
+                delta_y,
+            });
+        }
         let modifiers = evt.data.modifiers();
 
         let zoom_key = *state_wheel.zoom_activation_key_pressed.read();
@@ -504,6 +641,11 @@ pub fn PanZoomPane<
 
     let mut state_double = state.clone();
     let on_double_click = move |evt: MouseEvent| {
+        let on_pane = is_pane_target(evt.data.try_as_web_event().and_then(|e| e.target()));
+        let handler = *state_double.on_pane_double_click.read();
+        if let Some(handler) = handler.filter(|_| on_pane) {
+            handler.call(pane_mouse_event(&state_double, &evt));
+        }
         if !*state_double.zoom_on_double_click.read() {
             return;
         }
@@ -515,9 +657,62 @@ pub fn PanZoomPane<
         }
     };
 
+    let state_click = state.clone();
+    let on_click = move |evt: MouseEvent| {
+        let Some(press) = pane_press.write().take() else {
+            return;
+        };
+        let Some(handler) = *state_click.on_pane_click.read() else {
+            return;
+        };
+        let coords = evt.data.client_coordinates();
+        let moved = press.distance_to(&XYPosition::new(coords.x, coords.y));
+        if moved <= PANE_CLICK_TOLERANCE
+            && is_pane_target(evt.data.try_as_web_event().and_then(|e| e.target()))
+        {
+            handler.call(pane_mouse_event(&state_click, &evt));
+        }
+    };
+
+    let state_hover = state.clone();
+    let on_mouse_move = move |evt: MouseEvent| {
+        if let Some(handler) = *state_hover.on_pane_mouse_move.read() {
+            handler.call(pane_mouse_event(&state_hover, &evt));
+        }
+    };
+    let state_enter = state.clone();
+    let on_mouse_enter = move |evt: MouseEvent| {
+        if let Some(handler) = *state_enter.on_pane_mouse_enter.read() {
+            handler.call(pane_mouse_event(&state_enter, &evt));
+        }
+    };
+    let state_leave = state.clone();
+    let on_mouse_leave = move |evt: MouseEvent| {
+        if let Some(handler) = *state_leave.on_pane_mouse_leave.read() {
+            handler.call(pane_mouse_event(&state_leave, &evt));
+        }
+    };
+
+    let state_context = state.clone();
+    let on_context_menu = move |evt: MouseEvent| {
+        let Some(handler) = *state_context.on_pane_context_menu.read() else {
+            return;
+        };
+        if !is_pane_target(evt.data.try_as_web_event().and_then(|e| e.target())) {
+            return;
+        }
+        evt.prevent_default();
+        handler.call(pane_mouse_event(&state_context, &evt));
+    };
+
     rsx! {
         div {
             class: "dioxus-flow__panzoom",
+            onclick: on_click,
+            onmousemove: on_mouse_move,
+            onmouseenter: on_mouse_enter,
+            onmouseleave: on_mouse_leave,
+            oncontextmenu: on_context_menu,
             onpointerdown: on_pointer_down,
             onpointermove: on_pointer_move,
             onpointerup: on_pointer_up,
@@ -609,28 +804,25 @@ fn end_interaction<
     on_selection_end: &Option<EventHandler<crate::types::SelectionEndEvent<N, E>>>,
     pan_start: &mut Signal<Option<(f64, f64)>>,
     selection_start: &mut Signal<Option<XYPosition>>,
-    selection_additive: &mut Signal<bool>,
+    selection_operation: &mut Signal<SelectionOperation>,
 ) {
+    if state.waypoint_drag.read().is_some() {
+        state.waypoint_drag.set(None);
+        return;
+    }
+    // A pointer release aborts a keyboard connection rather than completing it
+    if state.connection.read().keyboard {
+        state.cancel_connection();
+    }
     if state.connection.read().in_progress {
-        let mut connection = state.connection.read().clone();
-        let reconnect_edge = connection.reconnect_edge_id.clone();
-        let reconnect_end = connection.reconnect_end;
-        let result = if connection.is_valid {
-            connection.to_connection()
-        } else {
-            None
+        let (reconnect_edge, reconnect_end) = {
+            let connection = state.connection.read();
+            (connection.reconnect_edge_id.clone(), connection.reconnect_end)
         };
         let edge_before = reconnect_edge
             .as_ref()
             .and_then(|id| state.edge_lookup.read().get(id).cloned());
-        if let Some(handler) = state.on_connect_end.read().clone() {
-            handler.call(crate::types::ConnectionEndEvent {
-                connection: result.clone(),
-                is_valid: connection.is_valid,
-            });
-        }
-        connection.reset();
-        state.connection.set(connection);
+        let result = state.finish_connection();
 
         if let Some(conn) = result {
             if let Some(edge_id) = reconnect_edge {
@@ -741,12 +933,17 @@ fn end_interaction<
             });
         }
         let next_nodes = apply_node_changes_with_next(state, on_nodes_change, changes);
-        if let Some(handler) = on_node_drag_stop {
-            if let Some(origin) = next_nodes
+        if drag_state.selection {
+            let handler = *state.on_selection_drag_stop.read();
+            if let Some(handler) = handler {
+                handler.call(selection_drag_event(&drag_state, next_nodes));
+            }
+        } else if let Some(handler) = on_node_drag_stop {
+            let origin = next_nodes
                 .iter()
                 .find(|n| n.id == drag_state.origin_node_id)
-                .cloned()
-            {
+                .cloned();
+            if let Some(origin) = origin {
                 handler.call(crate::types::NodeDragEvent {
                     node: origin,
                     nodes: next_nodes,
@@ -759,9 +956,11 @@ fn end_interaction<
 
     if selection_start.read().is_some() {
         let selection = state.user_selection_rect.read().clone();
+        let polygon = state.user_selection_polygon.read().clone();
         let nodes = state.nodes.read().clone();
         if let Some(rect) = selection {
             let selection_mode = *state.selection_mode.read();
+            let lasso = polygon.as_deref().unwrap_or_default();
             let selected_ids = {
                 let internal_lookup = state.node_lookup.read();
                 let mut selected_ids = HashSet::new();
@@ -785,6 +984,8 @@ fn end_interaction<
                     let is_selected = match selection_mode {
                         SelectionMode::Full => rect.contains_rect(&node_rect),
                         SelectionMode::Partial => rect.intersects(&node_rect),
+                        SelectionMode::Lasso => polygon_contains_rect(lasso, &node_rect),
+                        SelectionMode::LassoPartial => polygon_intersects_rect(lasso, &node_rect),
                     };
                     if is_selected {
                         selected_ids.insert(node.id.clone());
@@ -793,12 +994,13 @@ fn end_interaction<
                 selected_ids
             };
 
-            let additive = *selection_additive.read();
+            let operation = *selection_operation.read();
             let mut changes = Vec::new();
+            let mut selected_count = 0;
             for node in nodes.iter() {
-                let mut should_select = selected_ids.contains(&node.id);
-                if additive && node.selected {
-                    should_select = true;
+                let should_select = operation.apply(node.selected, selected_ids.contains(&node.id));
+                if should_select {
+                    selected_count += 1;
                 }
                 if node.selected != should_select {
                     changes.push(crate::types::NodeChange::Selection {
@@ -813,6 +1015,53 @@ fn end_interaction<
                 None
             };
             apply_node_changes(state, on_nodes_change, changes);
+            state.nodes_selection_active.set(selected_count > 1);
+            let mut next_edges_for_event = None;
+
+            // Lasso selections always pick up the edges whose paths they cross;
+            // boxes only when `select_edges_in_box` is set.
+            if polygon.is_some() || *state.select_edges_in_box.read() {
+                let selected_edge_ids: HashSet<String> = edge_path_results(state)
+                    .into_iter()
+                    .filter(|(edge, path)| {
+                        let geometry = &path.geometry;
+                        let in_selection = match selection_mode {
+                            SelectionMode::Full => geometry
+                                .bounding_box()
+                                .is_some_and(|bounds| rect.contains_rect(&bounds)),
+                            SelectionMode::Partial => geometry.intersects_rect(&rect),
+                            SelectionMode::Lasso | SelectionMode::LassoPartial => {
+                                geometry.intersects_polygon(lasso)
+                            }
+                        };
+                        edge.selectable.unwrap_or(true) && in_selection
+                    })
+                    .map(|(edge, _)| edge.id)
+                    .collect();
+                let edge_changes: Vec<_> = state
+                    .edges
+                    .read()
+                    .iter()
+                    .filter_map(|edge| {
+                        let should_select =
+                            operation.apply(edge.selected, selected_edge_ids.contains(&edge.id));
+                        (edge.selected != should_select).then(|| {
+                            crate::types::EdgeChange::Selection {
+                                id: edge.id.clone(),
+                                selected: should_select,
+                            }
+                        })
+                    })
+                    .collect();
+                if !edge_changes.is_empty() {
+                    next_edges_for_event = Some(crate::types::apply_edge_changes(
+                        edge_changes.clone(),
+                        state.edges.read().clone(),
+                    ));
+                    apply_edge_changes(state, on_edges_change, edge_changes);
+                }
+            }
+
             if let Some(handler) = on_selection_end {
                 let next_nodes = next_nodes_for_event.unwrap_or_else(|| state.nodes.read().clone());
                 let selected_nodes = next_nodes
@@ -820,15 +1069,16 @@ fn end_interaction<
                     .filter(|n| n.selected)
                     .cloned()
                     .collect();
-                let selected_edges = state
-                    .edges
-                    .read()
+                let next_edges = next_edges_for_event.unwrap_or_else(|| state.edges.read().clone());
+                let selected_edges = next_edges
                     .iter()
                     .filter(|e| e.selected)
                     .cloned()
                     .collect();
                 handler.call(crate::types::SelectionEndEvent {
                     selection_rect: Some(rect),
+                    selection_polygon: polygon.clone(),
+                    operation,
                     nodes: selected_nodes,
                     edges: selected_edges,
                 });
@@ -840,8 +1090,9 @@ fn end_interaction<
 
         state.user_selection_active.set(false);
         state.user_selection_rect.set(None);
+        state.user_selection_polygon.set(None);
         selection_start.set(None);
-        selection_additive.set(false);
+        selection_operation.set(SelectionOperation::Replace);
     }
 
     if *state.panning.read() {
@@ -999,25 +1250,59 @@ fn update_connection_target<
             target.handle_type,
             base_valid,
         );
-
-        let mut is_valid = base_valid;
-        if base_valid {
-            if let Some(conn) = connection.to_connection() {
-                if let Some(validator) = *state.is_valid_connection.read() {
-                    is_valid = validator(&conn);
-                }
-            } else {
-                is_valid = false;
-            }
-        }
-        connection.is_valid = is_valid;
+        connection.is_valid = base_valid && validate_connection_target(state, connection);
         connection.update_screen_position(target.screen_pos, target.flow_pos);
+    } else if let Some(node_id) = floating_connection_target(state, connection, flow_pos) {
+        // Floating edges connect to the node body; the line anchors to its boundary.
+        let handle_type = connection
+            .from_type
+            .map(|handle_type| handle_type.opposite())
+            .unwrap_or(HandleType::Target);
+        connection.set_target(node_id, None, handle_type, true);
+        connection.is_valid = validate_connection_target(state, connection);
+        connection.update_screen_position(screen_pos, flow_pos);
     } else {
         connection.clear_target();
         connection.update_screen_position(screen_pos, flow_pos);
     }
 }
 
+fn validate_connection_target<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    state: &FlowState<N, E>,
+    connection: &crate::types::ConnectionState,
+) -> bool {
+    let Some(conn) = connection.to_connection() else {
+        return false;
+    };
+    match *state.is_valid_connection.read() {
+        Some(validator) => validator(&conn),
+        None => true,
+    }
+}
+
+fn floating_connection_target<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    state: &FlowState<N, E>,
+    connection: &crate::types::ConnectionState,
+    flow_pos: XYPosition,
+) -> Option<String> {
+    if !*state.floating_edges.read() || !*state.nodes_connectable.read() {
+        return None;
+    }
+    let node = state.get_node_at_position(flow_pos)?;
+    if connection.from_node.as_deref() == Some(node.id.as_str())
+        || !node.connectable.unwrap_or(true)
+    {
+        return None;
+    }
+    Some(node.id)
+}
+
 fn find_closest_handle<
     N: Clone + PartialEq + Default + 'static,
     E: Clone + PartialEq + Default + 'static,
@@ -0,0 +1,132 @@
+//! Node search panel
+
+use crate::state::{FlowState, NodeSearch, NodeSearchOptions};
+use crate::types::Node;
+use dioxus::prelude::*;
+use dioxus::prelude::{ReadableExt, WritableExt};
+
+type SearchNodeText<N> = fn(&Node<N>) -> String;
+
+/// Text box that finds nodes by label and steps through the matches.
+/// Enter goes to the next match, Shift+Enter to the previous, Escape clears.
+#[component]
+pub fn SearchPanel<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    #[props(default)] position: Option<String>,
+    #[props(default)] class: Option<String>,
+    #[props(default)] placeholder: Option<String>,
+    /// Text a node is matched on, defaults to its aria label or id
+    #[props(default)]
+    node_text: Option<SearchNodeText<N>>,
+    #[props(default)] options: NodeSearchOptions,
+    #[props(default)] aria_label: Option<String>,
+    #[props(default)] _marker: std::marker::PhantomData<(N, E)>,
+) -> Element {
+    let state = use_context::<FlowState<N, E>>();
+    let mut query = use_signal(String::new);
+    let mut search = use_signal(|| None::<NodeSearch<N, E>>);
+    let position = position.unwrap_or_else(|| "top-right".to_string());
+    let class = class.unwrap_or_default();
+    let placeholder = placeholder.unwrap_or_else(|| "Search nodes".to_string());
+    let aria_label = aria_label.unwrap_or_else(|| "Search nodes".to_string());
+
+    use_drop(move || {
+        if let Some(mut search) = search.write().take() {
+            search.clear();
+        }
+    });
+
+    let state_input = state.clone();
+    let options_input = options.clone();
+    let on_input = move |evt: FormEvent| {
+        let value = evt.value();
+        query.set(value.clone());
+        if let Some(mut previous) = search.write().take() {
+            previous.clear();
+        }
+        let needle = value.trim().to_lowercase();
+        if needle.is_empty() {
+            return;
+        }
+        let matches = state_input
+            .find_nodes(|node| {
+                let text = match node_text {
+                    Some(node_text) => node_text(node),
+                    None => node.aria_label.clone().unwrap_or_else(|| node.id.clone()),
+                };
+                text.to_lowercase().contains(&needle)
+            })
+            .into_iter()
+            .map(|node| node.id)
+            .collect();
+        // Typing keeps DOM focus in the text box; Enter and the buttons move it
+        let mut next = NodeSearch::new(state_input.clone(), matches, options_input.clone());
+        next.set_focus_dom(false);
+        next.next_match();
+        next.set_focus_dom(options_input.focus_dom);
+        search.set(Some(next));
+    };
+
+    let on_key_down = move |evt: KeyboardEvent| match evt.key() {
+        Key::Enter => {
+            evt.prevent_default();
+            if let Some(search) = search.write().as_mut() {
+                if evt.modifiers().shift() {
+                    search.previous_match();
+                } else {
+                    search.next_match();
+                }
+            }
+        }
+        Key::Escape => {
+            query.set(String::new());
+            if let Some(mut search) = search.write().take() {
+                search.clear();
+            }
+        }
+        _ => {}
+    };
+    let on_previous = move |_| {
+        if let Some(search) = search.write().as_mut() {
+            search.previous_match();
+        }
+    };
+    let on_next = move |_| {
+        if let Some(search) = search.write().as_mut() {
+            search.next_match();
+        }
+    };
+
+    let (current, total) = search.read().as_ref().map_or((0, 0), |search| {
+        (
+            search.current_index().map_or(0, |index| index + 1),
+            search.len(),
+        )
+    });
+    let has_query = !query.read().trim().is_empty();
+
+    rsx! {
+        div {
+            class: "dioxus-flow__panel {position}",
+            div {
+                class: "dioxus-flow__search {class}",
+                role: "search",
+                input {
+                    r#type: "search",
+                    value: "{query}",
+                    placeholder: "{placeholder}",
+                    "aria-label": "{aria_label}",
+                    oninput: on_input,
+                    onkeydown: on_key_down,
+                }
+                if has_query {
+                    span { class: "dioxus-flow__search-count", "aria-live": "polite", "{current}/{total}" }
+                }
+                button { onclick: on_previous, disabled: total == 0, "aria-label": "Previous match", "<" }
+                button { onclick: on_next, disabled: total == 0, "aria-label": "Next match", ">" }
+            }
+        }
+    }
+}
@@ -40,10 +40,14 @@ pub fn StoreUpdater<
     #[props(default = true)] nodes_focusable: bool,
     #[props(default = true)] edges_focusable: bool,
     #[props(default = true)] edges_reconnectable: bool,
+    #[props(default = true)] edge_waypoints_editable: bool,
+    #[props(default = false)] floating_edges: bool,
+    #[props(default = 24.0)] parallel_edge_spacing: f64,
     #[props(default = true)] elements_selectable: bool,
     #[props(default = true)] select_nodes_on_drag: bool,
     #[props(default = false)] selection_on_drag: bool,
     #[props(default = SelectionMode::Partial)] selection_mode: SelectionMode,
+    #[props(default = false)] select_edges_in_box: bool,
     #[props(default)] connection_mode: Option<crate::types::ConnectionMode>,
     #[props(default)] connection_line_type: Option<ConnectionLineType>,
     #[props(default)] connection_line_style: Option<String>,
@@ -107,10 +111,14 @@ pub fn StoreUpdater<
         state_config.nodes_focusable.set(nodes_focusable);
         state_config.edges_focusable.set(edges_focusable);
         state_config.edges_reconnectable.set(edges_reconnectable);
+        state_config.edge_waypoints_editable.set(edge_waypoints_editable);
+        state_config.floating_edges.set(floating_edges);
+        state_config.parallel_edge_spacing.set(parallel_edge_spacing);
         state_config.elements_selectable.set(elements_selectable);
         state_config.select_nodes_on_drag.set(select_nodes_on_drag);
         state_config.selection_on_drag.set(selection_on_drag);
         state_config.selection_mode.set(selection_mode);
+        state_config.select_edges_in_box.set(select_edges_in_box);
         state_config.connection_radius.set(connection_radius);
         state_config.reconnect_radius.set(reconnect_radius);
         state_config.node_drag_threshold.set(node_drag_threshold);
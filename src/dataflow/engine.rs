@@ -0,0 +1,489 @@
+//! Incremental graph evaluation
+
+use super::{DataflowError, NodeInputs, NodeOutputs, NodeProcessor, NodeResult, Port, port_id};
+use crate::types::{Connection, Edge, Node};
+use crate::utils::{GraphEdge, GraphIndex};
+use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+
+/// Processor key of nodes without a `node_type`
+pub const DEFAULT_NODE_TYPE: &str = "default";
+
+/// Nodes touched by one `DataflowEngine::evaluate` call
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct DataflowPass {
+    /// Nodes whose processor ran, in evaluation order
+    pub evaluated: Vec<String>,
+    /// Nodes whose result changed or was removed
+    pub changed: Vec<String>,
+}
+
+/// Evaluates a flow by running each node's processor on the outputs of the
+/// nodes connected to its inputs, upstream first.
+///
+/// Results are kept between calls. A node is evaluated again only when its type
+/// or data, its incoming edges, or the result of a node feeding it changed, or
+/// after `mark_dirty`.
+pub struct DataflowEngine<
+    N: Clone + PartialEq + Default + 'static = (),
+    V: Clone + PartialEq + 'static = (),
+> {
+    processors: HashMap<String, Rc<dyn NodeProcessor<N, V>>>,
+    results: HashMap<String, NodeResult<V>>,
+    /// Node type and data as of the last evaluation
+    nodes: HashMap<String, (Option<String>, N)>,
+    edges: HashMap<String, GraphEdge>,
+    dirty: HashSet<String>,
+    invalidated: bool,
+}
+
+impl<N, V> Default for DataflowEngine<N, V>
+where
+    N: Clone + PartialEq + Default + 'static,
+    V: Clone + PartialEq + 'static,
+{
+    fn default() -> Self {
+        Self {
+            processors: HashMap::new(),
+            results: HashMap::new(),
+            nodes: HashMap::new(),
+            edges: HashMap::new(),
+            dirty: HashSet::new(),
+            invalidated: false,
+        }
+    }
+}
+
+impl<N, V> DataflowEngine<N, V>
+where
+    N: Clone + PartialEq + Default + 'static,
+    V: Clone + PartialEq + 'static,
+{
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Registers the processor for nodes of `node_type`
+    pub fn with_processor(
+        mut self,
+        node_type: impl Into<String>,
+        processor: impl NodeProcessor<N, V> + 'static,
+    ) -> Self {
+        self.set_processor(node_type, processor);
+        self
+    }
+
+    /// Registers the processor for nodes of `node_type`; every node is
+    /// evaluated again on the next pass
+    pub fn set_processor(
+        &mut self,
+        node_type: impl Into<String>,
+        processor: impl NodeProcessor<N, V> + 'static,
+    ) {
+        self.processors.insert(node_type.into(), Rc::new(processor));
+        self.invalidated = true;
+    }
+
+    /// Evaluates `node_id` again on the next pass, for processors that read
+    /// state outside the graph
+    pub fn mark_dirty(&mut self, node_id: impl Into<String>) {
+        self.dirty.insert(node_id.into());
+    }
+
+    /// Evaluates every node again on the next pass
+    pub fn invalidate(&mut self) {
+        self.invalidated = true;
+    }
+
+    pub fn result(&self, node_id: &str) -> Option<&NodeResult<V>> {
+        self.results.get(node_id)
+    }
+
+    pub fn outputs(&self, node_id: &str) -> Option<&NodeOutputs<V>> {
+        self.results.get(node_id)?.as_ref().ok()
+    }
+
+    pub fn error(&self, node_id: &str) -> Option<&DataflowError> {
+        self.results.get(node_id)?.as_ref().err()
+    }
+
+    pub fn results(&self) -> &HashMap<String, NodeResult<V>> {
+        &self.results
+    }
+
+    /// Whether the connection joins ports of matching kinds. Handles missing
+    /// from a processor's declared ports are rejected.
+    pub fn is_valid_connection(&self, connection: &Connection, nodes: &[Node<N>]) -> bool {
+        let find = |id: &str| nodes.iter().find(|node| node.id == id);
+        let (Some(source), Some(target)) = (find(&connection.source), find(&connection.target))
+        else {
+            return false;
+        };
+        let output = match self.processor(source) {
+            Some(processor) => declared_kind(
+                processor.outputs(source),
+                connection.source_handle.as_deref(),
+            ),
+            None => Some(None),
+        };
+        let input = match self.processor(target) {
+            Some(processor) => declared_kind(
+                processor.inputs(target),
+                connection.target_handle.as_deref(),
+            ),
+            None => Some(None),
+        };
+        match (output, input) {
+            (Some(Some(output)), Some(Some(input))) => output == input,
+            (Some(_), Some(_)) => true,
+            _ => false,
+        }
+    }
+
+    /// Brings the results up to date with `nodes` and `edges`
+    pub fn evaluate<E>(&mut self, nodes: &[Node<N>], edges: &[Edge<E>]) -> DataflowPass
+    where
+        E: Clone + PartialEq + Default,
+    {
+        self.evaluate_indexed(nodes, &GraphIndex::new(nodes, edges))
+    }
+
+    /// Like `evaluate`, reusing an index of `nodes` and their edges such as
+    /// `FlowState::graph_index`
+    pub fn evaluate_indexed(&mut self, nodes: &[Node<N>], index: &GraphIndex) -> DataflowPass {
+        let mut pass = DataflowPass::default();
+        let mut dirty = std::mem::take(&mut self.dirty);
+
+        let mut snapshot = HashMap::with_capacity(nodes.len());
+        let mut by_id: HashMap<&str, &Node<N>> = HashMap::with_capacity(nodes.len());
+        for node in nodes {
+            if by_id.contains_key(node.id.as_str()) {
+                continue;
+            }
+            by_id.insert(&node.id, node);
+            let current = (node.node_type.clone(), node.data.clone());
+            if self.invalidated || self.nodes.get(&node.id) != Some(&current) {
+                dirty.insert(node.id.clone());
+            }
+            snapshot.insert(node.id.clone(), current);
+        }
+        self.invalidated = false;
+        for id in self.nodes.keys() {
+            if !snapshot.contains_key(id) && self.results.remove(id).is_some() {
+                pass.changed.push(id.clone());
+            }
+        }
+        self.nodes = snapshot;
+
+        let edge_snapshot: HashMap<String, GraphEdge> = index
+            .edges()
+            .iter()
+            .map(|edge| (edge.id.clone(), edge.clone()))
+            .collect();
+        for (id, edge) in &edge_snapshot {
+            if self.edges.get(id) != Some(edge) {
+                dirty.insert(edge.target.clone());
+            }
+        }
+        for (id, edge) in &self.edges {
+            if edge_snapshot.get(id) != Some(edge) {
+                dirty.insert(edge.target.clone());
+            }
+        }
+        self.edges = edge_snapshot;
+
+        let mut changed: HashSet<String> = HashSet::new();
+        for group in index.strongly_connected_components() {
+            let cyclic = group.len() > 1
+                || index
+                    .outgoing_edges(&group[0])
+                    .iter()
+                    .any(|edge| edge.target == group[0]);
+            for id in &group {
+                let result = if cyclic {
+                    Err(DataflowError::Cycle)
+                } else {
+                    let stale = dirty.contains(id)
+                        || index
+                            .incoming_edges(id)
+                            .iter()
+                            .any(|edge| changed.contains(&edge.source));
+                    if !stale {
+                        continue;
+                    }
+                    pass.evaluated.push(id.clone());
+                    self.process(by_id[id.as_str()], &by_id, index)
+                };
+                if self.results.get(id) != Some(&result) {
+                    self.results.insert(id.clone(), result);
+                    changed.insert(id.clone());
+                    pass.changed.push(id.clone());
+                }
+            }
+        }
+        pass
+    }
+
+    fn processor(&self, node: &Node<N>) -> Option<&Rc<dyn NodeProcessor<N, V>>> {
+        let node_type = node.node_type.as_deref().unwrap_or(DEFAULT_NODE_TYPE);
+        self.processors.get(node_type)
+    }
+
+    /// Gathers the node's inputs from upstream results and runs its processor
+    fn process(
+        &self,
+        node: &Node<N>,
+        nodes: &HashMap<&str, &Node<N>>,
+        index: &GraphIndex,
+    ) -> NodeResult<V> {
+        let Some(processor) = self.processor(node) else {
+            let node_type = node.node_type.as_deref().unwrap_or(DEFAULT_NODE_TYPE);
+            return Err(DataflowError::NoProcessor(node_type.to_string()));
+        };
+        let ports = processor.inputs(node);
+        let mut inputs = NodeInputs::new();
+        for edge in index.incoming_edges(&node.id) {
+            if let Some(expected) = self.mismatched_kind(edge, &ports, nodes) {
+                return Err(DataflowError::TypeMismatch {
+                    port: port_id(edge.target_handle.as_deref()).to_string(),
+                    expected,
+                });
+            }
+            match self.results.get(&edge.source) {
+                Some(Ok(outputs)) => {
+                    if let Some(value) = outputs.get(port_id(edge.source_handle.as_deref())) {
+                        inputs.push(port_id(edge.target_handle.as_deref()), value.clone());
+                    }
+                }
+                Some(Err(_)) => return Err(DataflowError::Upstream(edge.source.clone())),
+                None => {}
+            }
+        }
+        for port in ports {
+            if inputs.contains(&port.id) {
+                continue;
+            }
+            match port.default {
+                Some(value) => inputs.push(port.id, value),
+                None if port.required => return Err(DataflowError::MissingInput(port.id)),
+                None => {}
+            }
+        }
+        processor.process(node, &inputs)
+    }
+
+    /// Kind the input of `edge` expects when the output feeding it declares a
+    /// different one. Connections made before a kind changed can still exist.
+    fn mismatched_kind(
+        &self,
+        edge: &GraphEdge,
+        inputs: &[Port<V>],
+        nodes: &HashMap<&str, &Node<N>>,
+    ) -> Option<String> {
+        let target_port = port_id(edge.target_handle.as_deref());
+        let expected = inputs
+            .iter()
+            .find(|port| port.id == target_port)?
+            .kind
+            .clone()?;
+        let source = nodes.get(edge.source.as_str())?;
+        let source_port = port_id(edge.source_handle.as_deref());
+        let actual = self
+            .processor(source)?
+            .outputs(source)
+            .into_iter()
+            .find(|port| port.id == source_port)?
+            .kind?;
+        (actual != expected).then_some(expected)
+    }
+}
+
+/// Kind of the port `handle` attaches to, None when the ports are declared but
+/// do not include it
+fn declared_kind<V>(ports: Vec<Port<V>>, handle: Option<&str>) -> Option<Option<String>> {
+    if ports.is_empty() {
+        return Some(None);
+    }
+    let id = port_id(handle);
+    ports
+        .into_iter()
+        .find(|port| port.id == id)
+        .map(|port| port.kind)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::dataflow::DEFAULT_PORT;
+    use crate::types::XYPosition;
+
+    fn value(node: &Node<f64>, _inputs: &NodeInputs<f64>) -> NodeResult<f64> {
+        Ok(NodeOutputs::single(node.data))
+    }
+
+    fn sum(_node: &Node<f64>, inputs: &NodeInputs<f64>) -> NodeResult<f64> {
+        Ok(NodeOutputs::single(inputs.all(DEFAULT_PORT).iter().sum()))
+    }
+
+    fn engine() -> DataflowEngine<f64, f64> {
+        DataflowEngine::new()
+            .with_processor("value", value)
+            .with_processor("sum", sum)
+    }
+
+    fn node(id: &str, node_type: &str, data: f64) -> Node<f64> {
+        Node::new(id, XYPosition::new(0.0, 0.0))
+            .with_type(node_type)
+            .with_data(data)
+    }
+
+    fn edge(source: &str, target: &str) -> Edge {
+        Edge::new(format!("{source}-{target}"), source, target)
+    }
+
+    fn evaluate(
+        engine: &mut DataflowEngine<f64, f64>,
+        nodes: &[Node<f64>],
+        edges: &[Edge],
+    ) -> DataflowPass {
+        engine.evaluate_indexed(nodes, &GraphIndex::new(nodes, edges))
+    }
+
+    fn output(engine: &DataflowEngine<f64, f64>, id: &str) -> Option<f64> {
+        engine.outputs(id)?.get(DEFAULT_PORT).copied()
+    }
+
+    #[test]
+    fn a_change_reevaluates_only_downstream_nodes() {
+        let mut engine = engine();
+        let mut nodes = vec![
+            node("a", "value", 1.0),
+            node("b", "sum", 0.0),
+            node("c", "sum", 0.0),
+            node("d", "value", 5.0),
+        ];
+        let edges = vec![edge("a", "b"), edge("b", "c")];
+        let pass = evaluate(&mut engine, &nodes, &edges);
+        assert_eq!(pass.evaluated.len(), 4);
+        assert_eq!(output(&engine, "c"), Some(1.0));
+
+        let pass = evaluate(&mut engine, &nodes, &edges);
+        assert!(pass.evaluated.is_empty());
+        assert!(pass.changed.is_empty());
+
+        nodes[0].data = 2.0;
+        let pass = evaluate(&mut engine, &nodes, &edges);
+        assert_eq!(pass.evaluated, ["a", "b", "c"]);
+        assert_eq!(pass.changed, ["a", "b", "c"]);
+        assert_eq!(output(&engine, "c"), Some(2.0));
+
+        engine.mark_dirty("d");
+        let pass = evaluate(&mut engine, &nodes, &edges);
+        assert_eq!(pass.evaluated, ["d"]);
+        assert!(pass.changed.is_empty());
+    }
+
+    #[test]
+    fn removing_an_edge_marks_its_target_dirty() {
+        let mut engine = engine();
+        let nodes = vec![
+            node("a", "value", 1.0),
+            node("b", "value", 2.0),
+            node("c", "sum", 0.0),
+        ];
+        let edges = vec![edge("a", "c"), edge("b", "c")];
+        evaluate(&mut engine, &nodes, &edges);
+        assert_eq!(output(&engine, "c"), Some(3.0));
+
+        let pass = evaluate(&mut engine, &nodes, &edges[1..]);
+        assert_eq!(pass.evaluated, ["c"]);
+        assert_eq!(output(&engine, "c"), Some(2.0));
+    }
+
+    #[test]
+    fn a_cycle_fails_its_members_and_the_nodes_below() {
+        let mut engine = engine();
+        let nodes = vec![
+            node("a", "value", 1.0),
+            node("b", "sum", 0.0),
+            node("c", "sum", 0.0),
+            node("d", "sum", 0.0),
+        ];
+        let edges = vec![
+            edge("a", "b"),
+            edge("b", "c"),
+            edge("c", "b"),
+            edge("c", "d"),
+        ];
+        evaluate(&mut engine, &nodes, &edges);
+        assert_eq!(output(&engine, "a"), Some(1.0));
+        assert_eq!(engine.error("b"), Some(&DataflowError::Cycle));
+        assert_eq!(engine.error("c"), Some(&DataflowError::Cycle));
+        assert_eq!(
+            engine.error("d"),
+            Some(&DataflowError::Upstream("c".to_string()))
+        );
+
+        let pass = evaluate(
+            &mut engine,
+            &nodes,
+            &[edge("a", "b"), edge("b", "c"), edge("c", "d")],
+        );
+        assert_eq!(pass.evaluated, ["b", "c", "d"]);
+        assert_eq!(output(&engine, "d"), Some(1.0));
+    }
+
+    /// Processor with one input and one output of the same kind
+    struct Typed(&'static str);
+
+    impl NodeProcessor<f64, f64> for Typed {
+        fn inputs(&self, _node: &Node<f64>) -> Vec<Port<f64>> {
+            vec![Port::new("in").with_kind(self.0)]
+        }
+
+        fn outputs(&self, _node: &Node<f64>) -> Vec<Port<f64>> {
+            vec![Port::new("out").with_kind(self.0)]
+        }
+
+        fn process(&self, node: &Node<f64>, _inputs: &NodeInputs<f64>) -> NodeResult<f64> {
+            Ok(NodeOutputs::new().with("out", node.data))
+        }
+    }
+
+    #[test]
+    fn connections_between_different_kinds_are_rejected() {
+        let engine: DataflowEngine<f64, f64> = DataflowEngine::new()
+            .with_processor("number", Typed("number"))
+            .with_processor("text", Typed("text"));
+        let nodes = vec![
+            node("n1", "number", 1.0),
+            node("n2", "number", 2.0),
+            node("t", "text", 0.0),
+        ];
+        let connection =
+            |source: &str, source_handle: &str, target: &str, target_handle: &str| Connection {
+                source: source.to_string(),
+                target: target.to_string(),
+                source_handle: Some(source_handle.to_string()),
+                target_handle: Some(target_handle.to_string()),
+            };
+        assert!(engine.is_valid_connection(&connection("n1", "out", "n2", "in"), &nodes));
+        assert!(!engine.is_valid_connection(&connection("n1", "out", "t", "in"), &nodes));
+        assert!(!engine.is_valid_connection(&connection("n1", "missing", "n2", "in"), &nodes));
+
+        let mut engine = engine;
+        let edges = vec![
+            Edge::new("e", "n1", "t")
+                .with_source_handle("out")
+                .with_target_handle("in"),
+        ];
+        evaluate(&mut engine, &nodes, &edges);
+        assert_eq!(
+            engine.error("t"),
+            Some(&DataflowError::TypeMismatch {
+                port: "in".to_string(),
+                expected: "text".to_string(),
+            })
+        );
+    }
+}
@@ -0,0 +1,156 @@
+//! Running the engine alongside the flow
+
+use super::{DataflowEngine, NodeProcessor, NodeResult};
+use crate::state::FlowState;
+use crate::types::{Connection, Edge, Node};
+use dioxus::prelude::*;
+use dioxus::prelude::{ReadableExt, WritableExt};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+
+/// Latest results by node id, provided to descendants by `use_dataflow`
+pub struct DataflowResults<V: 'static>(pub Signal<HashMap<String, NodeResult<V>>>);
+
+impl<V: 'static> Clone for DataflowResults<V> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<V: 'static> Copy for DataflowResults<V> {}
+
+/// Handle to the engine started by `use_dataflow`. Cloning returns a handle to
+/// the same engine.
+pub struct Dataflow<N: Clone + PartialEq + Default + 'static, V: Clone + PartialEq + 'static> {
+    engine: Rc<RefCell<DataflowEngine<N, V>>>,
+    nodes: Signal<Vec<Node<N>>>,
+    results: Signal<HashMap<String, NodeResult<V>>>,
+    /// Bumped to re-run the evaluation effect without a graph change
+    revision: Signal<u64>,
+}
+
+impl<N, V> Clone for Dataflow<N, V>
+where
+    N: Clone + PartialEq + Default + 'static,
+    V: Clone + PartialEq + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            engine: self.engine.clone(),
+            nodes: self.nodes,
+            results: self.results,
+            revision: self.revision,
+        }
+    }
+}
+
+impl<N, V> Dataflow<N, V>
+where
+    N: Clone + PartialEq + Default + 'static,
+    V: Clone + PartialEq + 'static,
+{
+    pub fn result(&self, node_id: &str) -> Option<NodeResult<V>> {
+        self.results.read().get(node_id).cloned()
+    }
+
+    pub fn results(&self) -> Signal<HashMap<String, NodeResult<V>>> {
+        self.results
+    }
+
+    /// Evaluates `node_id` and everything downstream again
+    pub fn mark_dirty(&mut self, node_id: impl Into<String>) {
+        self.engine.borrow_mut().mark_dirty(node_id);
+        *self.revision.write() += 1;
+    }
+
+    /// Evaluates every node again
+    pub fn invalidate(&mut self) {
+        self.engine.borrow_mut().invalidate();
+        *self.revision.write() += 1;
+    }
+
+    pub fn set_processor(
+        &mut self,
+        node_type: impl Into<String>,
+        processor: impl NodeProcessor<N, V> + 'static,
+    ) {
+        self.engine.borrow_mut().set_processor(node_type, processor);
+        *self.revision.write() += 1;
+    }
+
+    /// See `DataflowEngine::is_valid_connection`
+    pub fn is_valid_connection(&self, connection: &Connection) -> bool {
+        self.engine
+            .borrow()
+            .is_valid_connection(connection, &self.nodes.peek())
+    }
+}
+
+/// Evaluates `nodes` and `edges` with the engine from `init` whenever they
+/// change, and provides the results to `use_node_result` in descendants. Pass
+/// the signals given to `DioxusFlow`, or a `FlowState`'s `nodes` and `edges`;
+/// below a `FlowProvider`, the latter reuse its cached `GraphIndex`.
+pub fn use_dataflow<N, E, V>(
+    nodes: Signal<Vec<Node<N>>>,
+    edges: Signal<Vec<Edge<E>>>,
+    init: impl FnOnce() -> DataflowEngine<N, V>,
+) -> Dataflow<N, V>
+where
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+    V: Clone + PartialEq + 'static,
+{
+    let engine = use_hook(|| Rc::new(RefCell::new(init())));
+    let mut results = use_signal(HashMap::<String, NodeResult<V>>::new);
+    let revision = use_signal(|| 0u64);
+    use_context_provider(|| DataflowResults(results));
+    // The flow's cached graph index, when evaluating its own nodes and edges
+    let flow_state = try_use_context::<FlowState<N, E>>()
+        .filter(|state| state.nodes == nodes && state.edges == edges);
+
+    let engine_evaluate = engine.clone();
+    use_effect(move || {
+        let _ = revision.read();
+        let nodes = nodes.read();
+        let pass = match &flow_state {
+            Some(state) => engine_evaluate
+                .borrow_mut()
+                .evaluate_indexed(&nodes, &state.graph_index()),
+            None => engine_evaluate.borrow_mut().evaluate(&nodes, &edges.read()),
+        };
+        if pass.changed.is_empty() {
+            return;
+        }
+        let engine = engine_evaluate.borrow();
+        let mut entries = results.write();
+        for id in pass.changed {
+            match engine.result(&id) {
+                Some(result) => entries.insert(id, result.clone()),
+                None => entries.remove(&id),
+            };
+        }
+    });
+
+    Dataflow {
+        engine,
+        nodes,
+        results,
+        revision,
+    }
+}
+
+/// Outputs or error of `node_id`, for node components below `use_dataflow`.
+/// None until the node has been evaluated.
+pub fn use_node_result<V>(node_id: &str) -> Option<NodeResult<V>>
+where
+    V: Clone + PartialEq + 'static,
+{
+    let results = use_context::<DataflowResults<V>>();
+    let mut id = use_signal(|| node_id.to_string());
+    if *id.peek() != node_id {
+        id.set(node_id.to_string());
+    }
+    let result = use_memo(move || results.0.read().get(&*id.read()).cloned());
+    result.read().clone()
+}
@@ -0,0 +1,11 @@
+//! Dataflow evaluation: node processors computing outputs from their inputs
+
+mod engine;
+mod hooks;
+mod port;
+mod processor;
+
+pub use engine::*;
+pub use hooks::*;
+pub use port::*;
+pub use processor::*;
@@ -0,0 +1,152 @@
+//! Processor ports and the values flowing through them
+
+use super::DataflowError;
+use std::collections::HashMap;
+
+/// Port id of edges and handles without a handle id
+pub const DEFAULT_PORT: &str = "";
+
+/// Port an edge endpoint attaches to
+pub(crate) fn port_id(handle: Option<&str>) -> &str {
+    handle.unwrap_or(DEFAULT_PORT)
+}
+
+/// An input or output of a processor, keyed by handle id
+#[derive(Clone, PartialEq, Debug)]
+pub struct Port<V> {
+    /// Handle id, `DEFAULT_PORT` for a handle without one
+    pub id: String,
+    /// Value type, only ports of the same kind (or without one) connect
+    pub kind: Option<String>,
+    /// Inputs only: evaluation fails while the port has no value
+    pub required: bool,
+    /// Inputs only: value used while nothing is connected
+    pub default: Option<V>,
+}
+
+impl<V> Port<V> {
+    pub fn new(id: impl Into<String>) -> Self {
+        Self {
+            id: id.into(),
+            kind: None,
+            required: false,
+            default: None,
+        }
+    }
+
+    pub fn with_kind(mut self, kind: impl Into<String>) -> Self {
+        self.kind = Some(kind.into());
+        self
+    }
+
+    pub fn with_required(mut self, required: bool) -> Self {
+        self.required = required;
+        self
+    }
+
+    pub fn with_default(mut self, value: V) -> Self {
+        self.default = Some(value);
+        self
+    }
+}
+
+/// Values arriving at a node's input ports. A port connected to several
+/// edges receives one value per edge, in edge order.
+#[derive(Clone, PartialEq, Debug)]
+pub struct NodeInputs<V> {
+    values: HashMap<String, Vec<V>>,
+}
+
+impl<V> Default for NodeInputs<V> {
+    fn default() -> Self {
+        Self {
+            values: HashMap::new(),
+        }
+    }
+}
+
+impl<V> NodeInputs<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn push(&mut self, port: impl Into<String>, value: V) {
+        self.values.entry(port.into()).or_default().push(value);
+    }
+
+    /// First value at `port`
+    pub fn get(&self, port: &str) -> Option<&V> {
+        self.values.get(port).and_then(|values| values.first())
+    }
+
+    /// First value at `port`, or `DataflowError::MissingInput`
+    pub fn require(&self, port: &str) -> Result<&V, DataflowError> {
+        self.get(port)
+            .ok_or_else(|| DataflowError::MissingInput(port.to_string()))
+    }
+
+    /// Every value at `port`
+    pub fn all(&self, port: &str) -> &[V] {
+        self.values.get(port).map_or(&[], Vec::as_slice)
+    }
+
+    pub fn contains(&self, port: &str) -> bool {
+        self.values
+            .get(port)
+            .is_some_and(|values| !values.is_empty())
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &[V])> {
+        self.values
+            .iter()
+            .map(|(port, values)| (port.as_str(), values.as_slice()))
+    }
+}
+
+/// Values a node produced, by output port
+#[derive(Clone, PartialEq, Debug)]
+pub struct NodeOutputs<V> {
+    values: HashMap<String, V>,
+}
+
+impl<V> Default for NodeOutputs<V> {
+    fn default() -> Self {
+        Self {
+            values: HashMap::new(),
+        }
+    }
+}
+
+impl<V> NodeOutputs<V> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// A single value on `DEFAULT_PORT`
+    pub fn single(value: V) -> Self {
+        Self::new().with(DEFAULT_PORT, value)
+    }
+
+    pub fn with(mut self, port: impl Into<String>, value: V) -> Self {
+        self.set(port, value);
+        self
+    }
+
+    pub fn set(&mut self, port: impl Into<String>, value: V) {
+        self.values.insert(port.into(), value);
+    }
+
+    pub fn get(&self, port: &str) -> Option<&V> {
+        self.values.get(port)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.values.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &V)> {
+        self.values
+            .iter()
+            .map(|(port, value)| (port.as_str(), value))
+    }
+}
@@ -0,0 +1,68 @@
+//! Node processors
+
+use super::{NodeInputs, NodeOutputs, Port};
+use crate::types::Node;
+use std::fmt;
+
+/// Why a node has no outputs
+#[derive(Clone, PartialEq, Debug)]
+pub enum DataflowError {
+    /// No processor is registered for the node type
+    NoProcessor(String),
+    /// A required input port has no value
+    MissingInput(String),
+    /// An input port received a value of the wrong type
+    TypeMismatch { port: String, expected: String },
+    /// A node feeding this one failed
+    Upstream(String),
+    /// The node is on a cycle
+    Cycle,
+    /// Reported by the processor
+    Failed(String),
+}
+
+impl fmt::Display for DataflowError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::NoProcessor(node_type) => write!(f, "no processor for node type `{node_type}`"),
+            Self::MissingInput(port) => write!(f, "input `{port}` is not connected"),
+            Self::TypeMismatch { port, expected } => {
+                write!(f, "input `{port}` expects {expected}")
+            }
+            Self::Upstream(node) => write!(f, "upstream node `{node}` failed"),
+            Self::Cycle => write!(f, "node is part of a cycle"),
+            Self::Failed(message) => f.write_str(message),
+        }
+    }
+}
+
+impl std::error::Error for DataflowError {}
+
+/// Outcome of evaluating one node
+pub type NodeResult<V> = Result<NodeOutputs<V>, DataflowError>;
+
+/// Computes a node type's outputs from its inputs. Register one per node type
+/// with `DataflowEngine::with_processor`.
+pub trait NodeProcessor<N: Clone + PartialEq + Default, V> {
+    /// Input ports; any handle is accepted when empty
+    fn inputs(&self, _node: &Node<N>) -> Vec<Port<V>> {
+        Vec::new()
+    }
+
+    /// Output ports, used to check connections
+    fn outputs(&self, _node: &Node<N>) -> Vec<Port<V>> {
+        Vec::new()
+    }
+
+    fn process(&self, node: &Node<N>, inputs: &NodeInputs<V>) -> NodeResult<V>;
+}
+
+impl<N, V, F> NodeProcessor<N, V> for F
+where
+    N: Clone + PartialEq + Default,
+    F: Fn(&Node<N>, &NodeInputs<V>) -> NodeResult<V>,
+{
+    fn process(&self, node: &Node<N>, inputs: &NodeInputs<V>) -> NodeResult<V> {
+        self(node, inputs)
+    }
+}
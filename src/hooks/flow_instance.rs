@@ -1,7 +1,14 @@
 //! Flow instance helper
 
-use crate::state::FlowState;
-use crate::types::{FitBoundsOptions, FitViewOptions, Rect, SetCenterOptions, XYPosition};
+use crate::journal::{Journal, JournalPlayer};
+use crate::state::{FlowState, NodeSearch, NodeSearchOptions};
+use crate::types::{
+    ConnectionEndEvent, Diagnostic, Diagnostics, Edge, FitBoundsOptions, FitViewOptions, Node,
+    PulseOptions, Rect, SetCenterOptions, XYPosition,
+};
+use crate::utils::{GraphIndex, GraphPath};
+use dioxus::prelude::ReadableExt;
+use std::rc::Rc;
 
 #[derive(Clone)]
 pub struct FlowInstance<
@@ -47,4 +54,95 @@ where
     pub fn flow_to_screen_position(&self, position: XYPosition) -> XYPosition {
         self.state.flow_to_screen_position(position)
     }
+
+    pub fn add_connected_node(
+        &mut self,
+        event: &ConnectionEndEvent,
+        node: Node<N>,
+    ) -> Option<Edge<E>> {
+        self.state.add_connected_node(event, node)
+    }
+
+    /// Sends a one-shot token from source to target along an edge
+    pub fn pulse_edge(&mut self, edge_id: impl Into<String>, options: PulseOptions) -> u64 {
+        self.state.pulse_edge(edge_id, options)
+    }
+
+    /// Cached adjacency index of the current graph
+    pub fn graph_index(&self) -> Rc<GraphIndex> {
+        self.state.graph_index()
+    }
+
+    pub fn weighted_shortest_path(
+        &self,
+        from: &str,
+        to: &str,
+        weight: impl Fn(&Edge<E>) -> f64,
+    ) -> Option<GraphPath> {
+        self.state.weighted_shortest_path(from, to, weight)
+    }
+
+    /// Highlights the given node and edge ids and dims everything else,
+    /// overriding the built-in highlight mode until cleared
+    pub fn highlight(&mut self, ids: impl IntoIterator<Item = impl Into<String>>) {
+        self.state.set_highlight(ids);
+    }
+
+    pub fn clear_highlight(&mut self) {
+        self.state.clear_highlight();
+    }
+
+    /// Latest validator diagnostics
+    pub fn diagnostics(&self) -> Diagnostics {
+        self.state.diagnostics.read().clone()
+    }
+
+    /// Focuses and centers the element a diagnostic is attached to
+    pub fn focus_diagnostic(&mut self, diagnostic: &Diagnostic) {
+        self.state.focus_diagnostic(diagnostic);
+    }
+
+    /// Visible nodes matching `predicate`, top to bottom then left to right
+    pub fn find_nodes(&self, predicate: impl Fn(&Node<N>) -> bool) -> Vec<Node<N>> {
+        self.state.find_nodes(predicate)
+    }
+
+    /// Navigator over the nodes matching `predicate`; call `next_match` to focus the first
+    pub fn search(
+        &self,
+        predicate: impl Fn(&Node<N>) -> bool,
+        options: Option<NodeSearchOptions>,
+    ) -> NodeSearch<N, E> {
+        let matches = self
+            .state
+            .find_nodes(predicate)
+            .into_iter()
+            .map(|node| node.id)
+            .collect();
+        NodeSearch::new(self.state.clone(), matches, options.unwrap_or_default())
+    }
+
+    /// Starts recording applied changes and viewport moves
+    pub fn start_recording(&mut self) {
+        self.state.start_recording();
+    }
+
+    /// Stops recording and returns the journal
+    pub fn stop_recording(&mut self) -> Option<Journal<N, E>> {
+        self.state.stop_recording()
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.state.is_recording()
+    }
+
+    /// Journal recorded so far, without stopping
+    pub fn recorded_journal(&self) -> Option<Journal<N, E>> {
+        self.state.recorded_journal()
+    }
+
+    /// Loads a journal for replay, rewinding the flow to its start
+    pub fn journal_player(&self, journal: Journal<N, E>) -> JournalPlayer<N, E> {
+        JournalPlayer::new(self.state.clone(), journal)
+    }
 }
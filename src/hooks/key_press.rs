@@ -1,50 +1,9 @@
 //! Key press hook
 
+use crate::utils::browser::WindowListener;
 use dioxus::prelude::*;
 use std::collections::HashSet;
 use std::rc::Rc;
-use wasm_bindgen::JsCast;
-use wasm_bindgen::closure::Closure;
-
-struct WindowListener {
-    event_type: String,
-    closure: Option<Closure<dyn FnMut(web_sys::KeyboardEvent)>>,
-}
-
-impl WindowListener {
-    fn new(event_type: &str, handler: impl FnMut(web_sys::KeyboardEvent) + 'static) -> Self {
-        let Some(window) = web_sys::window() else {
-            return Self {
-                event_type: event_type.to_string(),
-                closure: None,
-            };
-        };
-        let closure = Closure::wrap(Box::new(handler) as Box<dyn FnMut(web_sys::KeyboardEvent)>);
-        window
-            .add_event_listener_with_callback(event_type, closure.as_ref().unchecked_ref())
-            .ok();
-
-        Self {
-            event_type: event_type.to_string(),
-            closure: Some(closure),
-        }
-    }
-}
-
-impl Drop for WindowListener {
-    fn drop(&mut self) {
-        if let Some(window) = web_sys::window() {
-            if let Some(closure) = &self.closure {
-                window
-                    .remove_event_listener_with_callback(
-                        &self.event_type,
-                        closure.as_ref().unchecked_ref(),
-                    )
-                    .ok();
-            }
-        }
-    }
-}
 
 pub fn use_key_press(key: impl Into<String>) -> Signal<bool> {
     let key = key.into();
@@ -63,21 +22,21 @@ pub fn use_key_press(key: impl Into<String>) -> Signal<bool> {
         let key_down = tracked_key;
         let key_up = tracked_key;
 
-        let listener_down = WindowListener::new("keydown", move |evt| {
+        let listener_down = WindowListener::new("keydown", move |evt: web_sys::KeyboardEvent| {
             if evt.key() == *key_down.read() {
                 pressed_down.set(true);
             }
         });
 
-        let listener_up = WindowListener::new("keyup", move |evt| {
+        let listener_up = WindowListener::new("keyup", move |evt: web_sys::KeyboardEvent| {
             if evt.key() == *key_up.read() {
                 pressed_up.set(false);
             }
         });
 
         struct Cleanup {
-            _down: WindowListener,
-            _up: WindowListener,
+            _down: WindowListener<web_sys::KeyboardEvent>,
+            _up: WindowListener<web_sys::KeyboardEvent>,
         }
 
         Rc::new(Cleanup {
@@ -108,7 +67,7 @@ pub fn use_key_press_multi(keys: Vec<String>) -> Signal<bool> {
         let keys_down = tracked_keys;
         let keys_up = tracked_keys;
 
-        let listener_down = WindowListener::new("keydown", move |evt| {
+        let listener_down = WindowListener::new("keydown", move |evt: web_sys::KeyboardEvent| {
             if keys_down.read().iter().any(|key| key == &evt.key()) {
                 let mut set = pressed_keys_down.write();
                 set.insert(evt.key());
@@ -116,7 +75,7 @@ pub fn use_key_press_multi(keys: Vec<String>) -> Signal<bool> {
             }
         });
 
-        let listener_up = WindowListener::new("keyup", move |evt| {
+        let listener_up = WindowListener::new("keyup", move |evt: web_sys::KeyboardEvent| {
             if keys_up.read().iter().any(|key| key == &evt.key()) {
                 let mut set = pressed_keys_up.write();
                 set.remove(&evt.key());
@@ -125,8 +84,8 @@ pub fn use_key_press_multi(keys: Vec<String>) -> Signal<bool> {
         });
 
         struct Cleanup {
-            _down: WindowListener,
-            _up: WindowListener,
+            _down: WindowListener<web_sys::KeyboardEvent>,
+            _up: WindowListener<web_sys::KeyboardEvent>,
         }
 
         Rc::new(Cleanup {
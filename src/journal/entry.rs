@@ -0,0 +1,73 @@
+//! Serializable journal types
+
+use crate::types::{Edge, EdgeChange, Node, NodeChange, Viewport};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+
+/// Something that happened to the flow
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum JournalEvent<N: Clone + PartialEq + Default = (), E: Clone + PartialEq + Default = ()> {
+    /// Full state the following entries build on
+    Snapshot {
+        nodes: Vec<Node<N>>,
+        edges: Vec<Edge<E>>,
+        viewport: Viewport,
+    },
+    NodeChanges(Vec<NodeChange<N>>),
+    EdgeChanges(Vec<EdgeChange<E>>),
+    Viewport(Viewport),
+}
+
+/// Journal event with the time it happened at
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct JournalEntry<N: Clone + PartialEq + Default = (), E: Clone + PartialEq + Default = ()> {
+    /// Milliseconds since recording started
+    pub time: f64,
+    pub event: JournalEvent<N, E>,
+}
+
+/// Timestamped record of how a flow changed, ordered by time
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct Journal<N: Clone + PartialEq + Default = (), E: Clone + PartialEq + Default = ()> {
+    pub entries: Vec<JournalEntry<N, E>>,
+}
+
+impl<N: Clone + PartialEq + Default, E: Clone + PartialEq + Default> Journal<N, E> {
+    pub fn new() -> Self {
+        Self {
+            entries: Vec::new(),
+        }
+    }
+
+    /// Time of the last entry in milliseconds
+    pub fn duration(&self) -> f64 {
+        self.entries.last().map_or(0.0, |entry| entry.time)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+
+    /// Number of entries at or before `time`
+    pub fn entries_until(&self, time: f64) -> usize {
+        self.entries.partition_point(|entry| entry.time <= time)
+    }
+}
+
+impl<N, E> Journal<N, E>
+where
+    N: Clone + PartialEq + Default + Serialize + DeserializeOwned,
+    E: Clone + PartialEq + Default + Serialize + DeserializeOwned,
+{
+    pub fn to_json(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string(self)
+    }
+
+    pub fn from_json(json: &str) -> Result<Self, serde_json::Error> {
+        serde_json::from_str(json)
+    }
+}
@@ -0,0 +1,9 @@
+//! Change journal: recording applied changes and replaying them
+
+mod entry;
+mod player;
+mod recorder;
+
+pub use entry::*;
+pub use player::*;
+pub use recorder::*;
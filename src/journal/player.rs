@@ -0,0 +1,431 @@
+//! Journal playback
+
+use super::{Journal, JournalEvent};
+use crate::state::FlowState;
+use crate::types::{Edge, Node, Viewport};
+use dioxus::prelude::{ReadableExt, WritableExt};
+use std::cell::RefCell;
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+use wasm_bindgen::closure::Closure;
+
+/// Progress of the player driving a flow, published in `FlowState::playback`
+#[derive(Clone, Copy, PartialEq, Debug, Default)]
+pub struct PlaybackStatus {
+    /// Current journal time in milliseconds
+    pub time: f64,
+    pub duration: f64,
+    pub speed: f64,
+    pub playing: bool,
+    /// Entries applied so far
+    pub position: usize,
+    pub len: usize,
+}
+
+struct PlayerInner<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+> {
+    state: FlowState<N, E>,
+    journal: Journal<N, E>,
+    /// Flow state before the first entry
+    base: JournalEvent<N, E>,
+    position: usize,
+    time: f64,
+    speed: f64,
+    playing: bool,
+    generation: u64,
+}
+
+/// Replays a journal onto a flow. Cloning returns a handle to the same player.
+///
+/// Replayed changes are written to the flow state directly, without calling
+/// change handlers or being recorded. Only one player should drive a flow.
+pub struct JournalPlayer<
+    N: Clone + PartialEq + Default + 'static = (),
+    E: Clone + PartialEq + Default + 'static = (),
+> {
+    inner: Rc<RefCell<PlayerInner<N, E>>>,
+}
+
+impl<N, E> Clone for JournalPlayer<N, E>
+where
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+{
+    fn clone(&self) -> Self {
+        Self {
+            inner: self.inner.clone(),
+        }
+    }
+}
+
+impl<N, E> JournalPlayer<N, E>
+where
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+{
+    /// Loads `journal` and rewinds the flow to its start
+    pub fn new(state: FlowState<N, E>, journal: Journal<N, E>) -> Self {
+        let base = JournalEvent::Snapshot {
+            nodes: state.nodes.peek().clone(),
+            edges: state.edges.peek().clone(),
+            viewport: *state.viewport.peek(),
+        };
+        let player = Self {
+            inner: Rc::new(RefCell::new(PlayerInner {
+                state,
+                journal,
+                base,
+                position: 0,
+                time: 0.0,
+                speed: 1.0,
+                playing: false,
+                generation: 0,
+            })),
+        };
+        player.seek(0.0);
+        player
+    }
+
+    /// Plays from the current time, restarting if the end was reached
+    pub fn play(&self) {
+        let at_end = {
+            let inner = self.inner.borrow();
+            if inner.playing {
+                return;
+            }
+            inner.position >= inner.journal.len()
+        };
+        if at_end {
+            self.seek(0.0);
+        }
+        let generation = {
+            let mut inner = self.inner.borrow_mut();
+            inner.playing = true;
+            inner.generation += 1;
+            inner.generation
+        };
+        self.publish();
+        self.run_loop(generation);
+    }
+
+    pub fn pause(&self) {
+        self.inner.borrow_mut().playing = false;
+        self.publish();
+    }
+
+    pub fn is_playing(&self) -> bool {
+        self.inner.borrow().playing
+    }
+
+    /// Sets the playback rate, 1.0 is real time
+    pub fn set_speed(&self, speed: f64) {
+        if speed.is_finite() && speed > 0.0 {
+            self.inner.borrow_mut().speed = speed;
+            self.publish();
+        }
+    }
+
+    /// Shows the flow as it was `time` milliseconds into the journal
+    pub fn seek(&self, time: f64) {
+        let (count, time) = {
+            let inner = self.inner.borrow();
+            let time = time.clamp(0.0, inner.journal.duration());
+            (inner.journal.entries_until(time), time)
+        };
+        self.rewind_to(count);
+        self.inner.borrow_mut().time = time;
+        self.publish();
+    }
+
+    /// Applies the next entry and pauses, returns false at the end
+    pub fn step(&self) -> bool {
+        let next = {
+            let mut inner = self.inner.borrow_mut();
+            inner.playing = false;
+            let next = inner.journal.entries.get(inner.position).cloned();
+            if let Some(entry) = &next {
+                inner.position += 1;
+                inner.time = entry.time;
+            }
+            next
+        };
+        if let Some(entry) = &next {
+            self.apply(vec![entry.event.clone()]);
+        }
+        self.publish();
+        next.is_some()
+    }
+
+    /// Undoes the last applied entry and pauses, returns false at the start
+    pub fn step_back(&self) -> bool {
+        let position = {
+            let mut inner = self.inner.borrow_mut();
+            inner.playing = false;
+            inner.position
+        };
+        if position == 0 {
+            self.publish();
+            return false;
+        }
+        self.rewind_to(position - 1);
+        let mut inner = self.inner.borrow_mut();
+        inner.time = match position {
+            1 => 0.0,
+            _ => inner.journal.entries[position - 2].time,
+        };
+        drop(inner);
+        self.publish();
+        true
+    }
+
+    /// Pauses and stops publishing `FlowState::playback`, leaving the flow as is
+    pub fn stop(&self) {
+        let mut state = {
+            let mut inner = self.inner.borrow_mut();
+            inner.playing = false;
+            inner.state.clone()
+        };
+        state.playback.set(None);
+    }
+
+    pub fn status(&self) -> PlaybackStatus {
+        let inner = self.inner.borrow();
+        PlaybackStatus {
+            time: inner.time,
+            duration: inner.journal.duration(),
+            speed: inner.speed,
+            playing: inner.playing,
+            position: inner.position,
+            len: inner.journal.len(),
+        }
+    }
+
+    pub fn time(&self) -> f64 {
+        self.inner.borrow().time
+    }
+
+    pub fn duration(&self) -> f64 {
+        self.inner.borrow().journal.duration()
+    }
+
+    pub fn journal(&self) -> Journal<N, E> {
+        self.inner.borrow().journal.clone()
+    }
+
+    /// Restores the base state and applies the first `count` entries
+    fn rewind_to(&self, count: usize) {
+        let events = {
+            let mut inner = self.inner.borrow_mut();
+            inner.position = count;
+            std::iter::once(inner.base.clone())
+                .chain(
+                    inner.journal.entries[..count]
+                        .iter()
+                        .map(|entry| entry.event.clone()),
+                )
+                .collect()
+        };
+        self.apply(events);
+    }
+
+    /// Folds the events and writes the result to the flow once
+    fn apply(&self, events: Vec<JournalEvent<N, E>>) {
+        let mut state = self.inner.borrow().state.clone();
+        let mut nodes: Option<Vec<Node<N>>> = None;
+        let mut edges: Option<Vec<Edge<E>>> = None;
+        let mut viewport: Option<Viewport> = None;
+        for event in events {
+            match event {
+                JournalEvent::Snapshot {
+                    nodes: snapshot_nodes,
+                    edges: snapshot_edges,
+                    viewport: snapshot_viewport,
+                } => {
+                    nodes = Some(snapshot_nodes);
+                    edges = Some(snapshot_edges);
+                    viewport = Some(snapshot_viewport);
+                }
+                JournalEvent::NodeChanges(changes) => {
+                    let current = nodes.take().unwrap_or_else(|| state.nodes.peek().clone());
+                    nodes = Some(crate::types::apply_node_changes(changes, current));
+                }
+                JournalEvent::EdgeChanges(changes) => {
+                    let current = edges.take().unwrap_or_else(|| state.edges.peek().clone());
+                    edges = Some(crate::types::apply_edge_changes(changes, current));
+                }
+                JournalEvent::Viewport(next) => viewport = Some(next),
+            }
+        }
+        state.replay(nodes, edges, viewport);
+    }
+
+    /// Advances playback by `elapsed` real milliseconds, returns whether to continue
+    fn advance(&self, generation: u64, elapsed: f64) -> bool {
+        let events = {
+            let mut inner = self.inner.borrow_mut();
+            if !inner.playing || inner.generation != generation {
+                return false;
+            }
+            inner.time = (inner.time + elapsed * inner.speed).min(inner.journal.duration());
+            let count = inner.journal.entries_until(inner.time).max(inner.position);
+            let events: Vec<JournalEvent<N, E>> = inner.journal.entries[inner.position..count]
+                .iter()
+                .map(|entry| entry.event.clone())
+                .collect();
+            inner.position = count;
+            if count >= inner.journal.len() {
+                inner.playing = false;
+            }
+            events
+        };
+        if !events.is_empty() {
+            self.apply(events);
+        }
+        self.publish();
+        self.inner.borrow().playing
+    }
+
+    fn publish(&self) {
+        let status = self.status();
+        let mut state = self.inner.borrow().state.clone();
+        if *state.playback.peek() != Some(status) {
+            state.playback.set(Some(status));
+        }
+    }
+
+    fn run_loop(&self, generation: u64) {
+        let Some(window) = crate::utils::browser::window() else {
+            self.pause();
+            self.inner
+                .borrow()
+                .state
+                .report_error("window not available for journal playback");
+            return;
+        };
+        let player = self.clone();
+        let mut last_frame: Option<f64> = None;
+
+        let raf: FrameCallback = Rc::new(RefCell::new(None));
+        let raf_clone = raf.clone();
+        let raf_loop = raf.clone();
+        *raf_clone.borrow_mut() = Some(Closure::wrap(Box::new(move |now: f64| {
+            let elapsed = last_frame.map_or(0.0, |last| now - last);
+            last_frame = Some(now);
+            let keep_going = player.advance(generation, elapsed);
+
+            let callback = raf_loop.borrow();
+            match (keep_going, crate::utils::browser::window(), callback.as_ref()) {
+                (true, Some(window), Some(callback)) => {
+                    let _ = window.request_animation_frame(callback.as_ref().unchecked_ref());
+                }
+                _ => {
+                    drop(callback);
+                    raf_loop.borrow_mut().take();
+                }
+            }
+        })));
+
+        if let Some(callback) = raf_clone.borrow().as_ref() {
+            let _ = window.request_animation_frame(callback.as_ref().unchecked_ref());
+        }
+    }
+}
+
+/// Self-rescheduling `requestAnimationFrame` callback
+type FrameCallback = Rc<RefCell<Option<Closure<dyn FnMut(f64)>>>>;
+
+#[cfg(all(test, feature = "testing"))]
+mod tests {
+    use super::*;
+    use crate::testing::FlowHarness;
+    use crate::types::XYPosition;
+
+    type Frame = (usize, Vec<Node>, Vec<Edge>, Viewport);
+
+    fn frame(harness: &FlowHarness) -> Frame {
+        let state = harness.state();
+        let len = state.recorded_journal().map_or(0, |journal| journal.len());
+        (len, harness.nodes(), harness.edges(), harness.viewport())
+    }
+
+    /// Records a few edits, returning the journal with one entry every 10ms
+    /// and the flow after each edit
+    fn record() -> (FlowHarness, Journal, Vec<Frame>) {
+        let nodes = vec![
+            Node::new("a", XYPosition::new(0.0, 0.0)),
+            Node::new("b", XYPosition::new(200.0, 0.0)),
+        ];
+        let mut harness = FlowHarness::<(), ()>::new(nodes, Vec::new());
+        harness.update(|state| state.start_recording());
+        let mut frames = vec![frame(&harness)];
+
+        harness.update(|state| state.set_edges(vec![Edge::new("e1", "a", "b")]));
+        frames.push(frame(&harness));
+        harness.update(|state| {
+            let mut nodes = state.nodes.peek().clone();
+            nodes[0].position = XYPosition::new(40.0, 20.0);
+            nodes.push(Node::new("c", XYPosition::new(400.0, 0.0)));
+            state.set_nodes(nodes);
+        });
+        frames.push(frame(&harness));
+        harness.update(|state| {
+            let mut viewport = *state.viewport.peek();
+            viewport.x += 50.0;
+            state.set_viewport(viewport, None);
+        });
+        frames.push(frame(&harness));
+        // Reordering is recorded as a snapshot
+        harness.update(|state| {
+            let mut nodes = state.nodes.peek().clone();
+            nodes.reverse();
+            state.set_nodes(nodes);
+        });
+        frames.push(frame(&harness));
+        harness.update(|state| state.set_edges(Vec::new()));
+        frames.push(frame(&harness));
+
+        let mut journal = harness.update(|state| state.stop_recording()).unwrap();
+        for (index, entry) in journal.entries.iter_mut().enumerate() {
+            entry.time = index as f64 * 10.0;
+        }
+        (harness, journal, frames)
+    }
+
+    fn assert_frame(harness: &FlowHarness, expected: &Frame) {
+        let (_, nodes, edges, viewport) = frame(harness);
+        assert_eq!(nodes, expected.1, "nodes after {} entries", expected.0);
+        assert_eq!(edges, expected.2, "edges after {} entries", expected.0);
+        assert_eq!(viewport, expected.3, "viewport after {} entries", expected.0);
+    }
+
+    #[test]
+    fn seeking_restores_each_recorded_state() {
+        let (mut harness, journal, frames) = record();
+        assert_eq!(frames.last().unwrap().0, journal.len());
+        let player = harness.update(|state| JournalPlayer::new(state.clone(), journal));
+        for expected in frames.iter().rev() {
+            let time = (expected.0 - 1) as f64 * 10.0;
+            harness.update(|_| player.seek(time));
+            assert_eq!(player.status().position, expected.0);
+            assert_frame(&harness, expected);
+        }
+    }
+
+    #[test]
+    fn stepping_back_undoes_entries() {
+        let (mut harness, journal, frames) = record();
+        let len = journal.len();
+        let player = harness.update(|state| JournalPlayer::new(state.clone(), journal));
+        harness.update(|_| player.seek(f64::MAX));
+        assert_eq!(player.status().position, len);
+        for position in (1..len).rev() {
+            assert!(harness.update(|_| player.step_back()));
+            assert_eq!(player.status().position, position);
+            if let Some(expected) = frames.iter().find(|frame| frame.0 == position) {
+                assert_frame(&harness, expected);
+            }
+        }
+    }
+}
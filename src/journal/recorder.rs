@@ -0,0 +1,192 @@
+//! Journal recording
+
+use super::{Journal, JournalEntry, JournalEvent};
+use crate::types::{Edge, EdgeChange, Node, NodeChange, Viewport};
+use std::collections::{HashMap, HashSet};
+
+/// Appends events to a journal, timed from when recording started
+#[derive(Clone, PartialEq, Debug)]
+pub struct JournalRecorder<N: Clone + PartialEq + Default = (), E: Clone + PartialEq + Default = ()>
+{
+    started_at: f64,
+    journal: Journal<N, E>,
+    last_viewport: Viewport,
+}
+
+impl<N: Clone + PartialEq + Default, E: Clone + PartialEq + Default> JournalRecorder<N, E> {
+    /// Starts a journal with a snapshot of the current state
+    pub fn new(nodes: Vec<Node<N>>, edges: Vec<Edge<E>>, viewport: Viewport) -> Self {
+        let mut journal = Journal::new();
+        journal.entries.push(JournalEntry {
+            time: 0.0,
+            event: JournalEvent::Snapshot {
+                nodes,
+                edges,
+                viewport,
+            },
+        });
+        Self {
+            started_at: crate::utils::browser::now(),
+            journal,
+            last_viewport: viewport,
+        }
+    }
+
+    pub fn record(&mut self, event: JournalEvent<N, E>) {
+        match &event {
+            JournalEvent::NodeChanges(changes) if changes.is_empty() => return,
+            JournalEvent::EdgeChanges(changes) if changes.is_empty() => return,
+            JournalEvent::Viewport(viewport) if *viewport == self.last_viewport => return,
+            JournalEvent::Viewport(viewport) => self.last_viewport = *viewport,
+            _ => {}
+        }
+        let time = (crate::utils::browser::now() - self.started_at).max(self.journal.duration());
+        self.journal.entries.push(JournalEntry { time, event });
+    }
+
+    pub fn journal(&self) -> &Journal<N, E> {
+        &self.journal
+    }
+
+    pub fn finish(self) -> Journal<N, E> {
+        self.journal
+    }
+}
+
+/// Changes that turn `previous` into `next`, or `None` when `next` reorders
+/// nodes in a way changes can't express (added nodes are always appended)
+pub fn diff_nodes<N: Clone + PartialEq + Default>(
+    previous: &[Node<N>],
+    next: &[Node<N>],
+) -> Option<Vec<NodeChange<N>>> {
+    let next_ids: HashMap<&str, &Node<N>> =
+        next.iter().map(|node| (node.id.as_str(), node)).collect();
+    let previous_ids: HashMap<&str, &Node<N>> = previous
+        .iter()
+        .map(|node| (node.id.as_str(), node))
+        .collect();
+    if !keeps_order(
+        previous.iter().map(|node| node.id.as_str()),
+        next.iter().map(|node| node.id.as_str()),
+    ) {
+        return None;
+    }
+    let mut changes: Vec<NodeChange<N>> = previous
+        .iter()
+        .filter(|node| !next_ids.contains_key(node.id.as_str()))
+        .map(|node| NodeChange::remove(node.id.clone()))
+        .collect();
+    for node in next {
+        match previous_ids.get(node.id.as_str()) {
+            None => changes.push(NodeChange::add(node.clone())),
+            Some(old) if *old != node => {
+                changes.push(NodeChange::replace(node.id.clone(), node.clone()))
+            }
+            Some(_) => {}
+        }
+    }
+    Some(changes)
+}
+
+/// Changes that turn `previous` into `next`, or `None` when `next` reorders
+/// edges in a way changes can't express (added edges are always appended)
+pub fn diff_edges<E: Clone + PartialEq + Default>(
+    previous: &[Edge<E>],
+    next: &[Edge<E>],
+) -> Option<Vec<EdgeChange<E>>> {
+    let next_ids: HashMap<&str, &Edge<E>> =
+        next.iter().map(|edge| (edge.id.as_str(), edge)).collect();
+    let previous_ids: HashMap<&str, &Edge<E>> = previous
+        .iter()
+        .map(|edge| (edge.id.as_str(), edge))
+        .collect();
+    if !keeps_order(
+        previous.iter().map(|edge| edge.id.as_str()),
+        next.iter().map(|edge| edge.id.as_str()),
+    ) {
+        return None;
+    }
+    let mut changes: Vec<EdgeChange<E>> = previous
+        .iter()
+        .filter(|edge| !next_ids.contains_key(edge.id.as_str()))
+        .map(|edge| EdgeChange::remove(edge.id.clone()))
+        .collect();
+    for edge in next {
+        match previous_ids.get(edge.id.as_str()) {
+            None => changes.push(EdgeChange::add(edge.clone())),
+            Some(old) if *old != edge => {
+                changes.push(EdgeChange::replace(edge.id.clone(), edge.clone()))
+            }
+            Some(_) => {}
+        }
+    }
+    Some(changes)
+}
+
+/// Whether removing, replacing in place and appending, in that order, turns
+/// the `previous` ids into the `next` ids
+fn keeps_order<'a>(
+    previous: impl Iterator<Item = &'a str> + Clone,
+    next: impl Iterator<Item = &'a str> + Clone,
+) -> bool {
+    let previous_ids: HashSet<&str> = previous.clone().collect();
+    let next_ids: HashSet<&str> = next.clone().collect();
+    let kept = previous.filter(|id| next_ids.contains(id));
+    let added = next.clone().filter(|id| !previous_ids.contains(id));
+    kept.chain(added).eq(next)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{XYPosition, apply_edge_changes, apply_node_changes};
+
+    fn node(id: &str, x: f64) -> Node {
+        Node::new(id, XYPosition::new(x, 0.0))
+    }
+
+    fn ids(nodes: &[Node]) -> Vec<&str> {
+        nodes.iter().map(|node| node.id.as_str()).collect()
+    }
+
+    #[test]
+    fn node_diffs_rebuild_the_next_nodes() {
+        let previous = vec![node("a", 0.0), node("b", 10.0), node("c", 20.0)];
+        let next = vec![node("a", 0.0), node("c", 25.0), node("d", 30.0)];
+        let changes = diff_nodes(&previous, &next).unwrap();
+        assert_eq!(changes.len(), 3);
+        assert!(matches!(&changes[0], NodeChange::Remove { id } if id == "b"));
+        assert!(matches!(&changes[1], NodeChange::Replace { id, .. } if id == "c"));
+        assert!(matches!(&changes[2], NodeChange::Add { node } if node.id == "d"));
+        assert_eq!(apply_node_changes(changes, previous), next);
+
+        assert_eq!(diff_nodes(&next, &next), Some(Vec::new()));
+    }
+
+    #[test]
+    fn reordered_nodes_have_no_diff() {
+        let previous = vec![node("a", 0.0), node("b", 10.0)];
+        assert_eq!(diff_nodes(&previous, &[node("b", 10.0), node("a", 0.0)]), None);
+        // An added node in front of existing ones would be appended
+        let next = vec![node("c", 20.0), node("a", 0.0), node("b", 10.0)];
+        assert_eq!(diff_nodes(&previous, &next), None);
+        let next = vec![node("a", 0.0), node("b", 10.0), node("c", 20.0)];
+        let changes = diff_nodes(&previous, &next).unwrap();
+        assert_eq!(ids(&apply_node_changes(changes, previous)), ["a", "b", "c"]);
+    }
+
+    #[test]
+    fn edge_diffs_rebuild_the_next_edges() {
+        let previous: Vec<Edge> = vec![Edge::new("e1", "a", "b"), Edge::new("e2", "b", "c")];
+        let next: Vec<Edge> = vec![
+            Edge::new("e2", "b", "c").with_type("step"),
+            Edge::new("e3", "a", "c"),
+        ];
+        let changes = diff_edges(&previous, &next).unwrap();
+        assert_eq!(changes.len(), 3);
+        assert_eq!(apply_edge_changes(changes, previous.clone()), next);
+
+        let swapped = vec![previous[1].clone(), previous[0].clone()];
+        assert_eq!(diff_edges(&previous, &swapped), None);
+    }
+}
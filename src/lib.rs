@@ -2,13 +2,23 @@
 #![allow(unpredictable_function_pointer_comparisons)]
 
 pub mod components;
+#[cfg(feature = "dataflow")]
+pub mod dataflow;
 pub mod hooks;
+pub mod journal;
 pub mod state;
+pub mod sync;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod types;
 pub mod utils;
 
 pub use components::*;
+#[cfg(feature = "dataflow")]
+pub use dataflow::*;
 pub use hooks::*;
+pub use journal::*;
 pub use state::*;
+pub use sync::*;
 pub use types::*;
 pub use utils::*;
@@ -1,12 +1,23 @@
 //! Flow state management using Dioxus signals
 
+use crate::journal::{
+    Journal, JournalEvent, JournalRecorder, PlaybackStatus, diff_edges, diff_nodes,
+};
 use crate::types::*;
+use crate::utils::browser::focus_element;
+use crate::utils::{GraphIndex, GraphPath, SharedValidator, get_highlight};
 use dioxus::prelude::*;
 use dioxus::prelude::{ReadableExt, WritableExt};
-use js_sys::Date;
 use std::collections::{HashMap, HashSet};
+use std::rc::Rc;
+use std::sync::atomic::{AtomicU64, Ordering};
 use wasm_bindgen::JsCast;
 
+/// Minimum time between live announcements
+const ANNOUNCE_THROTTLE_MS: f64 = 500.0;
+
+static NEXT_INSTANCE_ID: AtomicU64 = AtomicU64::new(0);
+
 /// Main flow state that holds all reactive data
 #[derive(Clone)]
 pub struct FlowState<
@@ -21,6 +32,8 @@ pub struct FlowState<
     pub node_lookup: Signal<HashMap<String, InternalNode<N>>>,
     pub edge_lookup: Signal<HashMap<String, Edge<E>>>,
     pub parent_lookup: Signal<HashMap<String, Vec<String>>>,
+    /// Built on first use by `graph_index`, dropped when edges or node ids change
+    graph_index: CopyValue<Option<Rc<GraphIndex>>>,
 
     // Viewport state
     pub viewport: Signal<Viewport>,
@@ -45,6 +58,9 @@ pub struct FlowState<
     pub nodes_focusable: Signal<bool>,
     pub edges_focusable: Signal<bool>,
     pub edges_reconnectable: Signal<bool>,
+    pub edge_waypoints_editable: Signal<bool>,
+    pub floating_edges: Signal<bool>,
+    pub parallel_edge_spacing: Signal<f64>,
     pub elements_selectable: Signal<bool>,
     pub only_render_visible_elements: Signal<bool>,
     pub visible_area_padding: Signal<f64>,
@@ -57,6 +73,7 @@ pub struct FlowState<
     pub nodes_selection_active: Signal<bool>,
     pub user_selection_active: Signal<bool>,
     pub user_selection_rect: Signal<Option<Rect>>,
+    pub user_selection_polygon: Signal<Option<Vec<XYPosition>>>,
 
     // Connection state
     pub connection: Signal<ConnectionState>,
@@ -96,7 +113,11 @@ pub struct FlowState<
     pub select_nodes_on_drag: Signal<bool>,
     pub selection_key_pressed: Signal<bool>,
     pub multi_selection_key_pressed: Signal<bool>,
+    pub add_selection_key_pressed: Signal<bool>,
+    pub subtract_selection_key_pressed: Signal<bool>,
+    pub toggle_selection_key_pressed: Signal<bool>,
     pub selection_mode: Signal<SelectionMode>,
+    pub select_edges_in_box: Signal<bool>,
     pub node_extent: Signal<Option<CoordinateExtent>>,
     pub focused_node_id: Signal<Option<String>>,
     pub focused_edge_id: Signal<Option<String>>,
@@ -106,6 +127,7 @@ pub struct FlowState<
 
     // Node dragging
     pub node_drag: Signal<Option<NodeDragState>>,
+    pub waypoint_drag: Signal<Option<WaypointDragState>>,
     pub node_drag_threshold: Signal<f64>,
     pub connection_drag_threshold: Signal<f64>,
     pub connect_on_click: Signal<bool>,
@@ -115,8 +137,56 @@ pub struct FlowState<
     pub pending_node_click: Signal<Option<PendingNodeClick>>,
     pub on_connect_start: Signal<Option<EventHandler<crate::types::ConnectionStartEvent>>>,
     pub on_connect_end: Signal<Option<EventHandler<crate::types::ConnectionEndEvent>>>,
+    pub on_pane_click: Signal<Option<EventHandler<crate::types::PaneMouseEvent>>>,
+    pub on_pane_double_click: Signal<Option<EventHandler<crate::types::PaneMouseEvent>>>,
+    pub on_pane_mouse_move: Signal<Option<EventHandler<crate::types::PaneMouseEvent>>>,
+    pub on_pane_mouse_enter: Signal<Option<EventHandler<crate::types::PaneMouseEvent>>>,
+    pub on_pane_mouse_leave: Signal<Option<EventHandler<crate::types::PaneMouseEvent>>>,
+    pub on_pane_scroll: Signal<Option<EventHandler<crate::types::PaneScrollEvent>>>,
+    pub on_pane_context_menu: Signal<Option<EventHandler<crate::types::PaneMouseEvent>>>,
+    pub on_node_context_menu: Signal<Option<EventHandler<crate::types::NodeContextMenuEvent<N>>>>,
+    pub on_edge_context_menu: Signal<Option<EventHandler<crate::types::EdgeContextMenuEvent<E>>>>,
+    pub on_selection_context_menu: Signal<Option<EventHandler<crate::types::SelectionContextMenuEvent<N, E>>>>,
+    pub on_selection_drag_start: Signal<Option<EventHandler<crate::types::SelectionDragEvent<N>>>>,
+    pub on_selection_drag: Signal<Option<EventHandler<crate::types::SelectionDragEvent<N>>>>,
+    pub on_selection_drag_stop: Signal<Option<EventHandler<crate::types::SelectionDragEvent<N>>>>,
+    pub on_command: Signal<Option<EventHandler<crate::types::FlowCommand>>>,
+    pub keymap: Signal<Keymap>,
     pub on_error: Signal<Option<OnError>>,
     pub viewport_animation_generation: Signal<u64>,
+    pub edge_pulses: Signal<Vec<EdgePulse>>,
+    pub next_pulse_id: Signal<u64>,
+
+    // Accessibility
+    /// Distinguishes the DOM ids of several flows on one page
+    pub instance_id: u64,
+    /// Current message of the ARIA live region
+    pub live_message: Signal<String>,
+    pub last_announcement: Signal<f64>,
+    /// Latest message held back by the throttle, announced when it expires
+    pub pending_announcement: Signal<Option<String>>,
+    /// Nodes up/down cycles through after graph focus navigation
+    pub focus_siblings: Signal<Vec<String>>,
+
+    // Journal
+    pub recorder: Signal<Option<JournalRecorder<N, E>>>,
+    /// Status of the journal player driving the flow, if any
+    pub playback: Signal<Option<PlaybackStatus>>,
+
+    // Highlighting
+    pub highlight_mode: Signal<HighlightMode>,
+    pub highlight_trigger: Signal<HighlightTrigger>,
+    pub hovered_node_id: Signal<Option<String>>,
+    pub hovered_edge_id: Signal<Option<String>>,
+    /// Set by `set_highlight`, replaces the built-in highlighting
+    pub custom_highlight: Signal<Option<Highlight>>,
+    /// Highlight being shown, kept up to date by the flow
+    pub active_highlight: Signal<Option<Highlight>>,
+
+    // Validation
+    pub validators: Signal<Vec<SharedValidator<N, E>>>,
+    /// Latest validator output, kept up to date by the flow
+    pub diagnostics: Signal<Diagnostics>,
 
     // Internal markers
     _node_marker: std::marker::PhantomData<N>,
@@ -135,6 +205,7 @@ where
             node_lookup: Signal::new(HashMap::new()),
             edge_lookup: Signal::new(HashMap::new()),
             parent_lookup: Signal::new(HashMap::new()),
+            graph_index: CopyValue::new(None),
             viewport: Signal::new(Viewport::identity()),
             width: Signal::new(0.0),
             height: Signal::new(0.0),
@@ -155,6 +226,9 @@ where
             nodes_focusable: Signal::new(true),
             edges_focusable: Signal::new(true),
             edges_reconnectable: Signal::new(true),
+            edge_waypoints_editable: Signal::new(true),
+            floating_edges: Signal::new(false),
+            parallel_edge_spacing: Signal::new(24.0),
             elements_selectable: Signal::new(true),
             only_render_visible_elements: Signal::new(false),
             visible_area_padding: Signal::new(0.2),
@@ -164,6 +238,7 @@ where
             nodes_selection_active: Signal::new(false),
             user_selection_active: Signal::new(false),
             user_selection_rect: Signal::new(None),
+            user_selection_polygon: Signal::new(None),
             connection: Signal::new(ConnectionState::default()),
             connection_mode: Signal::new(ConnectionMode::Strict),
             connection_radius: Signal::new(20.0),
@@ -195,12 +270,17 @@ where
             select_nodes_on_drag: Signal::new(true),
             selection_key_pressed: Signal::new(false),
             multi_selection_key_pressed: Signal::new(false),
+            add_selection_key_pressed: Signal::new(false),
+            subtract_selection_key_pressed: Signal::new(false),
+            toggle_selection_key_pressed: Signal::new(false),
             selection_mode: Signal::new(SelectionMode::Partial),
+            select_edges_in_box: Signal::new(false),
             node_extent: Signal::new(None),
             focused_node_id: Signal::new(None),
             focused_edge_id: Signal::new(None),
             delete_key_pressed: Signal::new(false),
             node_drag: Signal::new(None),
+            waypoint_drag: Signal::new(None),
             node_drag_threshold: Signal::new(1.0),
             connection_drag_threshold: Signal::new(1.0),
             connect_on_click: Signal::new(true),
@@ -210,8 +290,40 @@ where
             pending_node_click: Signal::new(None),
             on_connect_start: Signal::new(None),
             on_connect_end: Signal::new(None),
+            on_pane_click: Signal::new(None),
+            on_pane_double_click: Signal::new(None),
+            on_pane_mouse_move: Signal::new(None),
+            on_pane_mouse_enter: Signal::new(None),
+            on_pane_mouse_leave: Signal::new(None),
+            on_pane_scroll: Signal::new(None),
+            on_pane_context_menu: Signal::new(None),
+            on_node_context_menu: Signal::new(None),
+            on_edge_context_menu: Signal::new(None),
+            on_selection_context_menu: Signal::new(None),
+            on_selection_drag_start: Signal::new(None),
+            on_selection_drag: Signal::new(None),
+            on_selection_drag_stop: Signal::new(None),
+            on_command: Signal::new(None),
+            keymap: Signal::new(Keymap::default()),
             on_error: Signal::new(None),
             viewport_animation_generation: Signal::new(0),
+            edge_pulses: Signal::new(Vec::new()),
+            next_pulse_id: Signal::new(0),
+            instance_id: NEXT_INSTANCE_ID.fetch_add(1, Ordering::Relaxed),
+            live_message: Signal::new(String::new()),
+            last_announcement: Signal::new(0.0),
+            pending_announcement: Signal::new(None),
+            focus_siblings: Signal::new(Vec::new()),
+            recorder: Signal::new(None),
+            playback: Signal::new(None),
+            highlight_mode: Signal::new(HighlightMode::None),
+            highlight_trigger: Signal::new(HighlightTrigger::HoverOrSelection),
+            hovered_node_id: Signal::new(None),
+            hovered_edge_id: Signal::new(None),
+            custom_highlight: Signal::new(None),
+            active_highlight: Signal::new(None),
+            validators: Signal::new(Vec::new()),
+            diagnostics: Signal::new(Diagnostics::default()),
             _node_marker: std::marker::PhantomData,
             _edge_marker: std::marker::PhantomData,
         }
@@ -225,6 +337,17 @@ where
 
     /// Set nodes and rebuild lookups
     pub fn set_nodes(&mut self, nodes: Vec<Node<N>>) {
+        if self.recorder.peek().is_some() {
+            let event = match diff_nodes(&self.nodes.peek(), &nodes) {
+                Some(changes) => JournalEvent::NodeChanges(changes),
+                None => self.snapshot_event(nodes.clone(), self.edges.peek().clone()),
+            };
+            self.record(event);
+        }
+        self.replace_nodes(nodes);
+    }
+
+    fn replace_nodes(&mut self, nodes: Vec<Node<N>>) {
         let previous = self.node_lookup.read().clone();
         let mut node_lookup = HashMap::new();
         let mut parent_lookup: HashMap<String, Vec<String>> = HashMap::new();
@@ -249,6 +372,20 @@ where
             }
         }
 
+        if previous.len() != node_lookup.len()
+            || node_lookup.keys().any(|id| !previous.contains_key(id))
+        {
+            self.graph_index.set(None);
+        }
+        // A removed node gets no pointer leave to end its hover
+        let hovered_removed = self
+            .hovered_node_id
+            .peek()
+            .as_ref()
+            .is_some_and(|id| !node_lookup.contains_key(id));
+        if hovered_removed {
+            self.hovered_node_id.set(None);
+        }
         self.nodes.set(nodes);
         self.node_lookup.set(node_lookup);
         self.parent_lookup.set(parent_lookup);
@@ -256,11 +393,60 @@ where
 
     /// Set edges and rebuild lookup
     pub fn set_edges(&mut self, edges: Vec<Edge<E>>) {
+        if self.recorder.peek().is_some() {
+            let event = match diff_edges(&self.edges.peek(), &edges) {
+                Some(changes) => JournalEvent::EdgeChanges(changes),
+                None => self.snapshot_event(self.nodes.peek().clone(), edges.clone()),
+            };
+            self.record(event);
+        }
+        self.replace_edges(edges);
+    }
+
+    fn replace_edges(&mut self, edges: Vec<Edge<E>>) {
         let edge_lookup: HashMap<String, Edge<E>> =
             edges.iter().map(|e| (e.id.clone(), e.clone())).collect();
 
+        let hovered_removed = self
+            .hovered_edge_id
+            .peek()
+            .as_ref()
+            .is_some_and(|id| !edge_lookup.contains_key(id));
+        if hovered_removed {
+            self.hovered_edge_id.set(None);
+        }
         self.edges.set(edges);
         self.edge_lookup.set(edge_lookup);
+        self.graph_index.set(None);
+    }
+
+    /// Adjacency index of the current nodes and edges, for ancestors, cycles,
+    /// paths and other graph queries. Cached until the edges or node ids change.
+    pub fn graph_index(&self) -> Rc<GraphIndex> {
+        let nodes = self.nodes.read();
+        let edges = self.edges.read();
+        if let Some(index) = self.graph_index.peek().as_ref() {
+            return index.clone();
+        }
+        let index = Rc::new(GraphIndex::new(&nodes, &edges));
+        let mut cache = self.graph_index;
+        cache.set(Some(index.clone()));
+        index
+    }
+
+    /// Path from `from` to `to` with the lowest total `weight` of its edges.
+    /// Edges with a negative or non-finite weight are not taken.
+    pub fn weighted_shortest_path(
+        &self,
+        from: &str,
+        to: &str,
+        weight: impl Fn(&Edge<E>) -> f64,
+    ) -> Option<GraphPath> {
+        let index = self.graph_index();
+        let edges = self.edge_lookup.read();
+        index.shortest_path_by(from, to, |edge| {
+            edges.get(&edge.id).map_or(f64::INFINITY, &weight)
+        })
     }
 
     /// Compute absolute position including parent offsets
@@ -325,6 +511,7 @@ where
         if let Some(node) = nodes.iter_mut().find(|n| n.id == id) {
             f(node);
         }
+        self.graph_index.set(None);
     }
 
     /// Update a single edge
@@ -336,20 +523,89 @@ where
         if let Some(edge) = edges.iter_mut().find(|e| e.id == id) {
             f(edge);
         }
+        self.graph_index.set(None);
     }
 
     /// Apply node changes
     pub fn apply_node_changes(&mut self, changes: Vec<NodeChange<N>>) {
+        if self.recorder.peek().is_some() {
+            self.record(JournalEvent::NodeChanges(changes.clone()));
+        }
         let nodes = self.nodes.read().clone();
         let new_nodes = apply_node_changes(changes, nodes);
-        self.set_nodes(new_nodes);
+        self.replace_nodes(new_nodes);
     }
 
     /// Apply edge changes
     pub fn apply_edge_changes(&mut self, changes: Vec<EdgeChange<E>>) {
+        if self.recorder.peek().is_some() {
+            self.record(JournalEvent::EdgeChanges(changes.clone()));
+        }
         let edges = self.edges.read().clone();
         let new_edges = apply_edge_changes(changes, edges);
-        self.set_edges(new_edges);
+        self.replace_edges(new_edges);
+    }
+
+    /// Starts recording applied changes and viewport moves, dropping any
+    /// journal still being recorded
+    pub fn start_recording(&mut self) {
+        let recorder = JournalRecorder::new(
+            self.nodes.peek().clone(),
+            self.edges.peek().clone(),
+            *self.viewport.peek(),
+        );
+        self.recorder.set(Some(recorder));
+    }
+
+    /// Stops recording and returns the journal
+    pub fn stop_recording(&mut self) -> Option<Journal<N, E>> {
+        self.recorder.write().take().map(JournalRecorder::finish)
+    }
+
+    pub fn is_recording(&self) -> bool {
+        self.recorder.read().is_some()
+    }
+
+    /// Journal recorded so far, without stopping
+    pub fn recorded_journal(&self) -> Option<Journal<N, E>> {
+        self.recorder
+            .peek()
+            .as_ref()
+            .map(|recorder| recorder.journal().clone())
+    }
+
+    /// Full state entry for changes a diff can't express
+    fn snapshot_event(&self, nodes: Vec<Node<N>>, edges: Vec<Edge<E>>) -> JournalEvent<N, E> {
+        JournalEvent::Snapshot {
+            nodes,
+            edges,
+            viewport: *self.viewport.peek(),
+        }
+    }
+
+    fn record(&mut self, event: JournalEvent<N, E>) {
+        if let Some(recorder) = self.recorder.write().as_mut() {
+            recorder.record(event);
+        }
+    }
+
+    /// Writes replayed state without recording it or calling change handlers
+    pub(crate) fn replay(
+        &mut self,
+        nodes: Option<Vec<Node<N>>>,
+        edges: Option<Vec<Edge<E>>>,
+        viewport: Option<Viewport>,
+    ) {
+        if let Some(nodes) = nodes {
+            self.replace_nodes(nodes);
+        }
+        if let Some(edges) = edges {
+            self.replace_edges(edges);
+        }
+        if let Some(viewport) = viewport {
+            self.viewport.set(viewport);
+            self.refresh_connection_position();
+        }
     }
 
     /// Update internal node values (dimensions/absolute position) for a set of node ids.
@@ -387,6 +643,153 @@ where
         }
     }
 
+    /// Highlights the nodes and edges with the given ids and dims the rest,
+    /// replacing the built-in highlighting until `clear_highlight`
+    pub fn set_highlight(&mut self, ids: impl IntoIterator<Item = impl Into<String>>) {
+        let mut highlight = Highlight::default();
+        {
+            let nodes = self.node_lookup.peek();
+            let edges = self.edge_lookup.peek();
+            for id in ids {
+                let id = id.into();
+                if nodes.contains_key(&id) {
+                    highlight.nodes.insert(id);
+                } else if edges.contains_key(&id) {
+                    highlight.edges.insert(id);
+                }
+            }
+        }
+        self.custom_highlight.set(Some(highlight));
+    }
+
+    /// Removes the highlight set by `set_highlight`
+    pub fn clear_highlight(&mut self) {
+        self.custom_highlight.set(None);
+    }
+
+    /// Highlight to show: the custom one, else `highlight_mode` around the
+    /// hovered or selected elements
+    pub fn compute_highlight(&self) -> Option<Highlight> {
+        if let Some(custom) = self.custom_highlight.read().clone() {
+            return Some(custom);
+        }
+        let mode = *self.highlight_mode.read();
+        if mode == HighlightMode::None {
+            return None;
+        }
+        let trigger = *self.highlight_trigger.read();
+        let hovered_nodes: Vec<String> = self.hovered_node_id.read().iter().cloned().collect();
+        let hovered_edges: Vec<String> = self.hovered_edge_id.read().iter().cloned().collect();
+        let hovering = !hovered_nodes.is_empty() || !hovered_edges.is_empty();
+        let (nodes, edges) = match trigger {
+            HighlightTrigger::Hover | HighlightTrigger::HoverOrSelection if hovering => {
+                (hovered_nodes, hovered_edges)
+            }
+            HighlightTrigger::Hover => return None,
+            HighlightTrigger::Selection | HighlightTrigger::HoverOrSelection => (
+                self.nodes
+                    .read()
+                    .iter()
+                    .filter(|node| node.selected)
+                    .map(|node| node.id.clone())
+                    .collect(),
+                self.edges
+                    .read()
+                    .iter()
+                    .filter(|edge| edge.selected)
+                    .map(|edge| edge.id.clone())
+                    .collect(),
+            ),
+        };
+        let highlight = get_highlight(&self.graph_index(), mode, &nodes, &edges);
+        (!highlight.is_empty()).then_some(highlight)
+    }
+
+    /// Runs the validators over the current graph
+    pub fn validate(&self) -> Diagnostics {
+        let validators = self.validators.read().clone();
+        if validators.is_empty() {
+            return Diagnostics::default();
+        }
+        let graph = self.graph_index();
+        let nodes = self.nodes.read();
+        let edges = self.edges.read();
+        Diagnostics::new(
+            validators
+                .iter()
+                .flat_map(|validator| validator.0.validate(&nodes, &edges, &graph))
+                .collect(),
+        )
+    }
+
+    /// Focuses the element a diagnostic is attached to, on the canvas and in
+    /// the DOM, and centers it
+    pub fn focus_diagnostic(&mut self, diagnostic: &Diagnostic) {
+        let options = SetCenterOptions {
+            zoom: None,
+            duration: Some(300),
+        };
+        match &diagnostic.target {
+            DiagnosticTarget::Node(node_id) | DiagnosticTarget::Handle { node_id, .. } => {
+                self.focused_node_id.set(Some(node_id.clone()));
+                self.focused_edge_id.set(None);
+                self.center_on_node(node_id, Some(options));
+                focus_element(&format!("[data-id=\"{}\"]", node_id.replace('"', "\\\"")));
+            }
+            DiagnosticTarget::Edge(edge_id) => {
+                let Some(edge) = self.edge_lookup.peek().get(edge_id).cloned() else {
+                    return;
+                };
+                self.focused_edge_id.set(Some(edge_id.clone()));
+                self.focused_node_id.set(None);
+                let centers: Vec<XYPosition> = {
+                    let lookup = self.node_lookup.peek();
+                    [&edge.source, &edge.target]
+                        .into_iter()
+                        .filter_map(|id| lookup.get(id))
+                        .map(Self::node_flow_center)
+                        .collect()
+                };
+                if let [source, target] = centers[..] {
+                    self.set_center(
+                        (source.x + target.x) / 2.0,
+                        (source.y + target.y) / 2.0,
+                        Some(options),
+                    );
+                }
+                focus_element(&format!("[data-edge-id=\"{}\"]", edge_id.replace('"', "\\\"")));
+            }
+        }
+    }
+
+    /// Centers the viewport on a node, returns false if it does not exist
+    pub fn center_on_node(&mut self, node_id: &str, options: Option<SetCenterOptions>) -> bool {
+        let Some(center) = self.node_lookup.peek().get(node_id).map(Self::node_flow_center) else {
+            return false;
+        };
+        self.set_center(center.x, center.y, options);
+        true
+    }
+
+    /// Visible nodes matching `predicate`, top to bottom then left to right
+    pub fn find_nodes(&self, predicate: impl Fn(&Node<N>) -> bool) -> Vec<Node<N>> {
+        let lookup = self.node_lookup.read();
+        let mut matches: Vec<(XYPosition, Node<N>)> = self
+            .nodes
+            .read()
+            .iter()
+            .filter(|node| !node.hidden && predicate(node))
+            .map(|node| {
+                let position = lookup
+                    .get(&node.id)
+                    .map_or(node.position, |internal| internal.position_absolute);
+                (position, node.clone())
+            })
+            .collect();
+        matches.sort_by(|(a, _), (b, _)| a.y.total_cmp(&b.y).then(a.x.total_cmp(&b.x)));
+        matches.into_iter().map(|(_, node)| node).collect()
+    }
+
     /// Get selected nodes
     pub fn get_selected_nodes(&self) -> Vec<Node<N>> {
         self.nodes
@@ -407,6 +810,24 @@ where
             .collect()
     }
 
+    /// Context menu payload for the selection, if it spans more than one element
+    pub fn selection_context_menu_event(
+        &self,
+        screen_position: XYPosition,
+    ) -> Option<SelectionContextMenuEvent<N, E>> {
+        let nodes = self.get_selected_nodes();
+        let edges = self.get_selected_edges();
+        if nodes.len() + edges.len() < 2 {
+            return None;
+        }
+        Some(SelectionContextMenuEvent {
+            nodes,
+            edges,
+            screen_position,
+            flow_position: self.screen_to_flow_position(screen_position),
+        })
+    }
+
     /// Get visible nodes (not hidden, within viewport)
     pub fn get_visible_nodes(&self) -> Vec<Node<N>> {
         let viewport = *self.viewport.read();
@@ -449,6 +870,47 @@ where
             .collect()
     }
 
+    /// Get the topmost visible node whose bounds contain a flow position
+    pub fn get_node_at_position(&self, position: XYPosition) -> Option<Node<N>> {
+        self.find_node_at_position(position, |_| true)
+    }
+
+    /// Get the topmost group node whose bounds contain a flow position.
+    /// Nodes of type `group` and nodes with children count as groups.
+    pub fn get_group_node_at_position(&self, position: XYPosition) -> Option<Node<N>> {
+        let parent_lookup = self.parent_lookup.read();
+        self.find_node_at_position(position, |node| {
+            node.node_type.as_deref() == Some("group") || parent_lookup.contains_key(&node.id)
+        })
+    }
+
+    fn find_node_at_position(
+        &self,
+        position: XYPosition,
+        filter: impl Fn(&Node<N>) -> bool,
+    ) -> Option<Node<N>> {
+        self.node_lookup
+            .read()
+            .values()
+            .filter(|internal| {
+                !internal.node.hidden
+                    && filter(&internal.node)
+                    && Rect::from_position_and_dimensions(
+                        internal.position_absolute,
+                        internal.dimensions,
+                    )
+                    .contains(&position)
+            })
+            .max_by(|a, b| {
+                let a_z = a.node.z_index.unwrap_or(0);
+                let b_z = b.node.z_index.unwrap_or(0);
+                let a_area = a.dimensions.width * a.dimensions.height;
+                let b_area = b.dimensions.width * b.dimensions.height;
+                a_z.cmp(&b_z).then(b_area.total_cmp(&a_area))
+            })
+            .map(|internal| internal.node.clone())
+    }
+
     /// Get all edges connected to a node
     pub fn get_connected_edges(&self, node_id: &str) -> Vec<Edge<E>> {
         self.edges
@@ -679,12 +1141,12 @@ where
         };
         let start = *self.viewport.read();
         let duration_ms = duration as f64;
-        let Some(window) = web_sys::window() else {
+        let Some(window) = crate::utils::browser::window() else {
             self.viewport.set(target);
             self.report_error("window not available for viewport animation");
             return;
         };
-        let start_time = Date::now();
+        let start_time = crate::utils::browser::now();
         let mut state = self.clone();
 
         let raf: std::rc::Rc<
@@ -718,11 +1180,10 @@ where
                 state.notify_viewport_change(next);
 
                 if t < 1.0 {
-                    if let Some(window) = web_sys::window() {
-                        if let Some(callback) = raf_loop.borrow().as_ref() {
-                            let _ =
-                                window.request_animation_frame(callback.as_ref().unchecked_ref());
-                        }
+                    if let Some(window) = crate::utils::browser::window()
+                        && let Some(callback) = raf_loop.borrow().as_ref()
+                    {
+                        let _ = window.request_animation_frame(callback.as_ref().unchecked_ref());
                     }
                 } else {
                     raf_loop.borrow_mut().take();
@@ -735,7 +1196,74 @@ where
         }
     }
 
-    fn notify_viewport_change(&self, viewport: Viewport) {
+    /// Sends a one-shot token along an edge and returns its pulse id
+    pub fn pulse_edge(&mut self, edge_id: impl Into<String>, options: PulseOptions) -> u64 {
+        let id = {
+            let mut next = self.next_pulse_id.write();
+            *next += 1;
+            *next
+        };
+        let idle = self.edge_pulses.read().is_empty();
+        self.edge_pulses.write().push(EdgePulse {
+            id,
+            edge_id: edge_id.into(),
+            options,
+            started_at: crate::utils::browser::now(),
+            progress: 0.0,
+        });
+        if idle {
+            self.run_pulse_loop();
+        }
+        id
+    }
+
+    /// Advances active pulses every frame until none are left
+    fn run_pulse_loop(&mut self) {
+        let Some(window) = crate::utils::browser::window() else {
+            self.edge_pulses.write().clear();
+            self.report_error("window not available for edge pulses");
+            return;
+        };
+        let mut state = self.clone();
+
+        let raf: FrameCallback = std::rc::Rc::new(std::cell::RefCell::new(None));
+        let raf_clone = raf.clone();
+        let raf_loop = raf.clone();
+        *raf_clone.borrow_mut() = Some(wasm_bindgen::closure::Closure::wrap(Box::new(
+            move |_time: f64| {
+                let now = crate::utils::browser::now();
+                let remaining = {
+                    let mut pulses = state.edge_pulses.write();
+                    for pulse in pulses.iter_mut() {
+                        let duration = f64::from(pulse.options.duration_ms.max(1));
+                        pulse.progress = ((now - pulse.started_at) / duration).clamp(0.0, 1.0);
+                    }
+                    pulses.retain(|pulse| pulse.progress < 1.0);
+                    pulses.len()
+                };
+
+                let callback = raf_loop.borrow();
+                match (remaining, crate::utils::browser::window(), callback.as_ref()) {
+                    (1.., Some(window), Some(callback)) => {
+                        let _ = window.request_animation_frame(callback.as_ref().unchecked_ref());
+                    }
+                    _ => {
+                        drop(callback);
+                        raf_loop.borrow_mut().take();
+                    }
+                }
+            },
+        )));
+
+        if let Some(callback) = raf_clone.borrow().as_ref() {
+            let _ = window.request_animation_frame(callback.as_ref().unchecked_ref());
+        }
+    }
+
+    fn notify_viewport_change(&mut self, viewport: Viewport) {
+        if self.recorder.peek().is_some() {
+            self.record(JournalEvent::Viewport(viewport));
+        }
         if let Some(handler) = self.on_viewport_change.read().clone() {
             handler.call(viewport);
         }
@@ -792,6 +1320,252 @@ where
         self.connection.set(connection);
     }
 
+    /// Sends a message to the flow's ARIA live region. Messages are throttled so
+    /// drags and zoom gestures don't rewrite the region every frame; the last
+    /// message of a burst is announced once the throttle expires.
+    pub fn announce(&mut self, message: impl Into<String>) {
+        let elapsed = crate::utils::browser::now() - *self.last_announcement.peek();
+        // Without a browser there is no timer to flush with, so nothing is held back
+        let window = crate::utils::browser::window();
+        let Some(window) = window.filter(|_| elapsed < ANNOUNCE_THROTTLE_MS) else {
+            self.pending_announcement.set(None);
+            self.set_live_message(message.into());
+            return;
+        };
+        let scheduled = self.pending_announcement.peek().is_some();
+        self.pending_announcement.set(Some(message.into()));
+        if scheduled {
+            return;
+        }
+        let mut state = self.clone();
+        let flush = wasm_bindgen::closure::Closure::once_into_js(move || {
+            if let Some(message) = state.pending_announcement.take() {
+                state.set_live_message(message);
+            }
+        });
+        let _ = window.set_timeout_with_callback_and_timeout_and_arguments_0(
+            flush.unchecked_ref(),
+            (ANNOUNCE_THROTTLE_MS - elapsed).ceil() as i32,
+        );
+    }
+
+    fn set_live_message(&mut self, message: String) {
+        self.last_announcement.set(crate::utils::browser::now());
+        self.live_message.set(message);
+    }
+
+    /// Name of a node in announcements: its aria label, or its id
+    pub fn node_announcement_label(&self, id: &str) -> String {
+        self.node_lookup
+            .peek()
+            .get(id)
+            .and_then(|internal| internal.node.aria_label.clone())
+            .unwrap_or_else(|| id.to_string())
+    }
+
+    /// DOM id of the usage hint focused nodes are described by
+    pub fn node_description_id(&self) -> String {
+        format!("dioxus-flow__node-desc-{}", self.instance_id)
+    }
+
+    /// DOM id of the usage hint focused edges are described by
+    pub fn edge_description_id(&self) -> String {
+        format!("dioxus-flow__edge-desc-{}", self.instance_id)
+    }
+
+    /// Starts a keyboard-driven connection from a handle and targets the
+    /// nearest valid handle
+    pub fn start_keyboard_connection(
+        &mut self,
+        node_id: String,
+        handle_id: Option<String>,
+        handle_type: HandleType,
+        position: Position,
+    ) {
+        let mut connection =
+            ConnectionState::start(node_id.clone(), handle_id.clone(), handle_type, position);
+        connection.keyboard = true;
+        connection.dragging = true;
+        let from = self.node_lookup.read().get(&node_id).map(|internal| {
+            Self::resolve_handle_flow_position(internal, handle_type, handle_id.as_deref())
+                .unwrap_or_else(|| Self::node_flow_center(internal))
+        });
+        if let Some(from) = from {
+            connection.update_screen_position(self.flow_to_screen_position(from), from);
+        }
+        self.connection.set(connection);
+        let handler = *self.on_connect_start.read();
+        if let Some(handler) = handler {
+            handler.call(ConnectionStartEvent {
+                node_id,
+                handle_id,
+                handle_type,
+                position,
+            });
+        }
+        self.cycle_connection_target(true);
+    }
+
+    /// Handles the connection in progress can end on, respecting the connection
+    /// mode and `is_valid_connection`, nearest first
+    pub fn connection_targets(&self) -> Vec<ConnectionTarget> {
+        let connection = self.connection.read().clone();
+        let (Some(from_node), Some(from_type)) = (connection.from_node.clone(), connection.from_type)
+        else {
+            return Vec::new();
+        };
+        if !*self.nodes_connectable.read() {
+            return Vec::new();
+        }
+        let mode = *self.connection_mode.read();
+        let validator = *self.is_valid_connection.read();
+        let node_lookup = self.node_lookup.read();
+        let origin = node_lookup.get(&from_node).map(|internal| {
+            Self::resolve_handle_flow_position(
+                internal,
+                from_type,
+                connection.from_handle.as_deref(),
+            )
+            .unwrap_or_else(|| Self::node_flow_center(internal))
+        });
+
+        let mut targets = Vec::new();
+        for (node_id, internal) in node_lookup.iter() {
+            if internal.node.hidden || !internal.node.connectable.unwrap_or(true) {
+                continue;
+            }
+            let Some(bounds) = &internal.handle_bounds else {
+                continue;
+            };
+            for handle_type in [HandleType::Source, HandleType::Target] {
+                if mode == ConnectionMode::Strict && handle_type == from_type {
+                    continue;
+                }
+                let handles = match handle_type {
+                    HandleType::Source => &bounds.source,
+                    HandleType::Target => &bounds.target,
+                };
+                for handle in handles.iter().filter(|handle| handle.is_connectable) {
+                    if *node_id == from_node
+                        && handle_type == from_type
+                        && handle.id == connection.from_handle
+                    {
+                        continue;
+                    }
+                    let mut candidate = connection.clone();
+                    candidate.set_target(node_id.clone(), handle.id.clone(), handle_type, true);
+                    let valid = match (candidate.to_connection(), validator) {
+                        (Some(conn), Some(validator)) => validator(&conn),
+                        (Some(_), None) => true,
+                        (None, _) => false,
+                    };
+                    if valid {
+                        targets.push(ConnectionTarget {
+                            node_id: node_id.clone(),
+                            handle_id: handle.id.clone(),
+                            handle_type,
+                            position: XYPosition::new(
+                                internal.position_absolute.x + handle.x + handle.width / 2.0,
+                                internal.position_absolute.y + handle.y + handle.height / 2.0,
+                            ),
+                        });
+                    }
+                }
+            }
+        }
+        let distance = |target: &ConnectionTarget| {
+            origin.map_or(0.0, |origin| origin.distance_to(&target.position))
+        };
+        targets.sort_by(|a, b| {
+            distance(a)
+                .total_cmp(&distance(b))
+                .then_with(|| a.node_id.cmp(&b.node_id))
+                .then_with(|| a.handle_id.cmp(&b.handle_id))
+        });
+        targets
+    }
+
+    /// Moves the connection in progress to the next (or previous) valid target.
+    /// Returns false when there is no valid target.
+    pub fn cycle_connection_target(&mut self, forward: bool) -> bool {
+        let targets = self.connection_targets();
+        if targets.is_empty() {
+            return false;
+        }
+        let mut connection = self.connection.read().clone();
+        let current = targets.iter().position(|target| {
+            connection.to_node.as_deref() == Some(target.node_id.as_str())
+                && connection.to_handle == target.handle_id
+                && connection.to_type == Some(target.handle_type)
+        });
+        let len = targets.len();
+        let index = match current {
+            Some(index) if forward => (index + 1) % len,
+            Some(index) => (index + len - 1) % len,
+            None if forward => 0,
+            None => len - 1,
+        };
+        let target = targets[index].clone();
+        connection.set_target(target.node_id, target.handle_id, target.handle_type, true);
+        connection.update_screen_position(
+            self.flow_to_screen_position(target.position),
+            target.position,
+        );
+        self.connection.set(connection);
+        true
+    }
+
+    /// Ends the connection in progress, reporting it through `on_connect_end`.
+    /// Returns the connection if it ended on a valid target.
+    pub fn finish_connection(&mut self) -> Option<Connection> {
+        let mut connection = self.connection.read().clone();
+        if !connection.in_progress {
+            return None;
+        }
+        let result = if connection.is_valid {
+            connection.to_connection()
+        } else {
+            None
+        };
+        let handler = *self.on_connect_end.read();
+        if let Some(handler) = handler {
+            let drop_target = connection
+                .to_position
+                .and_then(|position| self.get_node_at_position(position))
+                .map(|node| ConnectionDropTarget::Node(node.id))
+                .unwrap_or_default();
+            handler.call(ConnectionEndEvent {
+                connection: result.clone(),
+                is_valid: connection.is_valid,
+                flow_position: connection.to_position,
+                screen_position: connection.to_position_screen,
+                from_node: connection.from_node.clone(),
+                from_handle: connection.from_handle.clone(),
+                from_handle_type: connection.from_type,
+                drop_target,
+            });
+        }
+        connection.reset();
+        self.connection.set(connection);
+        result
+    }
+
+    /// Aborts the connection in progress without connecting
+    pub fn cancel_connection(&mut self) {
+        let mut connection = self.connection.read().clone();
+        connection.clear_target();
+        self.connection.set(connection);
+        self.finish_connection();
+    }
+
+    /// Center of a node in flow coordinates
+    pub(crate) fn node_flow_center(internal: &InternalNode<N>) -> XYPosition {
+        XYPosition::new(
+            internal.position_absolute.x + internal.dimensions.width / 2.0,
+            internal.position_absolute.y + internal.dimensions.height / 2.0,
+        )
+    }
+
     // Prefer actual handle bounds when available so zoom/pan keep snapped targets aligned.
     fn resolve_handle_flow_position(
         internal: &InternalNode<N>,
@@ -961,6 +1735,42 @@ where
         self.apply_edge_changes(edge_changes);
     }
 
+    /// Snap a flow position to `snap_grid` when `snap_to_grid` is enabled.
+    pub fn snap_position(&self, position: XYPosition) -> XYPosition {
+        if !*self.snap_to_grid.read() {
+            return position;
+        }
+        let grid = *self.snap_grid.read();
+        XYPosition::new(
+            (position.x / grid.0).round() * grid.0,
+            (position.y / grid.1).round() * grid.1,
+        )
+    }
+
+    /// Add `node` at the drop position of a connection and connect it to the
+    /// handle the connection was dragged from. Returns the created edge.
+    pub fn add_connected_node(
+        &mut self,
+        event: &crate::types::ConnectionEndEvent,
+        node: Node<N>,
+    ) -> Option<Edge<E>> {
+        let (node_change, edge_change) = connected_node_changes::<N, E>(event, node, None)?;
+        let node_change = match node_change {
+            NodeChange::Add { mut node } => {
+                node.position = self.snap_position(node.position);
+                NodeChange::Add { node }
+            }
+            change => change,
+        };
+        let edge = match &edge_change {
+            EdgeChange::Add { edge } => Some(edge.clone()),
+            _ => None,
+        };
+        self.apply_node_changes(vec![node_change]);
+        self.apply_edge_changes(vec![edge_change]);
+        edge
+    }
+
     /// Register a selection change handler.
     pub fn add_selection_change_handler(
         &mut self,
@@ -1001,6 +1811,30 @@ pub struct NodeDragState {
     pub start_pointer: XYPosition,
     pub nodes: Vec<(String, XYPosition)>,
     pub started: bool,
+    /// Started from the nodes selection box rather than a node
+    pub selection: bool,
+}
+
+/// Self-rescheduling `requestAnimationFrame` callback.
+type FrameCallback =
+    std::rc::Rc<std::cell::RefCell<Option<wasm_bindgen::closure::Closure<dyn FnMut(f64)>>>>;
+
+/// One-shot token travelling along an edge.
+#[derive(Clone, PartialEq, Debug)]
+pub struct EdgePulse {
+    pub id: u64,
+    pub edge_id: String,
+    pub options: PulseOptions,
+    pub started_at: f64,
+    /// Fraction of the edge travelled, from 0.0 to 1.0
+    pub progress: f64,
+}
+
+/// Drag state for moving an edge waypoint.
+#[derive(Clone, PartialEq, Debug)]
+pub struct WaypointDragState {
+    pub edge_id: String,
+    pub index: usize,
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -1068,3 +1902,29 @@ pub fn connection_to_edge<E: Clone + PartialEq + Default>(
         ..Default::default()
     }
 }
+
+/// Build the changes that add `node` at a connection's drop position and
+/// connect it to the originating handle.
+///
+/// Returns `None` when the event has no origin or drop position.
+pub fn connected_node_changes<N, E>(
+    event: &crate::types::ConnectionEndEvent,
+    mut node: Node<N>,
+    edge_type: Option<String>,
+) -> Option<(NodeChange<N>, EdgeChange<E>)>
+where
+    N: Clone + PartialEq + Default,
+    E: Clone + PartialEq + Default,
+{
+    let from_node = event.from_node.clone()?;
+    let position = event.flow_position?;
+    node.position = position;
+    let connection = match event.from_handle_type.unwrap_or_default() {
+        HandleType::Source => Connection::new(from_node, node.id.clone())
+            .with_handles(event.from_handle.clone(), None),
+        HandleType::Target => Connection::new(node.id.clone(), from_node)
+            .with_handles(None, event.from_handle.clone()),
+    };
+    let edge = connection_to_edge::<E>(&connection, edge_type);
+    Some((NodeChange::Add { node }, EdgeChange::Add { edge }))
+}
@@ -1,5 +1,7 @@
 //! State management for Dioxus Flow
 
 mod flow_state;
+mod node_search;
 
 pub use flow_state::*;
+pub use node_search::*;
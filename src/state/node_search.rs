@@ -0,0 +1,161 @@
+//! Stepping through node search results
+
+use super::FlowState;
+use crate::types::SetCenterOptions;
+use crate::utils::browser::focus_element;
+use dioxus::prelude::{ReadableExt, WritableExt};
+
+/// How `NodeSearch` brings a match into view
+#[derive(Clone, PartialEq, Debug)]
+pub struct NodeSearchOptions {
+    /// Center the match, else only pan until it is visible
+    pub center: bool,
+    /// Zoom level when centering (None = keep current)
+    pub zoom: Option<f64>,
+    /// Animation duration in ms (None = no animation)
+    pub duration: Option<u32>,
+    /// Highlight every match and dim the rest
+    pub highlight: bool,
+    /// Move DOM focus to the focused match
+    pub focus_dom: bool,
+}
+
+impl Default for NodeSearchOptions {
+    fn default() -> Self {
+        Self {
+            center: true,
+            zoom: None,
+            duration: Some(300),
+            highlight: true,
+            focus_dom: true,
+        }
+    }
+}
+
+/// Cycles through matching nodes, focusing and centering one at a time
+#[derive(Clone)]
+pub struct NodeSearch<
+    N: Clone + PartialEq + Default + 'static = (),
+    E: Clone + PartialEq + Default + 'static = (),
+> {
+    state: FlowState<N, E>,
+    matches: Vec<String>,
+    current: Option<usize>,
+    options: NodeSearchOptions,
+}
+
+impl<N, E> NodeSearch<N, E>
+where
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+{
+    /// Starts a search over `matches`, highlighting them if enabled. Nothing is
+    /// focused until `next_match`, `previous_match` or `focus` is called.
+    pub fn new(state: FlowState<N, E>, matches: Vec<String>, options: NodeSearchOptions) -> Self {
+        let mut search = Self {
+            state,
+            matches,
+            current: None,
+            options,
+        };
+        if search.options.highlight {
+            if search.matches.is_empty() {
+                search.state.clear_highlight();
+            } else {
+                search.state.set_highlight(search.matches.clone());
+            }
+        }
+        search
+    }
+
+    pub fn matches(&self) -> &[String] {
+        &self.matches
+    }
+
+    pub fn len(&self) -> usize {
+        self.matches.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.matches.is_empty()
+    }
+
+    /// Index of the focused match
+    pub fn current_index(&self) -> Option<usize> {
+        self.current
+    }
+
+    /// Id of the focused match
+    pub fn current(&self) -> Option<&str> {
+        self.current.map(|index| self.matches[index].as_str())
+    }
+
+    /// Focuses the next match, wrapping around after the last
+    pub fn next_match(&mut self) -> Option<String> {
+        let len = self.matches.len();
+        if len == 0 {
+            return None;
+        }
+        let index = self.current.map_or(0, |index| (index + 1) % len);
+        self.focus(index)
+    }
+
+    /// Focuses the previous match, wrapping around before the first
+    pub fn previous_match(&mut self) -> Option<String> {
+        let len = self.matches.len();
+        if len == 0 {
+            return None;
+        }
+        let index = self
+            .current
+            .map_or(len - 1, |index| (index + len - 1) % len);
+        self.focus(index)
+    }
+
+    /// Focuses the match at `index`, on the canvas and, with `focus_dom`, in
+    /// the DOM, and brings it into view
+    pub fn focus(&mut self, index: usize) -> Option<String> {
+        let id = self.matches.get(index)?.clone();
+        self.current = Some(index);
+        self.state.focused_node_id.set(Some(id.clone()));
+        self.state.focused_edge_id.set(None);
+        if self.options.center {
+            let center = self
+                .state
+                .node_lookup
+                .peek()
+                .get(&id)
+                .map(FlowState::<N, E>::node_flow_center);
+            if let Some(center) = center {
+                self.state.set_center(
+                    center.x,
+                    center.y,
+                    Some(SetCenterOptions {
+                        zoom: self.options.zoom,
+                        duration: self.options.duration,
+                    }),
+                );
+            }
+        } else {
+            self.state.ensure_node_visible(&id);
+        }
+        if self.options.focus_dom {
+            focus_element(&format!("[data-id=\"{}\"]", id.replace('"', "\\\"")));
+        }
+        Some(id)
+    }
+
+    /// Sets whether later focus calls move DOM focus to the match
+    pub fn set_focus_dom(&mut self, focus_dom: bool) {
+        self.options.focus_dom = focus_dom;
+    }
+
+    /// Drops the matches and removes the search highlight
+    pub fn clear(&mut self) {
+        if self.options.highlight {
+            self.state.clear_highlight();
+        }
+        self.matches.clear();
+        self.current = None;
+    }
+}
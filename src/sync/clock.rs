@@ -0,0 +1,60 @@
+//! Lamport clock
+
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+
+/// Lamport timestamp. Ties are broken by peer id, so timestamps are unique and
+/// every peer orders operations the same way.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default, Serialize, Deserialize)]
+pub struct Timestamp {
+    pub counter: u64,
+    pub peer: String,
+}
+
+impl Ord for Timestamp {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.counter
+            .cmp(&other.counter)
+            .then_with(|| self.peer.cmp(&other.peer))
+    }
+}
+
+impl PartialOrd for Timestamp {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+/// Logical clock of one peer
+#[derive(Clone, PartialEq, Debug)]
+pub struct LamportClock {
+    counter: u64,
+    peer: String,
+}
+
+impl LamportClock {
+    pub fn new(peer: impl Into<String>) -> Self {
+        Self {
+            counter: 0,
+            peer: peer.into(),
+        }
+    }
+
+    pub fn peer(&self) -> &str {
+        &self.peer
+    }
+
+    /// Timestamp for a new local operation
+    pub fn tick(&mut self) -> Timestamp {
+        self.counter += 1;
+        Timestamp {
+            counter: self.counter,
+            peer: self.peer.clone(),
+        }
+    }
+
+    /// Moves the clock past a timestamp received from another peer
+    pub fn observe(&mut self, timestamp: &Timestamp) {
+        self.counter = self.counter.max(timestamp.counter);
+    }
+}
@@ -0,0 +1,598 @@
+//! Conflict resolution for synced graphs
+
+use super::{LamportClock, OperationKind, SyncOperation, Timestamp};
+use crate::types::{Edge, EdgeChange, Node, NodeChange};
+use std::collections::{HashMap, HashSet, VecDeque};
+
+#[derive(Clone, PartialEq, Eq, Hash, Debug)]
+enum ElementKey {
+    Node(String),
+    Edge(String),
+}
+
+/// Independently merged parts of an element
+#[derive(Clone, Copy, PartialEq, Eq, Hash, Debug)]
+enum Field {
+    /// Every field not listed below
+    Body,
+    Position,
+    Size,
+    Points,
+}
+
+/// Turns local edits into operations and merges remote ones. Each field group
+/// of a node or edge is a last-writer-wins register ordered by Lamport
+/// timestamp; deletes leave tombstones so older concurrent writes stay deleted.
+#[derive(Clone, Debug)]
+pub struct SyncEngine<N: Clone + PartialEq + Default = (), E: Clone + PartialEq + Default = ()> {
+    clock: LamportClock,
+    registers: HashMap<(ElementKey, Field), Timestamp>,
+    tombstones: HashMap<ElementKey, Timestamp>,
+    seen: HashSet<String>,
+    /// Field updates that arrived before their element, applied once it does
+    pending: HashMap<ElementKey, Vec<SyncOperation<N, E>>>,
+    /// Last synced state, without local-only fields
+    nodes: HashMap<String, Node<N>>,
+    edges: HashMap<String, Edge<E>>,
+}
+
+impl<N: Clone + PartialEq + Default, E: Clone + PartialEq + Default> SyncEngine<N, E> {
+    pub fn new(peer: impl Into<String>) -> Self {
+        Self {
+            clock: LamportClock::new(peer),
+            registers: HashMap::new(),
+            tombstones: HashMap::new(),
+            seen: HashSet::new(),
+            pending: HashMap::new(),
+            nodes: HashMap::new(),
+            edges: HashMap::new(),
+        }
+    }
+
+    pub fn peer(&self) -> &str {
+        self.clock.peer()
+    }
+
+    /// Operations for the local edits made since the last call
+    pub fn local_operations(
+        &mut self,
+        nodes: &[Node<N>],
+        edges: &[Edge<E>],
+    ) -> Vec<SyncOperation<N, E>> {
+        let mut kinds = Vec::new();
+        let mut node_ids = HashSet::new();
+        for node in nodes {
+            node_ids.insert(node.id.as_str());
+            let node = shared_node(node);
+            match self.nodes.get(&node.id) {
+                None => kinds.push(OperationKind::PutNode {
+                    node: Box::new(node),
+                }),
+                Some(known) if node_body(known) != node_body(&node) => {
+                    kinds.push(OperationKind::PutNode {
+                        node: Box::new(node),
+                    })
+                }
+                Some(known) => {
+                    if known.position != node.position {
+                        kinds.push(OperationKind::SetNodePosition {
+                            id: node.id.clone(),
+                            position: node.position,
+                        });
+                    }
+                    if (known.width, known.height) != (node.width, node.height) {
+                        kinds.push(OperationKind::SetNodeSize {
+                            id: node.id.clone(),
+                            width: node.width,
+                            height: node.height,
+                        });
+                    }
+                }
+            }
+        }
+        let mut edge_ids = HashSet::new();
+        for edge in edges {
+            edge_ids.insert(edge.id.as_str());
+            let edge = shared_edge(edge);
+            match self.edges.get(&edge.id) {
+                None => kinds.push(OperationKind::PutEdge {
+                    edge: Box::new(edge),
+                }),
+                Some(known) if edge_body(known) != edge_body(&edge) => {
+                    kinds.push(OperationKind::PutEdge {
+                        edge: Box::new(edge),
+                    })
+                }
+                Some(known) if known.points != edge.points => {
+                    kinds.push(OperationKind::SetEdgePoints {
+                        id: edge.id.clone(),
+                        points: edge.points.clone(),
+                    })
+                }
+                Some(_) => {}
+            }
+        }
+        let mut removed_edges: Vec<String> = self
+            .edges
+            .keys()
+            .filter(|id| !edge_ids.contains(id.as_str()))
+            .cloned()
+            .collect();
+        removed_edges.sort();
+        kinds.extend(
+            removed_edges
+                .into_iter()
+                .map(|id| OperationKind::RemoveEdge { id }),
+        );
+        let mut removed_nodes: Vec<String> = self
+            .nodes
+            .keys()
+            .filter(|id| !node_ids.contains(id.as_str()))
+            .cloned()
+            .collect();
+        removed_nodes.sort();
+        kinds.extend(
+            removed_nodes
+                .into_iter()
+                .map(|id| OperationKind::RemoveNode { id }),
+        );
+
+        kinds
+            .into_iter()
+            .map(|kind| {
+                let operation = SyncOperation::new(self.clock.tick(), kind);
+                self.seen.insert(operation.id.clone());
+                self.integrate(&operation);
+                operation
+            })
+            .collect()
+    }
+
+    /// Merges operations from other peers and returns the changes that bring
+    /// `nodes` and `edges` to the merged state. Duplicates are ignored, and
+    /// updates of elements that have not arrived yet wait for them.
+    pub fn apply_remote(
+        &mut self,
+        operations: Vec<SyncOperation<N, E>>,
+        nodes: &[Node<N>],
+        edges: &[Edge<E>],
+    ) -> (Vec<NodeChange<N>>, Vec<EdgeChange<E>>) {
+        let mut touched_nodes = Vec::new();
+        let mut touched_edges = Vec::new();
+        let mut queue = VecDeque::from(operations);
+        while let Some(operation) = queue.pop_front() {
+            if self.seen.contains(&operation.id) {
+                continue;
+            }
+            self.clock.observe(&operation.timestamp);
+            let key = element_key(&operation.kind);
+            if self.awaits_element(&operation) {
+                let pending = self.pending.entry(key).or_default();
+                if !pending.iter().any(|waiting| waiting.id == operation.id) {
+                    pending.push(operation);
+                }
+                continue;
+            }
+            self.seen.insert(operation.id.clone());
+            if !self.integrate(&operation) {
+                continue;
+            }
+            if let Some(waiting) = self.pending.remove(&key) {
+                queue.extend(waiting);
+            }
+            if let OperationKind::RemoveNode { id } = &operation.kind {
+                for edge_id in self.detach_edges(id) {
+                    if !touched_edges.contains(&edge_id) {
+                        touched_edges.push(edge_id);
+                    }
+                }
+            }
+            match key {
+                ElementKey::Node(id) if !touched_nodes.contains(&id) => touched_nodes.push(id),
+                ElementKey::Edge(id) if !touched_edges.contains(&id) => touched_edges.push(id),
+                _ => {}
+            }
+        }
+
+        let mut node_changes = Vec::new();
+        for id in touched_nodes {
+            let local = nodes.iter().find(|node| node.id == id);
+            match (self.nodes.get(&id), local) {
+                (Some(merged), Some(local)) => {
+                    if shared_node(local) != *merged {
+                        let mut node = merged.clone();
+                        node.selected = local.selected;
+                        node.dragging = local.dragging;
+                        node.resizing = local.resizing;
+                        node.measured_width = local.measured_width;
+                        node.measured_height = local.measured_height;
+                        node_changes.push(NodeChange::Replace { id, node });
+                    }
+                }
+                (Some(merged), None) => node_changes.push(NodeChange::Add {
+                    node: merged.clone(),
+                }),
+                (None, Some(_)) => node_changes.push(NodeChange::Remove { id }),
+                (None, None) => {}
+            }
+        }
+        let mut edge_changes = Vec::new();
+        for id in touched_edges {
+            let local = edges.iter().find(|edge| edge.id == id);
+            match (self.edges.get(&id), local) {
+                (Some(merged), Some(local)) => {
+                    if shared_edge(local) != *merged {
+                        let mut edge = merged.clone();
+                        edge.selected = local.selected;
+                        edge_changes.push(EdgeChange::Replace { id, edge });
+                    }
+                }
+                (Some(merged), None) => edge_changes.push(EdgeChange::Add {
+                    edge: merged.clone(),
+                }),
+                (None, Some(_)) => edge_changes.push(EdgeChange::Remove { id }),
+                (None, None) => {}
+            }
+        }
+        (node_changes, edge_changes)
+    }
+
+    /// Operations that rebuild the synced state, tombstones included, for a
+    /// peer that just joined. They keep their original ids and timestamps.
+    pub fn snapshot(&self) -> Vec<SyncOperation<N, E>> {
+        let mut operations = Vec::new();
+        for (id, node) in &self.nodes {
+            let key = ElementKey::Node(id.clone());
+            if let Some(timestamp) = self.register(&key, Field::Body) {
+                operations.push(SyncOperation::new(
+                    timestamp.clone(),
+                    OperationKind::PutNode {
+                        node: Box::new(node.clone()),
+                    },
+                ));
+            }
+            if let Some(timestamp) = self.register(&key, Field::Position) {
+                operations.push(SyncOperation::new(
+                    timestamp.clone(),
+                    OperationKind::SetNodePosition {
+                        id: id.clone(),
+                        position: node.position,
+                    },
+                ));
+            }
+            if let Some(timestamp) = self.register(&key, Field::Size) {
+                operations.push(SyncOperation::new(
+                    timestamp.clone(),
+                    OperationKind::SetNodeSize {
+                        id: id.clone(),
+                        width: node.width,
+                        height: node.height,
+                    },
+                ));
+            }
+        }
+        for (id, edge) in &self.edges {
+            let key = ElementKey::Edge(id.clone());
+            if let Some(timestamp) = self.register(&key, Field::Body) {
+                operations.push(SyncOperation::new(
+                    timestamp.clone(),
+                    OperationKind::PutEdge {
+                        edge: Box::new(edge.clone()),
+                    },
+                ));
+            }
+            if let Some(timestamp) = self.register(&key, Field::Points) {
+                operations.push(SyncOperation::new(
+                    timestamp.clone(),
+                    OperationKind::SetEdgePoints {
+                        id: id.clone(),
+                        points: edge.points.clone(),
+                    },
+                ));
+            }
+        }
+        for (key, timestamp) in &self.tombstones {
+            let kind = match key {
+                ElementKey::Node(id) => OperationKind::RemoveNode { id: id.clone() },
+                ElementKey::Edge(id) => OperationKind::RemoveEdge { id: id.clone() },
+            };
+            operations.push(SyncOperation::new(timestamp.clone(), kind));
+        }
+        operations.extend(self.pending.values().flatten().cloned());
+        // Deduplicate operations that set several registers at once
+        let mut ids = HashSet::new();
+        operations.retain(|operation| ids.insert(operation.id.clone()));
+        operations.sort_by(|a, b| a.timestamp.cmp(&b.timestamp));
+        operations
+    }
+
+    fn register(&self, key: &ElementKey, field: Field) -> Option<&Timestamp> {
+        self.registers.get(&(key.clone(), field))
+    }
+
+    /// Claims `field` for `timestamp` if it is newer than the current writer
+    fn claim(&mut self, key: &ElementKey, field: Field, timestamp: &Timestamp) -> bool {
+        match self.registers.get(&(key.clone(), field)) {
+            Some(current) if current >= timestamp => false,
+            _ => {
+                self.registers
+                    .insert((key.clone(), field), timestamp.clone());
+                true
+            }
+        }
+    }
+
+    fn deleted_after(&self, key: &ElementKey, timestamp: &Timestamp) -> bool {
+        self.tombstones
+            .get(key)
+            .is_some_and(|tombstone| tombstone >= timestamp)
+    }
+
+    /// Whether `operation` updates a node or edge that has not arrived yet
+    fn awaits_element(&self, operation: &SyncOperation<N, E>) -> bool {
+        let missing = match &operation.kind {
+            OperationKind::SetNodePosition { id, .. } | OperationKind::SetNodeSize { id, .. } => {
+                !self.nodes.contains_key(id)
+            }
+            OperationKind::SetEdgePoints { id, .. } => !self.edges.contains_key(id),
+            _ => false,
+        };
+        missing && !self.deleted_after(&element_key(&operation.kind), &operation.timestamp)
+    }
+
+    /// Whether the node was deleted and not put back since
+    fn node_deleted(&self, id: &str) -> bool {
+        !self.nodes.contains_key(id) && self.tombstones.contains_key(&ElementKey::Node(id.into()))
+    }
+
+    /// Drops the edges attached to a removed node; returns their ids
+    fn detach_edges(&mut self, node_id: &str) -> Vec<String> {
+        let mut detached: Vec<String> = self
+            .edges
+            .values()
+            .filter(|edge| edge.source == node_id || edge.target == node_id)
+            .map(|edge| edge.id.clone())
+            .collect();
+        detached.sort();
+        for id in &detached {
+            self.edges.remove(id);
+        }
+        detached
+    }
+
+    /// Applies an operation to the synced state; returns whether anything changed
+    fn integrate(&mut self, operation: &SyncOperation<N, E>) -> bool {
+        let timestamp = &operation.timestamp;
+        let key = element_key(&operation.kind);
+        if self.deleted_after(&key, timestamp) {
+            return false;
+        }
+        match &operation.kind {
+            OperationKind::PutNode { node } => {
+                let body = self.claim(&key, Field::Body, timestamp);
+                let position = self.claim(&key, Field::Position, timestamp);
+                let size = self.claim(&key, Field::Size, timestamp);
+                let known = self.nodes.get(&node.id);
+                let mut merged = match (body, known) {
+                    (false, Some(known)) => known.clone(),
+                    _ => (**node).clone(),
+                };
+                if let Some(known) = known {
+                    if !position {
+                        merged.position = known.position;
+                    }
+                    if !size {
+                        merged.width = known.width;
+                        merged.height = known.height;
+                    }
+                }
+                self.nodes.insert(node.id.clone(), merged);
+                body || position || size
+            }
+            OperationKind::SetNodePosition { id, position } => {
+                if !self.nodes.contains_key(id) || !self.claim(&key, Field::Position, timestamp) {
+                    return false;
+                }
+                if let Some(node) = self.nodes.get_mut(id) {
+                    node.position = *position;
+                }
+                true
+            }
+            OperationKind::SetNodeSize { id, width, height } => {
+                if !self.nodes.contains_key(id) || !self.claim(&key, Field::Size, timestamp) {
+                    return false;
+                }
+                if let Some(node) = self.nodes.get_mut(id) {
+                    node.width = *width;
+                    node.height = *height;
+                }
+                true
+            }
+            OperationKind::PutEdge { edge } => {
+                // Connections to a node deleted concurrently are dropped
+                if self.node_deleted(&edge.source) || self.node_deleted(&edge.target) {
+                    return false;
+                }
+                let body = self.claim(&key, Field::Body, timestamp);
+                let points = self.claim(&key, Field::Points, timestamp);
+                let known = self.edges.get(&edge.id);
+                let mut merged = match (body, known) {
+                    (false, Some(known)) => known.clone(),
+                    _ => (**edge).clone(),
+                };
+                if let (Some(known), false) = (known, points) {
+                    merged.points = known.points.clone();
+                }
+                self.edges.insert(edge.id.clone(), merged);
+                body || points
+            }
+            OperationKind::SetEdgePoints { id, points } => {
+                if !self.edges.contains_key(id) || !self.claim(&key, Field::Points, timestamp) {
+                    return false;
+                }
+                if let Some(edge) = self.edges.get_mut(id) {
+                    edge.points = points.clone();
+                }
+                true
+            }
+            OperationKind::RemoveNode { id } | OperationKind::RemoveEdge { id } => {
+                self.tombstones.insert(key.clone(), timestamp.clone());
+                // A put newer than the delete keeps the element alive
+                if self
+                    .register(&key, Field::Body)
+                    .is_some_and(|body| body > timestamp)
+                {
+                    return false;
+                }
+                match key {
+                    ElementKey::Node(_) => self.nodes.remove(id).is_some(),
+                    ElementKey::Edge(_) => self.edges.remove(id).is_some(),
+                }
+            }
+        }
+    }
+}
+
+fn element_key<N: Clone + PartialEq + Default, E: Clone + PartialEq + Default>(
+    kind: &OperationKind<N, E>,
+) -> ElementKey {
+    match kind {
+        OperationKind::PutNode { node } => ElementKey::Node(node.id.clone()),
+        OperationKind::SetNodePosition { id, .. }
+        | OperationKind::SetNodeSize { id, .. }
+        | OperationKind::RemoveNode { id } => ElementKey::Node(id.clone()),
+        OperationKind::PutEdge { edge } => ElementKey::Edge(edge.id.clone()),
+        OperationKind::SetEdgePoints { id, .. } | OperationKind::RemoveEdge { id } => {
+            ElementKey::Edge(id.clone())
+        }
+    }
+}
+
+/// Node without local-only fields
+fn shared_node<N: Clone + PartialEq + Default>(node: &Node<N>) -> Node<N> {
+    let mut node = node.clone();
+    node.selected = false;
+    node.dragging = false;
+    node.resizing = false;
+    node.measured_width = None;
+    node.measured_height = None;
+    node
+}
+
+/// Node fields merged as `Field::Body`
+fn node_body<N: Clone + PartialEq + Default>(node: &Node<N>) -> Node<N> {
+    let mut node = node.clone();
+    node.position = Default::default();
+    node.width = None;
+    node.height = None;
+    node
+}
+
+fn shared_edge<E: Clone + PartialEq + Default>(edge: &Edge<E>) -> Edge<E> {
+    let mut edge = edge.clone();
+    edge.selected = false;
+    edge
+}
+
+fn edge_body<E: Clone + PartialEq + Default>(edge: &Edge<E>) -> Edge<E> {
+    let mut edge = edge.clone();
+    edge.points = Vec::new();
+    edge
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::{XYPosition, apply_edge_changes, apply_node_changes};
+
+    /// A peer's engine together with its local graph
+    struct Peer {
+        engine: SyncEngine,
+        nodes: Vec<Node>,
+        edges: Vec<Edge>,
+    }
+
+    impl Peer {
+        fn new(id: &str) -> Self {
+            Self {
+                engine: SyncEngine::new(id),
+                nodes: Vec::new(),
+                edges: Vec::new(),
+            }
+        }
+
+        fn commit(&mut self) -> Vec<SyncOperation> {
+            self.engine.local_operations(&self.nodes, &self.edges)
+        }
+
+        fn receive(&mut self, operations: Vec<SyncOperation>) {
+            let (node_changes, edge_changes) =
+                self.engine
+                    .apply_remote(operations, &self.nodes, &self.edges);
+            self.nodes = apply_node_changes(node_changes, std::mem::take(&mut self.nodes));
+            self.edges = apply_edge_changes(edge_changes, std::mem::take(&mut self.edges));
+        }
+
+        fn position(&self, id: &str) -> Option<XYPosition> {
+            self.nodes
+                .iter()
+                .find(|node| node.id == id)
+                .map(|node| node.position)
+        }
+    }
+
+    #[test]
+    fn updates_wait_for_their_element() {
+        let mut a = Peer::new("a");
+        a.nodes.push(Node::new("n", XYPosition::new(0.0, 0.0)));
+        let mut operations = a.commit();
+        a.nodes[0].position = XYPosition::new(100.0, 0.0);
+        operations.extend(a.commit());
+
+        let mut b = Peer::new("b");
+        operations.reverse();
+        b.receive(operations);
+        assert_eq!(b.position("n"), Some(XYPosition::new(100.0, 0.0)));
+        assert!(b.engine.pending.is_empty());
+    }
+
+    #[test]
+    fn connecting_to_a_concurrently_removed_node_drops_the_edge() {
+        let mut a = Peer::new("a");
+        a.nodes.push(Node::new("1", XYPosition::new(0.0, 0.0)));
+        a.nodes.push(Node::new("2", XYPosition::new(100.0, 0.0)));
+        let mut b = Peer::new("b");
+        b.receive(a.commit());
+
+        a.nodes.retain(|node| node.id != "2");
+        let removal = a.commit();
+        b.edges.push(Edge::new("e", "1", "2"));
+        let connection = b.commit();
+
+        a.receive(connection);
+        b.receive(removal);
+        assert!(a.edges.is_empty());
+        assert!(b.edges.is_empty());
+        assert_eq!(a.nodes.len(), 1);
+        assert_eq!(b.nodes.len(), 1);
+    }
+
+    #[test]
+    fn concurrent_writes_are_won_by_the_higher_peer() {
+        let mut a = Peer::new("a");
+        a.nodes.push(Node::new("n", XYPosition::new(0.0, 0.0)));
+        let mut b = Peer::new("b");
+        b.receive(a.commit());
+
+        a.nodes[0].position = XYPosition::new(10.0, 0.0);
+        let from_a = a.commit();
+        b.nodes[0].position = XYPosition::new(20.0, 0.0);
+        let from_b = b.commit();
+        assert_eq!(from_a[0].timestamp.counter, from_b[0].timestamp.counter);
+
+        a.receive(from_b);
+        b.receive(from_a);
+        assert_eq!(a.position("n"), Some(XYPosition::new(20.0, 0.0)));
+        assert_eq!(b.position("n"), Some(XYPosition::new(20.0, 0.0)));
+    }
+}
@@ -0,0 +1,11 @@
+//! Collaborative editing: serializable operations, conflict resolution and transports
+
+mod clock;
+mod engine;
+mod operation;
+mod transport;
+
+pub use clock::*;
+pub use engine::*;
+pub use operation::*;
+pub use transport::*;
@@ -0,0 +1,85 @@
+//! Serializable sync messages
+
+use super::Timestamp;
+use crate::types::{Edge, Node, XYPosition};
+use serde::{Deserialize, Serialize};
+
+/// Edit of the shared graph. Selection, dragging and measured sizes are local
+/// and never synced.
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum OperationKind<N: Clone + PartialEq + Default = (), E: Clone + PartialEq + Default = ()> {
+    /// Add a node or overwrite all of its fields
+    PutNode {
+        node: Box<Node<N>>,
+    },
+    SetNodePosition {
+        id: String,
+        position: XYPosition,
+    },
+    /// Set the node's `width` and `height`
+    SetNodeSize {
+        id: String,
+        width: Option<f64>,
+        height: Option<f64>,
+    },
+    RemoveNode {
+        id: String,
+    },
+    /// Add an edge or overwrite all of its fields
+    PutEdge {
+        edge: Box<Edge<E>>,
+    },
+    SetEdgePoints {
+        id: String,
+        points: Vec<XYPosition>,
+    },
+    RemoveEdge {
+        id: String,
+    },
+}
+
+/// Operation stamped with the Lamport time it was made at
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct SyncOperation<N: Clone + PartialEq + Default = (), E: Clone + PartialEq + Default = ()> {
+    /// Unique id, `<peer>:<counter>`
+    pub id: String,
+    pub timestamp: Timestamp,
+    pub kind: OperationKind<N, E>,
+}
+
+impl<N: Clone + PartialEq + Default, E: Clone + PartialEq + Default> SyncOperation<N, E> {
+    pub fn new(timestamp: Timestamp, kind: OperationKind<N, E>) -> Self {
+        Self {
+            id: format!("{}:{}", timestamp.peer, timestamp.counter),
+            timestamp,
+            kind,
+        }
+    }
+}
+
+/// Where a peer's pointer is and what it has selected
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct PeerPresence {
+    pub peer: String,
+    pub name: String,
+    /// CSS color of the peer's cursor and selection outlines
+    pub color: String,
+    /// Pointer position in flow coordinates
+    #[serde(default)]
+    pub cursor: Option<XYPosition>,
+    #[serde(default)]
+    pub selected_nodes: Vec<String>,
+    #[serde(default)]
+    pub selected_edges: Vec<String>,
+}
+
+/// Message exchanged between peers
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum SyncMessage<N: Clone + PartialEq + Default = (), E: Clone + PartialEq + Default = ()> {
+    Operations(Vec<SyncOperation<N, E>>),
+    Presence(PeerPresence),
+    /// The peer left, drop its presence
+    Leave {
+        peer: String,
+    },
+}
@@ -0,0 +1,148 @@
+//! Sync transports
+
+use super::SyncMessage;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::rc::Rc;
+
+/// Callback a transport delivers incoming messages to
+pub type SyncReceiver<N, E> = Rc<dyn Fn(SyncMessage<N, E>)>;
+
+/// Carries sync messages between peers, e.g. over a WebSocket. Messages should
+/// be delivered on the UI thread, in the order each peer sent them.
+pub trait SyncTransport<N: Clone + PartialEq + Default = (), E: Clone + PartialEq + Default = ()> {
+    /// Sends a message to every other peer
+    fn send(&self, message: SyncMessage<N, E>);
+    /// Sets the callback messages from other peers are delivered to
+    fn set_receiver(&self, receiver: SyncReceiver<N, E>);
+}
+
+/// Shared transport handle, usable as a component prop
+pub struct SharedTransport<N: Clone + PartialEq + Default = (), E: Clone + PartialEq + Default = ()>(
+    pub Rc<dyn SyncTransport<N, E>>,
+);
+
+impl<N: Clone + PartialEq + Default, E: Clone + PartialEq + Default> SharedTransport<N, E> {
+    pub fn new(transport: impl SyncTransport<N, E> + 'static) -> Self {
+        Self(Rc::new(transport))
+    }
+}
+
+impl<N: Clone + PartialEq + Default, E: Clone + PartialEq + Default> Clone
+    for SharedTransport<N, E>
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<N: Clone + PartialEq + Default, E: Clone + PartialEq + Default> PartialEq
+    for SharedTransport<N, E>
+{
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+type JsonReceiver = Rc<dyn Fn(&str)>;
+
+#[derive(Default)]
+struct HubInner {
+    receivers: Vec<Option<JsonReceiver>>,
+    queue: VecDeque<(usize, String)>,
+    delivering: bool,
+}
+
+/// In-process hub connecting [`LoopbackTransport`]s, for tests or several flows
+/// on one page. Messages go through JSON like they would over a network, and
+/// are delivered in send order once the current delivery finishes.
+#[derive(Clone, Default)]
+pub struct LoopbackHub {
+    inner: Rc<RefCell<HubInner>>,
+}
+
+impl LoopbackHub {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Adds a peer to the hub
+    pub fn connect<N, E>(&self) -> LoopbackTransport<N, E>
+    where
+        N: Clone + PartialEq + Default + Serialize + DeserializeOwned + 'static,
+        E: Clone + PartialEq + Default + Serialize + DeserializeOwned + 'static,
+    {
+        let mut inner = self.inner.borrow_mut();
+        inner.receivers.push(None);
+        LoopbackTransport {
+            hub: self.clone(),
+            index: inner.receivers.len() - 1,
+            _marker: std::marker::PhantomData,
+        }
+    }
+
+    fn deliver(&self) {
+        {
+            let mut inner = self.inner.borrow_mut();
+            if inner.delivering {
+                return;
+            }
+            inner.delivering = true;
+        }
+        loop {
+            let next = self.inner.borrow_mut().queue.pop_front();
+            let Some((sender, json)) = next else {
+                break;
+            };
+            let receivers: Vec<JsonReceiver> = self
+                .inner
+                .borrow()
+                .receivers
+                .iter()
+                .enumerate()
+                .filter(|(index, _)| *index != sender)
+                .filter_map(|(_, receiver)| receiver.clone())
+                .collect();
+            for receiver in receivers {
+                receiver(&json);
+            }
+        }
+        self.inner.borrow_mut().delivering = false;
+    }
+}
+
+/// One peer of a [`LoopbackHub`]
+pub struct LoopbackTransport<N = (), E = ()> {
+    hub: LoopbackHub,
+    index: usize,
+    _marker: std::marker::PhantomData<(N, E)>,
+}
+
+impl<N, E> SyncTransport<N, E> for LoopbackTransport<N, E>
+where
+    N: Clone + PartialEq + Default + Serialize + DeserializeOwned + 'static,
+    E: Clone + PartialEq + Default + Serialize + DeserializeOwned + 'static,
+{
+    fn send(&self, message: SyncMessage<N, E>) {
+        let Ok(json) = serde_json::to_string(&message) else {
+            return;
+        };
+        self.hub
+            .inner
+            .borrow_mut()
+            .queue
+            .push_back((self.index, json));
+        self.hub.deliver();
+    }
+
+    fn set_receiver(&self, receiver: SyncReceiver<N, E>) {
+        let json_receiver: JsonReceiver = Rc::new(move |json: &str| {
+            if let Ok(message) = serde_json::from_str::<SyncMessage<N, E>>(json) {
+                receiver(message);
+            }
+        });
+        self.hub.inner.borrow_mut().receivers[self.index] = Some(json_receiver);
+    }
+}
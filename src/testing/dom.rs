@@ -0,0 +1,185 @@
+//! Mutation tracker standing in for the browser DOM
+
+use dioxus::dioxus_core::{
+    AttributeValue, ElementId, Template, TemplateAttribute, TemplateNode, WriteMutations,
+};
+use std::collections::{BTreeMap, HashMap, HashSet};
+
+/// What the harness knows about a mounted element
+#[derive(Clone, Debug, Default)]
+pub(crate) struct HeadlessElement {
+    pub(crate) attributes: HashMap<String, String>,
+    pub(crate) listeners: HashSet<&'static str>,
+}
+
+impl HeadlessElement {
+    pub(crate) fn attribute(&self, name: &str) -> Option<&str> {
+        self.attributes.get(name).map(String::as_str)
+    }
+
+    pub(crate) fn has_class(&self, class: &str) -> bool {
+        self.attribute("class")
+            .is_some_and(|value| value.split_whitespace().any(|name| name == class))
+    }
+}
+
+/// Keeps the attributes and listeners of every element the virtual DOM
+/// creates, so gestures can find their targets
+#[derive(Default)]
+pub(crate) struct HeadlessDom {
+    elements: BTreeMap<usize, HeadlessElement>,
+    /// Template node each entry of the renderer stack was loaded from
+    stack: Vec<Option<&'static TemplateNode>>,
+    edits: usize,
+}
+
+impl HeadlessDom {
+    /// Number of mutations applied so far
+    pub(crate) fn edits(&self) -> usize {
+        self.edits
+    }
+
+    /// First element, in creation order, matching `predicate`
+    pub(crate) fn find(
+        &self,
+        predicate: impl Fn(&HeadlessElement) -> bool,
+    ) -> Option<(ElementId, &HeadlessElement)> {
+        self.elements
+            .iter()
+            .find(|(_, element)| predicate(element))
+            .map(|(id, element)| (ElementId(*id), element))
+    }
+
+    pub(crate) fn filter(
+        &self,
+        predicate: impl Fn(&HeadlessElement) -> bool,
+    ) -> Vec<&HeadlessElement> {
+        self.elements
+            .values()
+            .filter(|element| predicate(element))
+            .collect()
+    }
+
+    fn mount(&mut self, id: ElementId, node: Option<&'static TemplateNode>) {
+        let mut element = HeadlessElement::default();
+        if let Some(TemplateNode::Element { attrs, .. }) = node {
+            for attr in attrs.iter() {
+                if let TemplateAttribute::Static { name, value, .. } = attr {
+                    element
+                        .attributes
+                        .insert(name.to_string(), value.to_string());
+                }
+            }
+        }
+        self.elements.insert(id.0, element);
+        self.edits += 1;
+    }
+
+    fn pop(&mut self, count: usize) {
+        let len = self.stack.len().saturating_sub(count);
+        self.stack.truncate(len);
+        self.edits += 1;
+    }
+}
+
+impl WriteMutations for HeadlessDom {
+    fn append_children(&mut self, _id: ElementId, m: usize) {
+        self.pop(m);
+    }
+
+    fn assign_node_id(&mut self, path: &'static [u8], id: ElementId) {
+        let mut node = self.stack.last().copied().flatten();
+        for index in path {
+            node = match node {
+                Some(TemplateNode::Element { children, .. }) => children.get(*index as usize),
+                _ => None,
+            };
+        }
+        self.mount(id, node);
+    }
+
+    fn create_placeholder(&mut self, id: ElementId) {
+        self.mount(id, None);
+        self.stack.push(None);
+    }
+
+    fn create_text_node(&mut self, _value: &str, id: ElementId) {
+        self.mount(id, None);
+        self.stack.push(None);
+    }
+
+    fn load_template(&mut self, template: Template, index: usize, id: ElementId) {
+        let root = template.roots.get(index);
+        self.mount(id, root);
+        self.stack.push(root);
+    }
+
+    fn replace_node_with(&mut self, id: ElementId, m: usize) {
+        self.elements.remove(&id.0);
+        self.pop(m);
+    }
+
+    fn replace_placeholder_with_nodes(&mut self, _path: &'static [u8], m: usize) {
+        self.pop(m);
+    }
+
+    fn insert_nodes_after(&mut self, _id: ElementId, m: usize) {
+        self.pop(m);
+    }
+
+    fn insert_nodes_before(&mut self, _id: ElementId, m: usize) {
+        self.pop(m);
+    }
+
+    fn set_attribute(
+        &mut self,
+        name: &'static str,
+        _ns: Option<&'static str>,
+        value: &AttributeValue,
+        id: ElementId,
+    ) {
+        self.edits += 1;
+        let Some(element) = self.elements.get_mut(&id.0) else {
+            return;
+        };
+        let value = match value {
+            AttributeValue::Text(text) => text.clone(),
+            AttributeValue::Float(float) => float.to_string(),
+            AttributeValue::Int(int) => int.to_string(),
+            AttributeValue::Bool(bool) => bool.to_string(),
+            AttributeValue::None => {
+                element.attributes.remove(name);
+                return;
+            }
+            AttributeValue::Listener(_) | AttributeValue::Any(_) => return,
+        };
+        element.attributes.insert(name.to_string(), value);
+    }
+
+    fn set_node_text(&mut self, _value: &str, _id: ElementId) {
+        self.edits += 1;
+    }
+
+    fn create_event_listener(&mut self, name: &'static str, id: ElementId) {
+        self.edits += 1;
+        if let Some(element) = self.elements.get_mut(&id.0) {
+            element.listeners.insert(name);
+        }
+    }
+
+    fn remove_event_listener(&mut self, name: &'static str, id: ElementId) {
+        self.edits += 1;
+        if let Some(element) = self.elements.get_mut(&id.0) {
+            element.listeners.remove(name);
+        }
+    }
+
+    fn remove_node(&mut self, id: ElementId) {
+        self.edits += 1;
+        self.elements.remove(&id.0);
+    }
+
+    fn push_root(&mut self, _id: ElementId) {
+        self.stack.push(None);
+    }
+}
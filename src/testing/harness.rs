@@ -0,0 +1,843 @@
+//! Headless `DioxusFlow` mount with simulated gestures
+
+use super::dom::{HeadlessDom, HeadlessElement};
+use crate::components::{DioxusFlow, EdgeComponentProps, KeyPressDispatcher, NodeProps};
+use crate::state::FlowState;
+use crate::types::{
+    Connection, Dimensions, Edge, EdgeChange, HandleBound, HandleBounds, KeyModifiers, Node,
+    NodeChange, Position, Rect, Viewport, XYPosition,
+};
+use dioxus::dioxus_core::{ElementId, Event};
+use dioxus::prelude::*;
+use dioxus_html::{
+    PlatformEventData, SerializedHtmlEventConverter, SerializedPointInteraction,
+    SerializedPointerData, SerializedWheelData,
+};
+use std::any::Any;
+use std::cell::RefCell;
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+
+/// Upper bound of render and measure rounds in [`FlowHarness::settle`]
+const MAX_SETTLE_PASSES: usize = 32;
+
+/// Sizes the harness reports in place of browser layout
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct FakeMeasure {
+    /// Measured size of nodes without an explicit `width` and `height`
+    pub node_size: Dimensions,
+    /// Width and height of every handle
+    pub handle_size: f64,
+    /// Size of the flow container
+    pub flow_size: Dimensions,
+}
+
+impl Default for FakeMeasure {
+    fn default() -> Self {
+        Self {
+            node_size: Dimensions {
+                width: 150.0,
+                height: 40.0,
+            },
+            handle_size: 8.0,
+            flow_size: Dimensions {
+                width: 1000.0,
+                height: 800.0,
+            },
+        }
+    }
+}
+
+/// Options of a [`FlowHarness`]
+pub struct HarnessOptions<
+    N: Clone + PartialEq + Default + 'static = (),
+    E: Clone + PartialEq + Default + 'static = (),
+> {
+    pub measure: FakeMeasure,
+    pub node_types: Option<HashMap<String, Component<NodeProps<N, E>>>>,
+    pub edge_types: Option<HashMap<String, Component<EdgeComponentProps<E>>>>,
+}
+
+impl<N: Clone + PartialEq + Default + 'static, E: Clone + PartialEq + Default + 'static> Default
+    for HarnessOptions<N, E>
+{
+    fn default() -> Self {
+        Self {
+            measure: FakeMeasure::default(),
+            node_types: None,
+            edge_types: None,
+        }
+    }
+}
+
+impl<N: Clone + PartialEq + Default + 'static, E: Clone + PartialEq + Default + 'static>
+    HarnessOptions<N, E>
+{
+    pub fn with_measure(mut self, measure: FakeMeasure) -> Self {
+        self.measure = measure;
+        self
+    }
+
+    pub fn with_node_types(
+        mut self,
+        node_types: HashMap<String, Component<NodeProps<N, E>>>,
+    ) -> Self {
+        self.node_types = Some(node_types);
+        self
+    }
+
+    pub fn with_edge_types(
+        mut self,
+        edge_types: HashMap<String, Component<EdgeComponentProps<E>>>,
+    ) -> Self {
+        self.edge_types = Some(edge_types);
+        self
+    }
+}
+
+/// Change handlers passed to the mounted flow
+struct HarnessHandlers<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+> {
+    on_nodes_change: EventHandler<Vec<NodeChange<N>>>,
+    on_edges_change: EventHandler<Vec<EdgeChange<E>>>,
+    on_connect: EventHandler<Connection>,
+}
+
+impl<N: Clone + PartialEq + Default + 'static, E: Clone + PartialEq + Default + 'static> Clone
+    for HarnessHandlers<N, E>
+{
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<N: Clone + PartialEq + Default + 'static, E: Clone + PartialEq + Default + 'static> Copy
+    for HarnessHandlers<N, E>
+{
+}
+
+struct HarnessLogInner<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+> {
+    state: Option<FlowState<N, E>>,
+    key_press: Option<KeyPressDispatcher>,
+    node_changes: Vec<NodeChange<N>>,
+    edge_changes: Vec<EdgeChange<E>>,
+    connections: Vec<Connection>,
+}
+
+/// What the mounted flow emitted, shared with the harness
+struct HarnessLog<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(Rc<RefCell<HarnessLogInner<N, E>>>);
+
+impl<N: Clone + PartialEq + Default + 'static, E: Clone + PartialEq + Default + 'static> Clone
+    for HarnessLog<N, E>
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<N: Clone + PartialEq + Default + 'static, E: Clone + PartialEq + Default + 'static> PartialEq
+    for HarnessLog<N, E>
+{
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl<N: Clone + PartialEq + Default + 'static, E: Clone + PartialEq + Default + 'static>
+    HarnessLog<N, E>
+{
+    fn state(&self) -> Option<FlowState<N, E>> {
+        self.0.borrow().state.clone()
+    }
+}
+
+struct HarnessProps<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+> {
+    nodes: Vec<Node<N>>,
+    edges: Vec<Edge<E>>,
+    node_types: Option<HashMap<String, Component<NodeProps<N, E>>>>,
+    edge_types: Option<HashMap<String, Component<EdgeComponentProps<E>>>>,
+    log: HarnessLog<N, E>,
+}
+
+impl<N: Clone + PartialEq + Default + 'static, E: Clone + PartialEq + Default + 'static> Clone
+    for HarnessProps<N, E>
+{
+    fn clone(&self) -> Self {
+        Self {
+            nodes: self.nodes.clone(),
+            edges: self.edges.clone(),
+            node_types: self.node_types.clone(),
+            edge_types: self.edge_types.clone(),
+            log: self.log.clone(),
+        }
+    }
+}
+
+/// Mounts the flow with change handlers that log, then apply
+fn harness_root<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    props: HarnessProps<N, E>,
+) -> Element {
+    let log = props.log.clone();
+    let handlers = use_hook(move || HarnessHandlers {
+        on_nodes_change: log_node_changes(log.clone()),
+        on_edges_change: log_edge_changes(log.clone()),
+        on_connect: log_connections(log.clone()),
+    });
+    rsx! {
+        DioxusFlow::<N, E> {
+            default_nodes: props.nodes.clone(),
+            default_edges: props.edges.clone(),
+            node_types: props.node_types.clone(),
+            edge_types: props.edge_types.clone(),
+            on_nodes_change: handlers.on_nodes_change,
+            on_edges_change: handlers.on_edges_change,
+            on_connect: handlers.on_connect,
+            StateProbe::<N, E> { log: props.log.clone() }
+        }
+    }
+}
+
+/// Hands the flow's state and key press dispatcher to the harness
+#[component]
+fn StateProbe<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    log: HarnessLog<N, E>,
+) -> Element {
+    let state = use_context::<FlowState<N, E>>();
+    let key_press = use_context::<KeyPressDispatcher>();
+    use_hook(move || {
+        let mut inner = log.0.borrow_mut();
+        inner.state = Some(state);
+        inner.key_press = Some(key_press);
+    });
+    rsx! {}
+}
+
+/// `DioxusFlow` mounted in a headless `VirtualDom`, for testing flows natively.
+///
+/// Layout comes from a [`FakeMeasure`]: nodes get their `width` and `height` or
+/// the fake node size, and handles are spread evenly along their node's side.
+/// Measured sizes are applied to the state directly and are not logged, so the
+/// logged changes are the ones the gestures caused. Change handlers log what
+/// the flow emits and then apply it, like a typical controlled flow.
+pub struct FlowHarness<
+    N: Clone + PartialEq + Default + 'static = (),
+    E: Clone + PartialEq + Default + 'static = (),
+> {
+    dom: VirtualDom,
+    tracker: HeadlessDom,
+    log: HarnessLog<N, E>,
+    measure: FakeMeasure,
+}
+
+impl<N: Clone + PartialEq + Default + 'static, E: Clone + PartialEq + Default + 'static>
+    FlowHarness<N, E>
+{
+    pub fn new(nodes: Vec<Node<N>>, edges: Vec<Edge<E>>) -> Self {
+        Self::with_options(nodes, edges, HarnessOptions::default())
+    }
+
+    pub fn with_options(
+        nodes: Vec<Node<N>>,
+        edges: Vec<Edge<E>>,
+        options: HarnessOptions<N, E>,
+    ) -> Self {
+        dioxus_html::set_event_converter(Box::new(SerializedHtmlEventConverter));
+        let log = HarnessLog(Rc::new(RefCell::new(HarnessLogInner {
+            state: None,
+            key_press: None,
+            node_changes: Vec::new(),
+            edge_changes: Vec::new(),
+            connections: Vec::new(),
+        })));
+        let props = HarnessProps {
+            nodes,
+            edges,
+            node_types: options.node_types,
+            edge_types: options.edge_types,
+            log: log.clone(),
+        };
+        let mut harness = Self {
+            dom: VirtualDom::new_with_props(harness_root::<N, E>, props),
+            tracker: HeadlessDom::default(),
+            log,
+            measure: options.measure,
+        };
+        harness.dom.rebuild(&mut harness.tracker);
+        harness.settle();
+        harness
+    }
+
+    /// Runs effects, renders and measures until nothing changes
+    pub fn settle(&mut self) {
+        let mut quiet = 0;
+        for _ in 0..MAX_SETTLE_PASSES {
+            let edits = self.tracker.edits();
+            self.dom.render_immediate(&mut self.tracker);
+            let measured = self.dom.in_runtime(|| self.measure_pass());
+            if measured || self.tracker.edits() != edits {
+                quiet = 0;
+            } else {
+                quiet += 1;
+                if quiet == 2 {
+                    return;
+                }
+            }
+        }
+    }
+
+    /// Runs `f` with the flow state, e.g. to call `FlowState` methods, then settles
+    pub fn update<R>(&mut self, f: impl FnOnce(&mut FlowState<N, E>) -> R) -> R {
+        let mut state = self.state();
+        let result = self.dom.in_runtime(|| f(&mut state));
+        self.settle();
+        result
+    }
+
+    /// The mounted flow's state. Read it with `peek` outside [`Self::update`].
+    pub fn state(&self) -> FlowState<N, E> {
+        self.log
+            .state()
+            .expect("the flow state is captured on the first render")
+    }
+
+    pub fn nodes(&self) -> Vec<Node<N>> {
+        self.dom.in_runtime(|| self.state().nodes.peek().clone())
+    }
+
+    pub fn edges(&self) -> Vec<Edge<E>> {
+        self.dom.in_runtime(|| self.state().edges.peek().clone())
+    }
+
+    pub fn node(&self, id: &str) -> Option<Node<N>> {
+        self.nodes().into_iter().find(|node| node.id == id)
+    }
+
+    pub fn edge(&self, id: &str) -> Option<Edge<E>> {
+        self.edges().into_iter().find(|edge| edge.id == id)
+    }
+
+    pub fn viewport(&self) -> Viewport {
+        self.dom.in_runtime(|| *self.state().viewport.peek())
+    }
+
+    /// Returns and clears the node changes the flow emitted
+    pub fn take_node_changes(&mut self) -> Vec<NodeChange<N>> {
+        std::mem::take(&mut self.log.0.borrow_mut().node_changes)
+    }
+
+    /// Returns and clears the edge changes the flow emitted, including the
+    /// additions made for new connections
+    pub fn take_edge_changes(&mut self) -> Vec<EdgeChange<E>> {
+        std::mem::take(&mut self.log.0.borrow_mut().edge_changes)
+    }
+
+    /// Returns and clears the connections passed to `on_connect`
+    pub fn take_connections(&mut self) -> Vec<Connection> {
+        std::mem::take(&mut self.log.0.borrow_mut().connections)
+    }
+
+    /// Drags node `id` by `delta` screen pixels, in a few pointer moves.
+    /// Panics if the node isn't rendered and measured.
+    pub fn drag_node(&mut self, id: &str, delta: XYPosition) {
+        let node = self
+            .find_element(|element| {
+                element.has_class("dioxus-flow__node") && element.attribute("data-id") == Some(id)
+            })
+            .unwrap_or_else(|| panic!("node {id} is not rendered"));
+        let start = self.node_center(id);
+        let pane = self.pane();
+        self.pointer_event("pointerdown", node, start, KeyModifiers::default());
+        for step in 1..=4 {
+            let t = step as f64 / 4.0;
+            let position = XYPosition::new(start.x + delta.x * t, start.y + delta.y * t);
+            self.pointer_event("pointermove", pane, position, KeyModifiers::default());
+        }
+        let end = XYPosition::new(start.x + delta.x, start.y + delta.y);
+        self.pointer_event("pointerup", pane, end, KeyModifiers::default());
+    }
+
+    /// Drags a connection from a source handle and releases it on a target handle.
+    /// `None` picks the node's handle without an id. Panics if either handle isn't
+    /// rendered and measured.
+    pub fn connect(
+        &mut self,
+        source_node: &str,
+        source_handle: Option<&str>,
+        target_node: &str,
+        target_handle: Option<&str>,
+    ) {
+        let source = self.handle_element(source_node, source_handle, "source");
+        let target = self.handle_element(target_node, target_handle, "target");
+        let (start, end) = (source.1, target.1);
+        let pane = self.pane();
+        self.pointer_event("pointerdown", source.0, start, KeyModifiers::default());
+        let middle = XYPosition::new((start.x + end.x) / 2.0, (start.y + end.y) / 2.0);
+        self.pointer_event("pointermove", pane, middle, KeyModifiers::default());
+        self.pointer_event("pointermove", pane, end, KeyModifiers::default());
+        self.dispatch(
+            "pointerenter",
+            target.0,
+            pointer_data(end, KeyModifiers::default()),
+            false,
+        );
+        self.pointer_event("pointerup", target.0, end, KeyModifiers::default());
+    }
+
+    /// Shift-drags a selection box over `rect`, given in screen pixels
+    pub fn box_select(&mut self, rect: Rect) {
+        let pane = self.pane();
+        let shift = KeyModifiers {
+            shift: true,
+            ..Default::default()
+        };
+        let start = XYPosition::new(rect.x, rect.y);
+        let end = XYPosition::new(rect.x + rect.width, rect.y + rect.height);
+        self.pointer_event("pointerdown", pane, start, shift);
+        self.pointer_event("pointermove", pane, end, shift);
+        self.pointer_event("pointerup", pane, end, shift);
+    }
+
+    /// Presses `key` with the flow focused, returns whether a command consumed it
+    pub fn press_key(&mut self, key: &str, modifiers: KeyModifiers) -> bool {
+        let key_press = self
+            .log
+            .0
+            .borrow()
+            .key_press
+            .clone()
+            .expect("the key press dispatcher is captured on the first render");
+        let consumed = self
+            .dom
+            .in_runtime(|| (key_press.0)(key, modifiers, false, false));
+        self.settle();
+        consumed
+    }
+
+    /// Scrolls the wheel by `delta_y` pixels at `position`, in screen pixels
+    pub fn wheel_zoom(&mut self, position: XYPosition, delta_y: f64) {
+        let pane = self.pane();
+        let data = SerializedWheelData {
+            mouse: point_interaction(position, KeyModifiers::default()),
+            delta_mode: 0,
+            delta_x: 0.0,
+            delta_y,
+            delta_z: 0.0,
+        };
+        self.dispatch("wheel", pane, data, true);
+    }
+
+    fn pointer_event(
+        &mut self,
+        name: &str,
+        element: ElementId,
+        position: XYPosition,
+        modifiers: KeyModifiers,
+    ) {
+        self.dispatch(name, element, pointer_data(position, modifiers), true);
+    }
+
+    fn dispatch(&mut self, name: &str, element: ElementId, data: impl Any, bubbles: bool) {
+        let data: Rc<dyn Any> = Rc::new(PlatformEventData::new(Box::new(data)));
+        self.dom
+            .runtime()
+            .handle_event(name, Event::new(data, bubbles), element);
+        self.settle();
+    }
+
+    fn find_element(&self, predicate: impl Fn(&HeadlessElement) -> bool) -> Option<ElementId> {
+        self.tracker.find(predicate).map(|(id, _)| id)
+    }
+
+    /// The pan and zoom pane, panicking if the flow isn't rendered with a measured size
+    fn pane(&self) -> ElementId {
+        let pane = self
+            .find_element(|element| element.has_class("dioxus-flow__panzoom"))
+            .expect("the flow renders a pan and zoom pane");
+        let state = self.state();
+        assert!(
+            *state.width.peek() > 0.0 && *state.height.peek() > 0.0,
+            "the flow has no measured size"
+        );
+        pane
+    }
+
+    fn node_center(&self, id: &str) -> XYPosition {
+        self.dom.in_runtime(|| {
+            let state = self.state();
+            let lookup = state.node_lookup.peek();
+            let internal = lookup
+                .get(id)
+                .unwrap_or_else(|| panic!("node {id} is not in the flow"));
+            if internal.node.measured_width.is_none() || internal.node.measured_height.is_none() {
+                panic!("node {id} has no measured size");
+            }
+            let center = FlowState::<N, E>::node_flow_center(internal);
+            state.flow_to_screen_position(center)
+        })
+    }
+
+    /// Handle element and its screen center
+    fn handle_element(
+        &self,
+        node_id: &str,
+        handle_id: Option<&str>,
+        handle_type: &str,
+    ) -> (ElementId, XYPosition) {
+        let class = format!("dioxus-flow__handle-{handle_type}");
+        let name = format!("{handle_type} handle {node_id}:{}", handle_id.unwrap_or("<none>"));
+        let handle_id = handle_id.unwrap_or_default();
+        let element = self
+            .find_element(|element| {
+                element.has_class(&class)
+                    && element.attribute("data-node-id") == Some(node_id)
+                    && element.attribute("data-handle-id").unwrap_or_default() == handle_id
+            })
+            .unwrap_or_else(|| panic!("{name} is not rendered"));
+        let center = self.dom.in_runtime(|| {
+            let state = self.state();
+            let lookup = state.node_lookup.peek();
+            let internal = lookup
+                .get(node_id)
+                .unwrap_or_else(|| panic!("node {node_id} is not in the flow"));
+            let bounds = internal
+                .handle_bounds
+                .as_ref()
+                .unwrap_or_else(|| panic!("node {node_id} has no measured handle bounds"));
+            let list = match handle_type {
+                "source" => &bounds.source,
+                _ => &bounds.target,
+            };
+            let bound = list
+                .iter()
+                .find(|bound| bound.id.as_deref().unwrap_or_default() == handle_id)
+                .unwrap_or_else(|| panic!("{name} has no measured bounds"));
+            let center = XYPosition::new(
+                internal.position_absolute.x + bound.x + bound.width / 2.0,
+                internal.position_absolute.y + bound.y + bound.height / 2.0,
+            );
+            state.flow_to_screen_position(center)
+        });
+        (element, center)
+    }
+
+    /// Applies the fake layout, returns whether anything changed
+    fn measure_pass(&self) -> bool {
+        let mut state = self.state();
+        let mut changed = false;
+        let flow_size = self.measure.flow_size;
+        if *state.width.peek() != flow_size.width {
+            state.width.set(flow_size.width);
+            changed = true;
+        }
+        if *state.height.peek() != flow_size.height {
+            state.height.set(flow_size.height);
+            changed = true;
+        }
+
+        let rendered: Vec<String> = self
+            .tracker
+            .filter(|element| element.has_class("dioxus-flow__node"))
+            .into_iter()
+            .filter_map(|element| element.attribute("data-id").map(str::to_string))
+            .collect();
+        let dimension_changes: Vec<NodeChange<N>> = state
+            .nodes
+            .peek()
+            .iter()
+            .filter(|node| rendered.contains(&node.id))
+            .filter_map(|node| {
+                let dimensions = Dimensions {
+                    width: node.width.unwrap_or(self.measure.node_size.width),
+                    height: node.height.unwrap_or(self.measure.node_size.height),
+                };
+                let measured = node.measured_width == Some(dimensions.width)
+                    && node.measured_height == Some(dimensions.height);
+                (!measured).then(|| NodeChange::dimensions(node.id.clone(), dimensions, false))
+            })
+            .collect();
+        if !dimension_changes.is_empty() {
+            state.apply_node_changes(dimension_changes);
+            changed = true;
+        }
+
+        for (node_id, bounds) in self.handle_bounds() {
+            let stale = state
+                .node_lookup
+                .peek()
+                .get(&node_id)
+                .is_some_and(|internal| internal.handle_bounds.as_ref() != Some(&bounds));
+            if stale {
+                state.update_handle_bounds(&node_id, bounds);
+                changed = true;
+            }
+        }
+        changed
+    }
+
+    /// Fake handle bounds of every rendered node with handles
+    fn handle_bounds(&self) -> BTreeMap<String, HandleBounds> {
+        let state = self.state();
+        let nodes = state.node_lookup.peek();
+        let mut sides: BTreeMap<(String, String), Vec<&HeadlessElement>> = BTreeMap::new();
+        for element in self
+            .tracker
+            .filter(|element| element.has_class("dioxus-flow__handle"))
+        {
+            let (Some(node_id), Some(side)) = (
+                element.attribute("data-node-id"),
+                element.attribute("data-handle-pos"),
+            ) else {
+                continue;
+            };
+            sides
+                .entry((node_id.to_string(), side.to_string()))
+                .or_default()
+                .push(element);
+        }
+
+        let size = self.measure.handle_size;
+        let mut result: BTreeMap<String, HandleBounds> = BTreeMap::new();
+        for ((node_id, side), handles) in sides {
+            let Some(internal) = nodes.get(&node_id) else {
+                continue;
+            };
+            let Dimensions { width, height } = internal.dimensions;
+            let position = match side.as_str() {
+                "Left" => Position::Left,
+                "Right" => Position::Right,
+                "Top" => Position::Top,
+                _ => Position::Bottom,
+            };
+            let bounds = result.entry(node_id).or_default();
+            let count = handles.len() as f64;
+            for (index, element) in handles.into_iter().enumerate() {
+                let along = (index as f64 + 1.0) / (count + 1.0);
+                let (x, y) = match position {
+                    Position::Left => (0.0, height * along),
+                    Position::Right => (width, height * along),
+                    Position::Top => (width * along, 0.0),
+                    Position::Bottom => (width * along, height),
+                };
+                let bound = HandleBound {
+                    id: element
+                        .attribute("data-handle-id")
+                        .filter(|id| !id.is_empty())
+                        .map(str::to_string),
+                    position,
+                    x: x - size / 2.0,
+                    y: y - size / 2.0,
+                    width: size,
+                    height: size,
+                    is_connectable: element.has_class("connectable"),
+                };
+                if element.has_class("dioxus-flow__handle-target") {
+                    bounds.target.push(bound);
+                } else {
+                    bounds.source.push(bound);
+                }
+            }
+        }
+        result
+    }
+}
+
+fn log_node_changes<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    log: HarnessLog<N, E>,
+) -> EventHandler<Vec<NodeChange<N>>> {
+    EventHandler::new(move |changes: Vec<NodeChange<N>>| {
+        log.0
+            .borrow_mut()
+            .node_changes
+            .extend(changes.iter().cloned());
+        if let Some(mut state) = log.state() {
+            state.apply_node_changes(changes);
+        }
+    })
+}
+
+fn log_edge_changes<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    log: HarnessLog<N, E>,
+) -> EventHandler<Vec<EdgeChange<E>>> {
+    EventHandler::new(move |changes: Vec<EdgeChange<E>>| {
+        log.0
+            .borrow_mut()
+            .edge_changes
+            .extend(changes.iter().cloned());
+        if let Some(mut state) = log.state() {
+            state.apply_edge_changes(changes);
+        }
+    })
+}
+
+fn log_connections<
+    N: Clone + PartialEq + Default + 'static,
+    E: Clone + PartialEq + Default + 'static,
+>(
+    log: HarnessLog<N, E>,
+) -> EventHandler<Connection> {
+    EventHandler::new(move |connection: Connection| {
+        let edge = crate::state::connection_to_edge::<E>(&connection, None);
+        {
+            let mut inner = log.0.borrow_mut();
+            inner.connections.push(connection);
+            inner.edge_changes.push(EdgeChange::add(edge.clone()));
+        }
+        if let Some(mut state) = log.state() {
+            state.apply_edge_changes(vec![EdgeChange::add(edge)]);
+        }
+    })
+}
+
+fn point_interaction(position: XYPosition, modifiers: KeyModifiers) -> SerializedPointInteraction {
+    serde_json::from_value(serde_json::json!({
+        "alt_key": modifiers.alt,
+        "button": 0,
+        "buttons": 1,
+        "client_x": position.x,
+        "client_y": position.y,
+        "ctrl_key": modifiers.ctrl,
+        "meta_key": modifiers.meta,
+        "offset_x": position.x,
+        "offset_y": position.y,
+        "page_x": position.x,
+        "page_y": position.y,
+        "screen_x": position.x,
+        "screen_y": position.y,
+        "shift_key": modifiers.shift,
+    }))
+    .expect("valid pointer interaction")
+}
+
+fn pointer_data(position: XYPosition, modifiers: KeyModifiers) -> SerializedPointerData {
+    serde_json::from_value(serde_json::json!({
+        "alt_key": modifiers.alt,
+        "button": 0,
+        "buttons": 1,
+        "client_x": position.x,
+        "client_y": position.y,
+        "ctrl_key": modifiers.ctrl,
+        "meta_key": modifiers.meta,
+        "offset_x": position.x,
+        "offset_y": position.y,
+        "page_x": position.x,
+        "page_y": position.y,
+        "screen_x": position.x,
+        "screen_y": position.y,
+        "shift_key": modifiers.shift,
+        "pointer_id": 1,
+        "width": 1.0,
+        "height": 1.0,
+        "pressure": 0.5,
+        "tangential_pressure": 0.0,
+        "tilt_x": 0,
+        "tilt_y": 0,
+        "twist": 0,
+        "pointer_type": "mouse",
+        "is_primary": true,
+    }))
+    .expect("valid pointer data")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn two_nodes() -> Vec<Node> {
+        vec![
+            Node::new("1", XYPosition::new(0.0, 0.0)),
+            Node::new("2", XYPosition::new(300.0, 0.0)),
+        ]
+    }
+
+    #[test]
+    fn drag_node_moves_the_node() {
+        let mut harness = FlowHarness::<(), ()>::new(two_nodes(), Vec::new());
+        harness.drag_node("1", XYPosition::new(40.0, 20.0));
+        let node = harness.node("1").unwrap();
+        assert_eq!(node.position, XYPosition::new(40.0, 20.0));
+        assert!(!node.dragging);
+        assert!(
+            harness
+                .take_node_changes()
+                .iter()
+                .any(|change| matches!(change, NodeChange::Position { id, .. } if id == "1"))
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "node 3 is not rendered")]
+    fn drag_node_panics_on_a_missing_node() {
+        let mut harness = FlowHarness::<(), ()>::new(two_nodes(), Vec::new());
+        harness.drag_node("3", XYPosition::new(40.0, 20.0));
+    }
+
+    #[test]
+    #[should_panic(expected = "source handle 1:out is not rendered")]
+    fn connect_panics_on_a_missing_handle() {
+        let mut harness = FlowHarness::<(), ()>::new(two_nodes(), Vec::new());
+        harness.connect("1", Some("out"), "2", None);
+    }
+
+    #[test]
+    fn connect_adds_an_edge() {
+        let mut harness = FlowHarness::<(), ()>::new(two_nodes(), Vec::new());
+        harness.connect("1", None, "2", None);
+        let connections = harness.take_connections();
+        assert_eq!(connections.len(), 1);
+        assert_eq!(connections[0].source, "1");
+        assert_eq!(connections[0].target, "2");
+        assert_eq!(harness.edges().len(), 1);
+    }
+
+    #[test]
+    fn box_select_selects_the_enclosed_nodes() {
+        let mut harness = FlowHarness::<(), ()>::new(two_nodes(), Vec::new());
+        harness.box_select(Rect::new(-10.0, -10.0, 200.0, 100.0));
+        assert!(harness.node("1").unwrap().selected);
+        assert!(!harness.node("2").unwrap().selected);
+    }
+
+    #[test]
+    fn press_key_runs_the_keymap() {
+        let mut nodes = two_nodes();
+        nodes[0].selected = true;
+        let mut harness = FlowHarness::<(), ()>::new(nodes, Vec::new());
+        assert!(harness.press_key("Delete", KeyModifiers::default()));
+        assert!(harness.node("1").is_none());
+        assert!(harness.node("2").is_some());
+        assert!(!harness.press_key("q", KeyModifiers::default()));
+    }
+
+    #[test]
+    fn wheel_zoom_zooms_around_the_pointer() {
+        let mut harness = FlowHarness::<(), ()>::new(two_nodes(), Vec::new());
+        let before = harness.viewport();
+        harness.wheel_zoom(XYPosition::new(100.0, 100.0), -100.0);
+        let after = harness.viewport();
+        assert!(after.zoom > before.zoom);
+        assert_ne!((after.x, after.y), (before.x, before.y));
+    }
+}
@@ -0,0 +1,6 @@
+//! Headless test harness for flows, enabled with the `testing` feature
+
+mod dom;
+mod harness;
+
+pub use harness::*;
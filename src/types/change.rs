@@ -1,9 +1,10 @@
 //! Change types for nodes and edges
 
 use super::{Dimensions, Edge, Node, XYPosition};
+use serde::{Deserialize, Serialize};
 
 /// Changes that can be applied to nodes
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub enum NodeChange<T: Clone + PartialEq + Default = ()> {
     /// Position change
     Position {
@@ -68,7 +69,7 @@ impl<T: Clone + PartialEq + Default> NodeChange<T> {
 }
 
 /// Changes that can be applied to edges
-#[derive(Clone, PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub enum EdgeChange<T: Clone + PartialEq + Default = ()> {
     /// Selection change
     Selection { id: String, selected: bool },
@@ -78,6 +79,8 @@ pub enum EdgeChange<T: Clone + PartialEq + Default = ()> {
     Add { edge: Edge<T> },
     /// Replace edge
     Replace { id: String, edge: Edge<T> },
+    /// Replace edge waypoints
+    Points { id: String, points: Vec<XYPosition> },
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -109,6 +112,13 @@ impl<T: Clone + PartialEq + Default> EdgeChange<T> {
             edge,
         }
     }
+
+    pub fn points(id: impl Into<String>, points: Vec<XYPosition>) -> Self {
+        EdgeChange::Points {
+            id: id.into(),
+            points,
+        }
+    }
 }
 
 /// Apply node changes to a list of nodes
@@ -187,6 +197,11 @@ pub fn apply_edge_changes<T: Clone + PartialEq + Default>(
                     edges[idx] = edge;
                 }
             }
+            EdgeChange::Points { id, points } => {
+                if let Some(edge) = edges.iter_mut().find(|e| e.id == id) {
+                    edge.points = points;
+                }
+            }
         }
     }
     edges
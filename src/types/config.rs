@@ -43,6 +43,44 @@ pub struct AriaLabelConfig {
     pub attribution: Option<String>,
     pub edge: Option<String>,
     pub node: Option<String>,
+    /// Usage hint for focused nodes, referenced through `aria-describedby`
+    pub node_description: Option<String>,
+    /// Usage hint for focused edges
+    pub edge_description: Option<String>,
+    /// Live announcement templates; `{name}` placeholders are filled in.
+    /// `{nodes}` and `{edges}` are the selected counts.
+    pub selection_changed: Option<String>,
+    /// `{node}` is the moved node (or count of nodes), `{x}` and `{y}` its new position
+    pub node_moved: Option<String>,
+    /// `{source}` and `{target}` are the connected nodes
+    pub connection_created: Option<String>,
+    pub connection_removed: Option<String>,
+    /// `{nodes}` and `{edges}` are the deleted counts
+    pub elements_deleted: Option<String>,
+    /// `{zoom}` is the zoom level in percent
+    pub zoom_changed: Option<String>,
+}
+
+impl AriaLabelConfig {
+    pub const DEFAULT_NODE_DESCRIPTION: &'static str = "Press Enter or Space to select, arrow keys to move, Tab to reach its handles and Delete to remove.";
+    pub const DEFAULT_EDGE_DESCRIPTION: &'static str =
+        "Press Enter or Space to select and Delete to remove.";
+    pub const DEFAULT_SELECTION_CHANGED: &'static str = "{nodes} nodes and {edges} edges selected";
+    pub const DEFAULT_NODE_MOVED: &'static str = "Moved {node} to {x}, {y}";
+    pub const DEFAULT_CONNECTION_CREATED: &'static str = "Connected {source} to {target}";
+    pub const DEFAULT_CONNECTION_REMOVED: &'static str =
+        "Removed connection from {source} to {target}";
+    pub const DEFAULT_ELEMENTS_DELETED: &'static str = "Deleted {nodes} nodes and {edges} edges";
+    pub const DEFAULT_ZOOM_CHANGED: &'static str = "Zoom {zoom}%";
+
+    /// Fills the `{name}` placeholders of `template`, or of `default` when unset
+    pub fn fill(template: &Option<String>, default: &str, values: &[(&str, String)]) -> String {
+        let mut message = template.as_deref().unwrap_or(default).to_string();
+        for (name, value) in values {
+            message = message.replace(&format!("{{{name}}}"), value);
+        }
+        message
+    }
 }
 
 /// Pro configuration options
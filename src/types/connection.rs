@@ -95,6 +95,8 @@ pub struct ConnectionState {
     pub dragging: bool,
     /// Initial screen position for drag threshold checks
     pub start_screen: Option<XYPosition>,
+    /// Whether the connection is driven by the keyboard rather than the pointer
+    pub keyboard: bool,
 }
 
 impl ConnectionState {
@@ -120,6 +122,7 @@ impl ConnectionState {
             is_valid: false,
             dragging: false,
             start_screen: None,
+            keyboard: false,
         }
     }
 
@@ -147,6 +150,7 @@ impl ConnectionState {
             is_valid: false,
             dragging: false,
             start_screen: None,
+            keyboard: false,
         }
     }
 
@@ -223,6 +227,16 @@ impl ConnectionState {
     }
 }
 
+/// Handle a keyboard connection can be completed on
+#[derive(Clone, PartialEq, Debug)]
+pub struct ConnectionTarget {
+    pub node_id: String,
+    pub handle_id: Option<String>,
+    pub handle_type: HandleType,
+    /// Handle center in flow coordinates
+    pub position: XYPosition,
+}
+
 /// Props for connection line component
 #[derive(Clone, PartialEq, Debug)]
 pub struct ConnectionLineProps {
@@ -0,0 +1,183 @@
+//! Validation diagnostics
+
+use super::HandleType;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+/// How serious a diagnostic is, ordered from least to most
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug, Serialize, Deserialize)]
+pub enum DiagnosticSeverity {
+    Info,
+    Warning,
+    Error,
+}
+
+impl DiagnosticSeverity {
+    /// Lowercase name, used in CSS classes
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Self::Info => "info",
+            Self::Warning => "warning",
+            Self::Error => "error",
+        }
+    }
+}
+
+/// Element a diagnostic is attached to
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub enum DiagnosticTarget {
+    Node(String),
+    Edge(String),
+    Handle {
+        node_id: String,
+        #[serde(default)]
+        handle_id: Option<String>,
+        handle_type: HandleType,
+    },
+}
+
+impl DiagnosticTarget {
+    /// Node the target is or belongs to
+    pub fn node_id(&self) -> Option<&str> {
+        match self {
+            Self::Node(id) => Some(id),
+            Self::Handle { node_id, .. } => Some(node_id),
+            Self::Edge(_) => None,
+        }
+    }
+}
+
+/// A message from a validator about one element
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct Diagnostic {
+    pub severity: DiagnosticSeverity,
+    pub target: DiagnosticTarget,
+    pub message: String,
+    /// Machine-readable kind, e.g. "unconnected-port"
+    #[serde(default)]
+    pub code: Option<String>,
+}
+
+impl Diagnostic {
+    pub fn new(
+        severity: DiagnosticSeverity,
+        target: DiagnosticTarget,
+        message: impl Into<String>,
+    ) -> Self {
+        Self {
+            severity,
+            target,
+            message: message.into(),
+            code: None,
+        }
+    }
+
+    pub fn error(target: DiagnosticTarget, message: impl Into<String>) -> Self {
+        Self::new(DiagnosticSeverity::Error, target, message)
+    }
+
+    pub fn warning(target: DiagnosticTarget, message: impl Into<String>) -> Self {
+        Self::new(DiagnosticSeverity::Warning, target, message)
+    }
+
+    pub fn info(target: DiagnosticTarget, message: impl Into<String>) -> Self {
+        Self::new(DiagnosticSeverity::Info, target, message)
+    }
+
+    pub fn with_code(mut self, code: impl Into<String>) -> Self {
+        self.code = Some(code.into());
+        self
+    }
+}
+
+/// Diagnostics of a flow, indexed by the node or edge they are attached to
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Diagnostics {
+    items: Vec<Diagnostic>,
+    /// Positions in `items` by node id, handle diagnostics included
+    by_node: HashMap<String, Vec<usize>>,
+    by_edge: HashMap<String, Vec<usize>>,
+}
+
+impl Diagnostics {
+    pub fn new(items: Vec<Diagnostic>) -> Self {
+        let mut by_node: HashMap<String, Vec<usize>> = HashMap::new();
+        let mut by_edge: HashMap<String, Vec<usize>> = HashMap::new();
+        for (position, diagnostic) in items.iter().enumerate() {
+            match &diagnostic.target {
+                DiagnosticTarget::Edge(id) => by_edge.entry(id.clone()).or_default().push(position),
+                DiagnosticTarget::Node(id) | DiagnosticTarget::Handle { node_id: id, .. } => {
+                    by_node.entry(id.clone()).or_default().push(position)
+                }
+            }
+        }
+        Self {
+            items,
+            by_node,
+            by_edge,
+        }
+    }
+
+    pub fn all(&self) -> &[Diagnostic] {
+        &self.items
+    }
+
+    pub fn len(&self) -> usize {
+        self.items.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.items.is_empty()
+    }
+
+    pub fn count(&self, severity: DiagnosticSeverity) -> usize {
+        self.items
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == severity)
+            .count()
+    }
+
+    /// Diagnostics of the node and its handles
+    pub fn for_node(&self, node_id: &str) -> Vec<&Diagnostic> {
+        self.at(self.by_node.get(node_id))
+    }
+
+    pub fn for_edge(&self, edge_id: &str) -> Vec<&Diagnostic> {
+        self.at(self.by_edge.get(edge_id))
+    }
+
+    pub fn for_handle(
+        &self,
+        node_id: &str,
+        handle_id: Option<&str>,
+        handle_type: HandleType,
+    ) -> Vec<&Diagnostic> {
+        self.for_node(node_id)
+            .into_iter()
+            .filter(|diagnostic| match &diagnostic.target {
+                DiagnosticTarget::Handle {
+                    handle_id: id,
+                    handle_type: kind,
+                    ..
+                } => id.as_deref() == handle_id && *kind == handle_type,
+                _ => false,
+            })
+            .collect()
+    }
+
+    fn at(&self, positions: Option<&Vec<usize>>) -> Vec<&Diagnostic> {
+        positions
+            .map(|positions| positions.iter().map(|&index| &self.items[index]).collect())
+            .unwrap_or_default()
+    }
+}
+
+/// Highest severity among `diagnostics`
+pub fn max_severity<'a>(
+    diagnostics: impl IntoIterator<Item = &'a Diagnostic>,
+) -> Option<DiagnosticSeverity> {
+    diagnostics
+        .into_iter()
+        .map(|diagnostic| diagnostic.severity)
+        .max()
+}
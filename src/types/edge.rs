@@ -1,6 +1,6 @@
 //! Edge types
 
-use super::Position;
+use super::{Position, XYPosition};
 use crate::types::Connection;
 use serde::{Deserialize, Serialize};
 
@@ -28,6 +28,9 @@ pub struct Edge<T: Clone + PartialEq + Default = ()> {
     /// Whether the edge is animated
     #[serde(default)]
     pub animated: bool,
+    /// Animation settings (implies `animated`; defaults apply when only `animated` is set)
+    #[serde(default)]
+    pub animation: Option<EdgeAnimation>,
     /// Whether the edge is selected
     #[serde(default)]
     pub selected: bool,
@@ -67,6 +70,12 @@ pub struct Edge<T: Clone + PartialEq + Default = ()> {
     /// Label background border radius
     #[serde(default)]
     pub label_bg_border_radius: Option<f64>,
+    /// Rotate `label` to follow the path direction
+    #[serde(default)]
+    pub label_rotate: bool,
+    /// Extra labels placed along the path
+    #[serde(default)]
+    pub labels: Vec<EdgeLabel>,
     /// Edge path style
     #[serde(default)]
     pub style: Option<String>,
@@ -82,6 +91,18 @@ pub struct Edge<T: Clone + PartialEq + Default = ()> {
     /// Interaction width for easier selection
     #[serde(default)]
     pub interaction_width: Option<f64>,
+    /// Waypoints the path runs through, in flow coordinates
+    #[serde(default)]
+    pub points: Vec<XYPosition>,
+    /// How the path is drawn through `points` (inferred from the edge type if unset)
+    #[serde(default)]
+    pub waypoint_style: Option<WaypointStyle>,
+    /// Anchor endpoints to node boundaries instead of handles (defaults to the flow setting)
+    #[serde(default)]
+    pub floating: Option<bool>,
+    /// Side of the node a self-loop is drawn on (defaults to top)
+    #[serde(default)]
+    pub loop_position: Option<Position>,
 }
 
 impl<T: Clone + PartialEq + Default> Default for Edge<T> {
@@ -95,6 +116,7 @@ impl<T: Clone + PartialEq + Default> Default for Edge<T> {
             data: None,
             edge_type: None,
             animated: false,
+            animation: None,
             selected: false,
             selectable: None,
             deletable: None,
@@ -108,11 +130,17 @@ impl<T: Clone + PartialEq + Default> Default for Edge<T> {
             label_bg_style: None,
             label_bg_padding: None,
             label_bg_border_radius: None,
+            label_rotate: false,
+            labels: Vec::new(),
             style: None,
             class_name: None,
             marker_start: None,
             marker_end: None,
             interaction_width: None,
+            points: Vec::new(),
+            waypoint_style: None,
+            floating: None,
+            loop_position: None,
         }
     }
 }
@@ -151,11 +179,47 @@ impl<T: Clone + PartialEq + Default> Edge<T> {
         self
     }
 
+    pub fn with_label_rotation(mut self, rotate: bool) -> Self {
+        self.label_rotate = rotate;
+        self
+    }
+
+    /// Label near the source end, e.g. a cardinality
+    pub fn with_start_label(mut self, text: impl Into<String>) -> Self {
+        self.labels.push(EdgeLabel::new(text, LabelAnchor::Start));
+        self
+    }
+
+    /// Label near the target end, e.g. a cardinality
+    pub fn with_end_label(mut self, text: impl Into<String>) -> Self {
+        self.labels.push(EdgeLabel::new(text, LabelAnchor::End));
+        self
+    }
+
+    pub fn with_labels(mut self, labels: Vec<EdgeLabel>) -> Self {
+        self.labels = labels;
+        self
+    }
+
     pub fn with_animated(mut self, animated: bool) -> Self {
         self.animated = animated;
         self
     }
 
+    pub fn with_animation(mut self, animation: EdgeAnimation) -> Self {
+        self.animation = Some(animation);
+        self
+    }
+
+    /// Animation to render, if any
+    pub fn resolved_animation(&self) -> Option<EdgeAnimation> {
+        match &self.animation {
+            Some(animation) => Some(animation.clone()),
+            None if self.animated => Some(EdgeAnimation::default()),
+            None => None,
+        }
+    }
+
     pub fn with_style(mut self, style: impl Into<String>) -> Self {
         self.style = Some(style.into());
         self
@@ -165,6 +229,48 @@ impl<T: Clone + PartialEq + Default> Edge<T> {
         self.data = Some(data);
         self
     }
+
+    pub fn with_points(mut self, points: Vec<XYPosition>) -> Self {
+        self.points = points;
+        self
+    }
+
+    pub fn with_floating(mut self, floating: bool) -> Self {
+        self.floating = Some(floating);
+        self
+    }
+
+    pub fn with_loop_position(mut self, position: Position) -> Self {
+        self.loop_position = Some(position);
+        self
+    }
+
+    pub fn with_waypoint_style(mut self, style: WaypointStyle) -> Self {
+        self.waypoint_style = Some(style);
+        self
+    }
+
+    /// Waypoint style, falling back to one that matches the edge type
+    pub fn resolved_waypoint_style(&self) -> WaypointStyle {
+        let inferred = match self.edge_type.as_deref() {
+            Some("step") | Some("smoothstep") => WaypointStyle::Orthogonal,
+            Some("straight") => WaypointStyle::Polyline,
+            _ => WaypointStyle::CatmullRom,
+        };
+        self.waypoint_style.unwrap_or(inferred)
+    }
+}
+
+/// Path style used for edges with waypoints
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum WaypointStyle {
+    /// Straight segments between waypoints
+    #[default]
+    Polyline,
+    /// Axis-aligned segments with rounded corners
+    Orthogonal,
+    /// Smooth Catmull-Rom curve through the waypoints
+    CatmullRom,
 }
 
 /// Reconnectable value - can be true, false, or source/target only
@@ -196,6 +302,205 @@ pub struct EdgeUpdateEndEvent<T: Clone + PartialEq + Default = ()> {
     pub connection: Option<Connection>,
 }
 
+/// Where a label sits along its edge
+#[derive(Clone, Copy, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub enum LabelAnchor {
+    /// A short distance from the source
+    Start,
+    #[default]
+    Middle,
+    /// A short distance from the target
+    End,
+    /// Fraction of the path length (0.0 at the source, 1.0 at the target)
+    Fraction(f64),
+}
+
+/// Additional label drawn along an edge
+#[derive(Clone, PartialEq, Debug, Default, Serialize, Deserialize)]
+pub struct EdgeLabel {
+    pub text: String,
+    #[serde(default)]
+    pub anchor: LabelAnchor,
+    /// Shift from the anchor point; `x` runs along the path and `y` across it
+    #[serde(default)]
+    pub offset: XYPosition,
+    /// Rotate to follow the path direction
+    #[serde(default)]
+    pub rotate: bool,
+    #[serde(default)]
+    pub class_name: Option<String>,
+    #[serde(default)]
+    pub style: Option<String>,
+}
+
+impl EdgeLabel {
+    pub fn new(text: impl Into<String>, anchor: LabelAnchor) -> Self {
+        Self {
+            text: text.into(),
+            anchor,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_offset(mut self, along: f64, across: f64) -> Self {
+        self.offset = XYPosition::new(along, across);
+        self
+    }
+
+    pub fn with_rotation(mut self, rotate: bool) -> Self {
+        self.rotate = rotate;
+        self
+    }
+
+    pub fn with_class_name(mut self, class_name: impl Into<String>) -> Self {
+        self.class_name = Some(class_name.into());
+        self
+    }
+
+    pub fn with_style(mut self, style: impl Into<String>) -> Self {
+        self.style = Some(style.into());
+        self
+    }
+}
+
+/// Computed placement of an edge label, in flow coordinates
+#[derive(Clone, PartialEq, Debug)]
+pub struct EdgeLabelPosition {
+    pub edge_id: String,
+    /// Index into `Edge::labels`, or `None` for the main `label`
+    pub index: Option<usize>,
+    pub text: String,
+    /// Label center
+    pub x: f64,
+    pub y: f64,
+    /// Rotation in degrees (0 when the label is not rotated)
+    pub angle: f64,
+    /// Estimated label size
+    pub width: f64,
+    pub height: f64,
+}
+
+/// Direction an edge animation travels in
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum AnimationDirection {
+    /// From source to target
+    #[default]
+    Forward,
+    /// From target to source
+    Reverse,
+}
+
+/// Continuous edge animation settings
+#[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
+pub struct EdgeAnimation {
+    /// Travel speed in flow units per second
+    #[serde(default = "EdgeAnimation::default_speed")]
+    pub speed: f64,
+    #[serde(default)]
+    pub direction: AnimationDirection,
+    /// Animate the stroke as moving dashes
+    #[serde(default = "EdgeAnimation::default_dashes")]
+    pub dashes: bool,
+    /// Number of dots travelling along the edge
+    #[serde(default)]
+    pub particles: u32,
+    /// Dot radius
+    #[serde(default = "EdgeAnimation::default_particle_radius")]
+    pub particle_radius: f64,
+    /// Dot color (defaults to the edge color)
+    #[serde(default)]
+    pub particle_color: Option<String>,
+}
+
+impl Default for EdgeAnimation {
+    fn default() -> Self {
+        Self {
+            speed: Self::default_speed(),
+            direction: AnimationDirection::Forward,
+            dashes: Self::default_dashes(),
+            particles: 0,
+            particle_radius: Self::default_particle_radius(),
+            particle_color: None,
+        }
+    }
+}
+
+impl EdgeAnimation {
+    fn default_speed() -> f64 {
+        20.0
+    }
+
+    fn default_dashes() -> bool {
+        true
+    }
+
+    fn default_particle_radius() -> f64 {
+        3.0
+    }
+
+    /// Dots only, without the dash animation
+    pub fn particles(count: u32) -> Self {
+        Self {
+            dashes: false,
+            particles: count,
+            ..Self::default()
+        }
+    }
+
+    pub fn with_speed(mut self, speed: f64) -> Self {
+        self.speed = speed;
+        self
+    }
+
+    pub fn with_direction(mut self, direction: AnimationDirection) -> Self {
+        self.direction = direction;
+        self
+    }
+
+    pub fn with_dashes(mut self, dashes: bool) -> Self {
+        self.dashes = dashes;
+        self
+    }
+
+    pub fn with_particles(mut self, particles: u32) -> Self {
+        self.particles = particles;
+        self
+    }
+
+    pub fn with_particle_radius(mut self, radius: f64) -> Self {
+        self.particle_radius = radius;
+        self
+    }
+
+    pub fn with_particle_color(mut self, color: impl Into<String>) -> Self {
+        self.particle_color = Some(color.into());
+        self
+    }
+}
+
+/// Options for a one-shot token sent along an edge
+#[derive(Clone, PartialEq, Debug)]
+pub struct PulseOptions {
+    /// Time to travel the whole edge
+    pub duration_ms: u32,
+    pub direction: AnimationDirection,
+    /// Token radius
+    pub radius: f64,
+    /// Token color (defaults to the selected edge color)
+    pub color: Option<String>,
+}
+
+impl Default for PulseOptions {
+    fn default() -> Self {
+        Self {
+            duration_ms: 800,
+            direction: AnimationDirection::Forward,
+            radius: 4.0,
+            color: None,
+        }
+    }
+}
+
 /// Edge marker configuration
 #[derive(Clone, PartialEq, Debug, Serialize, Deserialize)]
 pub struct EdgeMarker {
@@ -210,54 +515,223 @@ pub struct EdgeMarker {
     /// Marker height
     #[serde(default)]
     pub height: Option<f64>,
-    /// Marker units
+    /// Marker units (`strokeWidth` or `userSpaceOnUse`)
     #[serde(default)]
     pub marker_units: Option<String>,
-    /// Orient
+    /// Orient (`auto`, `auto-start-reverse` or an angle; defaults to
+    /// `auto` at the end and `auto-start-reverse` at the start of an edge)
     #[serde(default)]
     pub orient: Option<String>,
     /// Stroke width
     #[serde(default)]
     pub stroke_width: Option<f64>,
+    /// Distance the edge line is pulled back from its endpoint (built-in markers compute this)
+    #[serde(default)]
+    pub inset: Option<f64>,
 }
 
 impl EdgeMarker {
-    pub fn arrow() -> Self {
+    pub fn new(marker_type: MarkerType) -> Self {
         Self {
-            marker_type: MarkerType::Arrow,
+            marker_type,
             color: None,
             width: None,
             height: None,
             marker_units: None,
             orient: None,
             stroke_width: None,
+            inset: None,
         }
     }
 
+    pub fn arrow() -> Self {
+        Self::new(MarkerType::Arrow)
+    }
+
     pub fn arrow_closed() -> Self {
-        Self {
-            marker_type: MarkerType::ArrowClosed,
-            color: None,
-            width: None,
-            height: None,
-            marker_units: None,
-            orient: None,
-            stroke_width: None,
-        }
+        Self::new(MarkerType::ArrowClosed)
+    }
+
+    pub fn circle() -> Self {
+        Self::new(MarkerType::Circle)
+    }
+
+    pub fn diamond() -> Self {
+        Self::new(MarkerType::Diamond)
+    }
+
+    pub fn bar() -> Self {
+        Self::new(MarkerType::Bar)
+    }
+
+    pub fn crows_foot_one() -> Self {
+        Self::new(MarkerType::CrowsFootOne)
+    }
+
+    pub fn crows_foot_many() -> Self {
+        Self::new(MarkerType::CrowsFootMany)
+    }
+
+    pub fn crows_foot_zero_or_one() -> Self {
+        Self::new(MarkerType::CrowsFootZeroOrOne)
+    }
+
+    pub fn crows_foot_zero_or_many() -> Self {
+        Self::new(MarkerType::CrowsFootZeroOrMany)
+    }
+
+    /// Marker drawn by the component registered under `name` in `marker_types`
+    pub fn custom(name: impl Into<String>) -> Self {
+        Self::new(MarkerType::Custom(name.into()))
     }
 
     pub fn with_color(mut self, color: impl Into<String>) -> Self {
         self.color = Some(color.into());
         self
     }
+
+    pub fn with_size(mut self, width: f64, height: f64) -> Self {
+        self.width = Some(width);
+        self.height = Some(height);
+        self
+    }
+
+    pub fn with_stroke_width(mut self, stroke_width: f64) -> Self {
+        self.stroke_width = Some(stroke_width);
+        self
+    }
+
+    pub fn with_marker_units(mut self, marker_units: impl Into<String>) -> Self {
+        self.marker_units = Some(marker_units.into());
+        self
+    }
+
+    pub fn with_orient(mut self, orient: impl Into<String>) -> Self {
+        self.orient = Some(orient.into());
+        self
+    }
+
+    pub fn with_inset(mut self, inset: f64) -> Self {
+        self.inset = Some(inset);
+        self
+    }
+
+    /// Width and height, defaulting to 12x12
+    pub fn size(&self) -> (f64, f64) {
+        (self.width.unwrap_or(12.0), self.height.unwrap_or(12.0))
+    }
+
+    /// How far the edge line stops short of its endpoint so it ends at the
+    /// back of a filled marker rather than running through it.
+    pub fn line_inset(&self) -> f64 {
+        if let Some(inset) = self.inset {
+            return inset;
+        }
+        match self.marker_type.shape() {
+            Some(shape) => (shape.tip_x - shape.ref_x) * self.size().0 / 10.0,
+            None => 0.0,
+        }
+    }
 }
 
 /// Marker type
-#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+#[derive(Clone, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
 pub enum MarkerType {
     #[default]
     Arrow,
     ArrowClosed,
+    Circle,
+    Diamond,
+    /// Single bar across the line
+    Bar,
+    /// Crow's foot "exactly one" (double bar)
+    CrowsFootOne,
+    /// Crow's foot "many"
+    CrowsFootMany,
+    /// Crow's foot "zero or one"
+    CrowsFootZeroOrOne,
+    /// Crow's foot "zero or many"
+    CrowsFootZeroOrMany,
+    /// Marker drawn by the component registered under this key in `marker_types`
+    Custom(String),
+}
+
+/// Geometry of a built-in marker in a 10x10 view box pointing along +x
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct MarkerShape {
+    /// SVG path data
+    pub path: &'static str,
+    /// Whether the path is filled (otherwise only stroked)
+    pub filled: bool,
+    /// Optional hollow circle `(cx, r)` on the centre line, used for "zero" markers
+    pub ring: Option<(f64, f64)>,
+    /// X coordinate placed on the end of the edge line
+    pub ref_x: f64,
+    /// X coordinate that touches the node
+    pub tip_x: f64,
+}
+
+impl MarkerType {
+    /// Key used to tell marker definitions apart
+    pub fn key(&self) -> &'static str {
+        match self {
+            MarkerType::Arrow => "arrow",
+            MarkerType::ArrowClosed => "arrow-closed",
+            MarkerType::Circle => "circle",
+            MarkerType::Diamond => "diamond",
+            MarkerType::Bar => "bar",
+            MarkerType::CrowsFootOne => "crows-foot-one",
+            MarkerType::CrowsFootMany => "crows-foot-many",
+            MarkerType::CrowsFootZeroOrOne => "crows-foot-zero-or-one",
+            MarkerType::CrowsFootZeroOrMany => "crows-foot-zero-or-many",
+            MarkerType::Custom(_) => "custom",
+        }
+    }
+
+    /// Built-in geometry, `None` for custom markers
+    pub fn shape(&self) -> Option<MarkerShape> {
+        let shape = |path, filled, ring, ref_x, tip_x| MarkerShape {
+            path,
+            filled,
+            ring,
+            ref_x,
+            tip_x,
+        };
+        match self {
+            MarkerType::Arrow => Some(shape("M0,0 L10,5 L0,10", true, None, 10.0, 10.0)),
+            MarkerType::ArrowClosed => {
+                Some(shape("M0,0 L10,5 L0,10 z", true, None, 0.0, 10.0))
+            }
+            MarkerType::Circle => Some(shape(
+                "M1,5 A4,4 0 1,0 9,5 A4,4 0 1,0 1,5 z",
+                true,
+                None,
+                1.0,
+                9.0,
+            )),
+            MarkerType::Diamond => Some(shape("M0,5 L5,1 L10,5 L5,9 z", true, None, 0.0, 10.0)),
+            MarkerType::Bar => Some(shape("M8,0 L8,10", false, None, 10.0, 10.0)),
+            MarkerType::CrowsFootOne => {
+                Some(shape("M5,0 L5,10 M8,0 L8,10", false, None, 10.0, 10.0))
+            }
+            MarkerType::CrowsFootMany => Some(shape("M10,0 L4,5 L10,10", false, None, 10.0, 10.0)),
+            MarkerType::CrowsFootZeroOrOne => Some(shape(
+                "M7,0 L7,10",
+                false,
+                Some((2.5, 2.0)),
+                10.0,
+                10.0,
+            )),
+            MarkerType::CrowsFootZeroOrMany => Some(shape(
+                "M10,0 L5,5 L10,10",
+                false,
+                Some((2.5, 2.0)),
+                10.0,
+                10.0,
+            )),
+            MarkerType::Custom(_) => None,
+        }
+    }
 }
 
 /// Edge path options
@@ -301,10 +775,23 @@ pub struct EdgeProps<T: Clone + PartialEq + Default = ()> {
     pub animated: bool,
 }
 
+/// Endpoints of a floating edge
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct FloatingEdgeParams {
+    pub source_x: f64,
+    pub source_y: f64,
+    pub target_x: f64,
+    pub target_y: f64,
+    pub source_position: Position,
+    pub target_position: Position,
+}
+
 /// Result from edge path calculation
 #[derive(Clone, PartialEq, Debug)]
 pub struct EdgePathResult {
     pub path: String,
+    /// Typed segments the `path` string was built from
+    pub geometry: crate::utils::EdgeGeometry,
     pub label_x: f64,
     pub label_y: f64,
     pub offset_x: f64,
@@ -1,6 +1,9 @@
 //! Event payload types
 
-use crate::types::{Connection, Edge, HandleType, Node, Position, Rect, XYPosition};
+use crate::types::{
+    Connection, Edge, HandleType, Node, Position, Rect, SelectionOperation, XYPosition,
+};
+use serde::de::DeserializeOwned;
 
 #[derive(Clone, PartialEq, Debug)]
 pub struct ConnectionStartEvent {
@@ -14,6 +17,28 @@ pub struct ConnectionStartEvent {
 pub struct ConnectionEndEvent {
     pub connection: Option<Connection>,
     pub is_valid: bool,
+    /// Drop position in flow coordinates
+    pub flow_position: Option<XYPosition>,
+    /// Drop position in screen coordinates
+    pub screen_position: Option<XYPosition>,
+    /// Node the connection was dragged from
+    pub from_node: Option<String>,
+    /// Handle the connection was dragged from
+    pub from_handle: Option<String>,
+    /// Type of the handle the connection was dragged from
+    pub from_handle_type: Option<HandleType>,
+    /// What the pointer was over when the connection ended
+    pub drop_target: ConnectionDropTarget,
+}
+
+/// Element under the pointer when a connection drag ends
+#[derive(Clone, PartialEq, Eq, Debug, Default)]
+pub enum ConnectionDropTarget {
+    /// Empty pane
+    #[default]
+    Pane,
+    /// Body of a node (by ID)
+    Node(String),
 }
 
 #[derive(Clone, PartialEq, Debug)]
@@ -34,6 +59,10 @@ pub struct SelectionStartEvent {
 #[derive(Clone, PartialEq, Debug)]
 pub struct SelectionEndEvent<N: Clone + PartialEq + Default = (), E: Clone + PartialEq + Default = ()> {
     pub selection_rect: Option<Rect>,
+    /// Lasso outline in flow coordinates, when a lasso mode was used
+    pub selection_polygon: Option<Vec<XYPosition>>,
+    /// How the selection was combined with the previous one
+    pub operation: SelectionOperation,
     pub nodes: Vec<Node<N>>,
     pub edges: Vec<Edge<E>>,
 }
@@ -53,3 +82,82 @@ pub struct DeleteEvent<N: Clone + PartialEq + Default = (), E: Clone + PartialEq
     pub node_changes: Vec<crate::types::NodeChange<N>>,
     pub edge_changes: Vec<crate::types::EdgeChange<E>>,
 }
+
+/// Data format used by `DragSource` for its payload
+pub const DRAG_DATA_FORMAT: &str = "application/dioxus-flow";
+
+/// Payload of an external drag dropped onto the flow
+#[derive(Clone, PartialEq, Debug)]
+pub struct FlowDropEvent<N: Clone + PartialEq + Default = ()> {
+    /// Raw payload set by the drag source
+    pub payload: Option<String>,
+    /// Drop position in flow coordinates (snapped when `snap_to_grid` is on)
+    pub flow_position: XYPosition,
+    /// Drop position in screen coordinates
+    pub screen_position: XYPosition,
+    /// Group node under the drop position
+    pub parent_node: Option<Node<N>>,
+    /// Drop position relative to `parent_node`
+    pub position_in_parent: Option<XYPosition>,
+}
+
+impl<N: Clone + PartialEq + Default> FlowDropEvent<N> {
+    /// Deserialize the payload from JSON
+    pub fn data<T: DeserializeOwned>(&self) -> Option<T> {
+        serde_json::from_str(self.payload.as_deref()?).ok()
+    }
+}
+
+/// Pointer event on the empty pane
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PaneMouseEvent {
+    pub screen_position: XYPosition,
+    pub flow_position: XYPosition,
+}
+
+/// Wheel event over the pane
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct PaneScrollEvent {
+    pub screen_position: XYPosition,
+    pub flow_position: XYPosition,
+    /// Scroll distance in pixels
+    pub delta_x: f64,
+    pub delta_y: f64,
+}
+
+/// Right-click on a node
+#[derive(Clone, PartialEq, Debug)]
+pub struct NodeContextMenuEvent<N: Clone + PartialEq + Default = ()> {
+    pub node: Node<N>,
+    pub screen_position: XYPosition,
+    pub flow_position: XYPosition,
+}
+
+/// Right-click on an edge
+#[derive(Clone, PartialEq, Debug)]
+pub struct EdgeContextMenuEvent<E: Clone + PartialEq + Default = ()> {
+    pub edge: Edge<E>,
+    pub screen_position: XYPosition,
+    pub flow_position: XYPosition,
+}
+
+/// Right-click on a multi-element selection
+#[derive(Clone, PartialEq, Debug)]
+pub struct SelectionContextMenuEvent<
+    N: Clone + PartialEq + Default = (),
+    E: Clone + PartialEq + Default = (),
+> {
+    pub nodes: Vec<Node<N>>,
+    pub edges: Vec<Edge<E>>,
+    pub screen_position: XYPosition,
+    pub flow_position: XYPosition,
+}
+
+/// Drag of the multi-node selection box
+#[derive(Clone, PartialEq, Debug)]
+pub struct SelectionDragEvent<N: Clone + PartialEq + Default = ()> {
+    /// The dragged nodes at their current positions
+    pub nodes: Vec<Node<N>>,
+    /// Bounds of the dragged nodes
+    pub bounds: Rect,
+}
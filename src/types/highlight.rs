@@ -0,0 +1,52 @@
+//! Highlighting of related nodes and edges
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+
+/// What the built-in highlighting shows around the hovered or selected elements
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum HighlightMode {
+    /// No built-in highlighting
+    #[default]
+    None,
+    /// Everything the elements depend on
+    Upstream,
+    /// Everything that depends on the elements
+    Downstream,
+    /// Upstream and downstream
+    Lineage,
+    /// Direct neighbors and the edges to them
+    Connected,
+}
+
+/// Which elements the built-in highlighting starts from
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default, Serialize, Deserialize)]
+pub enum HighlightTrigger {
+    /// The hovered node or edge, else the selection
+    #[default]
+    HoverOrSelection,
+    Hover,
+    Selection,
+}
+
+/// Highlighted nodes and edges. While a highlight is active, every other
+/// element is dimmed.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct Highlight {
+    pub nodes: HashSet<String>,
+    pub edges: HashSet<String>,
+}
+
+impl Highlight {
+    pub fn contains_node(&self, id: &str) -> bool {
+        self.nodes.contains(id)
+    }
+
+    pub fn contains_edge(&self, id: &str) -> bool {
+        self.edges.contains(id)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.nodes.is_empty() && self.edges.is_empty()
+    }
+}
@@ -0,0 +1,370 @@
+//! Keyboard shortcuts for Dioxus Flow
+
+use super::Position;
+
+/// Action a key binding triggers
+#[derive(Clone, PartialEq, Debug)]
+pub enum FlowCommand {
+    /// Move the selected nodes by the given flow distance
+    MoveSelection {
+        dx: f64,
+        dy: f64,
+    },
+    SelectAll,
+    /// Delete the selected nodes and edges (and edges attached to those nodes)
+    DeleteSelection,
+    ZoomIn,
+    ZoomOut,
+    FitView,
+    /// Move keyboard focus to the next node or edge
+    FocusNext,
+    FocusPrevious,
+    /// Move focus from the focused node towards `Position`, see [`FocusNavigation`]
+    FocusDirection(Position),
+    /// Toggle the selection of the focused node or edge
+    ToggleFocusedSelection,
+    /// Start a connection from the focused handle
+    StartConnection,
+    /// Target the next valid handle of the keyboard connection
+    NextConnectionTarget,
+    PreviousConnectionTarget,
+    /// Connect to the current target of the keyboard connection
+    ConfirmConnection,
+    CancelConnection,
+    /// User-defined command, handled by `on_command`
+    Custom(String),
+}
+
+/// Where a keymap listens for keys
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum KeymapScope {
+    /// Keys pressed anywhere on the page
+    #[default]
+    Window,
+    /// Only keys pressed while focus is inside the flow
+    Flow,
+}
+
+/// How [`FlowCommand::FocusDirection`] picks the next node
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum FocusNavigation {
+    /// Nearest node in that direction
+    #[default]
+    Spatial,
+    /// Follow edges: right goes to an outgoer, left to an incomer, and up/down
+    /// cycle through the siblings reached that way
+    Graph,
+}
+
+/// Key with modifiers, e.g. `Mod+Shift+Z`. `Mod` is Cmd on macOS and Ctrl elsewhere.
+#[derive(Clone, PartialEq, Eq, Hash, Debug, Default)]
+pub struct KeyChord {
+    /// `KeyboardEvent.key` value, e.g. `"a"`, `"ArrowUp"` or `" "`
+    pub key: String,
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub meta: bool,
+    /// Platform command modifier
+    pub mod_key: bool,
+}
+
+impl KeyChord {
+    pub fn new(key: impl Into<String>) -> Self {
+        Self {
+            key: key.into(),
+            ..Default::default()
+        }
+    }
+
+    /// Parses `+`-separated chords such as `"Mod+A"`, `"Shift+ArrowUp"` or `"Ctrl++"`
+    pub fn parse(chord: &str) -> Option<Self> {
+        let (modifiers, key) = match chord.strip_suffix("++") {
+            Some(rest) => (rest, "+"),
+            None => match chord.rsplit_once('+') {
+                Some((modifiers, key)) => (modifiers, key),
+                None => ("", chord),
+            },
+        };
+        if key.is_empty() {
+            return None;
+        }
+        let key = match key {
+            "Space" | "Spacebar" => " ",
+            "Esc" => "Escape",
+            "Del" => "Delete",
+            other => other,
+        };
+        let mut result = Self::new(key);
+        for modifier in modifiers.split('+').filter(|part| !part.is_empty()) {
+            match modifier.to_ascii_lowercase().as_str() {
+                "ctrl" | "control" => result.ctrl = true,
+                "shift" => result.shift = true,
+                "alt" | "option" => result.alt = true,
+                "meta" | "cmd" | "command" | "super" => result.meta = true,
+                "mod" => result.mod_key = true,
+                _ => return None,
+            }
+        }
+        Some(result)
+    }
+
+    pub fn with_ctrl(mut self) -> Self {
+        self.ctrl = true;
+        self
+    }
+
+    pub fn with_shift(mut self) -> Self {
+        self.shift = true;
+        self
+    }
+
+    pub fn with_alt(mut self) -> Self {
+        self.alt = true;
+        self
+    }
+
+    pub fn with_meta(mut self) -> Self {
+        self.meta = true;
+        self
+    }
+
+    pub fn with_mod(mut self) -> Self {
+        self.mod_key = true;
+        self
+    }
+
+    /// Whether a key press matches this chord exactly, resolving `Mod` for the platform
+    pub fn matches(&self, key: &str, modifiers: KeyModifiers, is_mac: bool) -> bool {
+        let ctrl = self.ctrl || (self.mod_key && !is_mac);
+        let meta = self.meta || (self.mod_key && is_mac);
+        let same_key = if self.key.chars().count() == 1 {
+            self.key.eq_ignore_ascii_case(key)
+        } else {
+            self.key == key
+        };
+        same_key
+            && ctrl == modifiers.ctrl
+            && meta == modifiers.meta
+            && self.shift == modifiers.shift
+            && self.alt == modifiers.alt
+    }
+}
+
+/// Modifier state of a key press
+#[derive(Clone, Copy, PartialEq, Eq, Debug, Default)]
+pub struct KeyModifiers {
+    pub ctrl: bool,
+    pub shift: bool,
+    pub alt: bool,
+    pub meta: bool,
+}
+
+/// A chord and the command it runs
+#[derive(Clone, PartialEq, Debug)]
+pub struct KeyBinding {
+    pub chord: KeyChord,
+    pub command: FlowCommand,
+    /// Also fire while typing in inputs, textareas and editable content
+    pub in_inputs: bool,
+}
+
+/// Key bindings of a flow. Later bindings win, so user bindings override the defaults.
+#[derive(Clone, PartialEq, Debug)]
+pub struct Keymap {
+    pub bindings: Vec<KeyBinding>,
+    /// Bindings used instead of `bindings` while a keyboard connection is in progress
+    pub connecting: Vec<KeyBinding>,
+    pub scope: KeymapScope,
+    pub focus_navigation: FocusNavigation,
+}
+
+impl Default for Keymap {
+    /// Arrow keys move the selection (Shift for 10px steps, also with Ctrl or Meta
+    /// held), Alt+arrows move focus between nodes, Tab/Shift+Tab move focus,
+    /// Enter/Space toggle the focused element, Ctrl+A or Meta+A selects all and
+    /// Backspace/Delete remove the selection. Enter on a focused handle starts a
+    /// connection; Tab and the arrow keys then cycle targets, Enter connects and
+    /// Escape cancels.
+    fn default() -> Self {
+        let mut keymap = Self::empty();
+        for (key, dx, dy) in [
+            ("ArrowUp", 0.0, -1.0),
+            ("ArrowDown", 0.0, 1.0),
+            ("ArrowLeft", -1.0, 0.0),
+            ("ArrowRight", 1.0, 0.0),
+        ] {
+            for modifier in ["", "Ctrl+", "Meta+"] {
+                keymap = keymap
+                    .bind(
+                        &format!("{modifier}{key}"),
+                        FlowCommand::MoveSelection { dx, dy },
+                    )
+                    .bind(
+                        &format!("{modifier}Shift+{key}"),
+                        FlowCommand::MoveSelection {
+                            dx: dx * 10.0,
+                            dy: dy * 10.0,
+                        },
+                    );
+            }
+        }
+        for (key, direction) in [
+            ("ArrowUp", Position::Top),
+            ("ArrowDown", Position::Bottom),
+            ("ArrowLeft", Position::Left),
+            ("ArrowRight", Position::Right),
+        ] {
+            keymap = keymap.bind(&format!("Alt+{key}"), FlowCommand::FocusDirection(direction));
+        }
+        keymap
+            .bind("Tab", FlowCommand::FocusNext)
+            .bind("Shift+Tab", FlowCommand::FocusPrevious)
+            .bind("Enter", FlowCommand::ToggleFocusedSelection)
+            .bind("Enter", FlowCommand::StartConnection)
+            .bind("Space", FlowCommand::ToggleFocusedSelection)
+            .bind("Ctrl+A", FlowCommand::SelectAll)
+            .bind("Meta+A", FlowCommand::SelectAll)
+            .bind("Backspace", FlowCommand::DeleteSelection)
+            .bind("Delete", FlowCommand::DeleteSelection)
+            .bind_connecting("Tab", FlowCommand::NextConnectionTarget)
+            .bind_connecting("ArrowRight", FlowCommand::NextConnectionTarget)
+            .bind_connecting("ArrowDown", FlowCommand::NextConnectionTarget)
+            .bind_connecting("Shift+Tab", FlowCommand::PreviousConnectionTarget)
+            .bind_connecting("ArrowLeft", FlowCommand::PreviousConnectionTarget)
+            .bind_connecting("ArrowUp", FlowCommand::PreviousConnectionTarget)
+            .bind_connecting("Enter", FlowCommand::ConfirmConnection)
+            .bind_connecting("Escape", FlowCommand::CancelConnection)
+    }
+}
+
+impl Keymap {
+    /// Keymap without any bindings
+    pub fn empty() -> Self {
+        Self {
+            bindings: Vec::new(),
+            connecting: Vec::new(),
+            scope: KeymapScope::Window,
+            focus_navigation: FocusNavigation::Spatial,
+        }
+    }
+
+    /// Binds `chord` (see [`KeyChord::parse`]); invalid chords are ignored
+    pub fn bind(mut self, chord: &str, command: FlowCommand) -> Self {
+        if let Some(chord) = KeyChord::parse(chord) {
+            self.bindings.push(KeyBinding {
+                chord,
+                command,
+                in_inputs: false,
+            });
+        }
+        self
+    }
+
+    /// Like [`Keymap::bind`], but the binding also fires while typing in inputs
+    pub fn bind_in_inputs(mut self, chord: &str, command: FlowCommand) -> Self {
+        if let Some(chord) = KeyChord::parse(chord) {
+            self.bindings.push(KeyBinding {
+                chord,
+                command,
+                in_inputs: true,
+            });
+        }
+        self
+    }
+
+    /// Binds `chord` while a keyboard connection is in progress
+    pub fn bind_connecting(mut self, chord: &str, command: FlowCommand) -> Self {
+        if let Some(chord) = KeyChord::parse(chord) {
+            self.connecting.push(KeyBinding {
+                chord,
+                command,
+                in_inputs: false,
+            });
+        }
+        self
+    }
+
+    /// Removes every binding of `chord`
+    pub fn unbind(mut self, chord: &str) -> Self {
+        if let Some(chord) = KeyChord::parse(chord) {
+            self.bindings.retain(|binding| binding.chord != chord);
+            self.connecting.retain(|binding| binding.chord != chord);
+        }
+        self
+    }
+
+    /// Removes every binding that runs `command`
+    pub fn unbind_command(mut self, command: &FlowCommand) -> Self {
+        self.bindings.retain(|binding| binding.command != *command);
+        self.connecting.retain(|binding| binding.command != *command);
+        self
+    }
+
+    /// Replaces the bindings of `command` with `keys`, e.g. from `delete_key_code`
+    pub fn with_command_keys(mut self, command: FlowCommand, keys: &[String]) -> Self {
+        self = self.unbind_command(&command);
+        for key in keys {
+            self = self.bind(key, command.clone());
+        }
+        self
+    }
+
+    pub fn with_scope(mut self, scope: KeymapScope) -> Self {
+        self.scope = scope;
+        self
+    }
+
+    pub fn with_focus_navigation(mut self, focus_navigation: FocusNavigation) -> Self {
+        self.focus_navigation = focus_navigation;
+        self
+    }
+
+    /// Command bound to a key press; `in_input` is whether the target is editable
+    pub fn command_for(
+        &self,
+        key: &str,
+        modifiers: KeyModifiers,
+        in_input: bool,
+    ) -> Option<&FlowCommand> {
+        self.commands_for(key, modifiers, in_input, false)
+            .into_iter()
+            .next()
+    }
+
+    /// Every command bound to a key press, latest binding first. Commands that
+    /// do not apply (e.g. `StartConnection` without a focused handle) fall
+    /// through to the next one.
+    pub fn commands_for(
+        &self,
+        key: &str,
+        modifiers: KeyModifiers,
+        in_input: bool,
+        connecting: bool,
+    ) -> Vec<&FlowCommand> {
+        let is_mac = is_mac_platform();
+        let bindings = if connecting {
+            &self.connecting
+        } else {
+            &self.bindings
+        };
+        bindings
+            .iter()
+            .rev()
+            .filter(|binding| binding.in_inputs || !in_input)
+            .filter(|binding| binding.chord.matches(key, modifiers, is_mac))
+            .map(|binding| &binding.command)
+            .collect()
+    }
+}
+
+/// Whether the browser runs on an Apple platform, where `Mod` means Cmd
+pub fn is_mac_platform() -> bool {
+    crate::utils::browser::window()
+        .and_then(|window| window.navigator().platform().ok())
+        .is_some_and(|platform| {
+            ["Mac", "iPhone", "iPad", "iPod"]
+                .iter()
+                .any(|prefix| platform.starts_with(prefix))
+        })
+}
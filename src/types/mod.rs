@@ -3,9 +3,12 @@
 mod change;
 mod connection;
 mod config;
+mod diagnostic;
 mod events;
 mod edge;
 mod handle;
+mod highlight;
+mod keymap;
 mod node;
 mod position;
 mod viewport;
@@ -13,9 +16,12 @@ mod viewport;
 pub use change::*;
 pub use connection::*;
 pub use config::*;
+pub use diagnostic::*;
 pub use events::*;
 pub use edge::*;
 pub use handle::*;
+pub use highlight::*;
+pub use keymap::*;
 pub use node::*;
 pub use position::*;
 pub use viewport::*;
@@ -86,4 +86,40 @@ pub enum SelectionMode {
     #[default]
     Partial,
     Full,
+    /// Freeform lasso; selects nodes lying entirely inside the drawn polygon
+    Lasso,
+    /// Freeform lasso; selects nodes the drawn polygon touches
+    LassoPartial,
+}
+
+impl SelectionMode {
+    pub fn is_lasso(&self) -> bool {
+        matches!(self, SelectionMode::Lasso | SelectionMode::LassoPartial)
+    }
+}
+
+/// How a selection box combines with the current selection
+#[derive(Clone, Copy, PartialEq, Eq, Default, Debug)]
+pub enum SelectionOperation {
+    /// Select exactly the elements in the box
+    #[default]
+    Replace,
+    /// Add the elements in the box
+    Add,
+    /// Deselect the elements in the box
+    Subtract,
+    /// Flip the selection of the elements in the box
+    Toggle,
+}
+
+impl SelectionOperation {
+    /// New selection state of an element that was `selected` and is `in_box` or not
+    pub fn apply(&self, selected: bool, in_box: bool) -> bool {
+        match self {
+            SelectionOperation::Replace => in_box,
+            SelectionOperation::Add => selected || in_box,
+            SelectionOperation::Subtract => selected && !in_box,
+            SelectionOperation::Toggle => selected != in_box,
+        }
+    }
 }
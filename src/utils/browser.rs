@@ -0,0 +1,80 @@
+//! Browser access that degrades outside of a browser, e.g. in headless tests
+
+use wasm_bindgen::JsCast;
+use wasm_bindgen::closure::Closure;
+use wasm_bindgen::convert::FromWasmAbi;
+
+/// The browser window, `None` when not running in a browser
+pub fn window() -> Option<web_sys::Window> {
+    #[cfg(target_arch = "wasm32")]
+    {
+        web_sys::window()
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        None
+    }
+}
+
+/// Milliseconds since the Unix epoch, like `Date.now()`
+pub fn now() -> f64 {
+    #[cfg(target_arch = "wasm32")]
+    {
+        js_sys::Date::now()
+    }
+    #[cfg(not(target_arch = "wasm32"))]
+    {
+        std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .map_or(0.0, |elapsed| elapsed.as_secs_f64() * 1000.0)
+    }
+}
+
+/// Moves DOM focus to the first element matching `selector`; returns whether
+/// one was found
+pub(crate) fn focus_element(selector: &str) -> bool {
+    let element = window()
+        .and_then(|window| window.document())
+        .and_then(|document| document.query_selector(selector).ok().flatten())
+        .and_then(|element| element.dyn_into::<web_sys::HtmlElement>().ok());
+    match element {
+        Some(element) => element.focus().is_ok(),
+        None => false,
+    }
+}
+
+/// Window event listener, removed again on drop. Does nothing outside a browser.
+pub(crate) struct WindowListener<T: FromWasmAbi + 'static> {
+    event_type: String,
+    closure: Option<Closure<dyn FnMut(T)>>,
+}
+
+impl<T: FromWasmAbi + 'static> WindowListener<T> {
+    pub(crate) fn new(event_type: &str, handler: impl FnMut(T) + 'static) -> Self {
+        let Some(window) = window() else {
+            return Self {
+                event_type: event_type.to_string(),
+                closure: None,
+            };
+        };
+        let closure = Closure::wrap(Box::new(handler) as Box<dyn FnMut(T)>);
+        window
+            .add_event_listener_with_callback(event_type, closure.as_ref().unchecked_ref())
+            .ok();
+        Self {
+            event_type: event_type.to_string(),
+            closure: Some(closure),
+        }
+    }
+}
+
+impl<T: FromWasmAbi + 'static> Drop for WindowListener<T> {
+    fn drop(&mut self) {
+        let (Some(window), Some(closure)) = (window(), &self.closure) else {
+            return;
+        };
+        window
+            .remove_event_listener_with_callback(&self.event_type, closure.as_ref().unchecked_ref())
+            .ok();
+    }
+}
@@ -0,0 +1,627 @@
+//! Typed edge path geometry
+
+use crate::types::{Rect, XYPosition};
+use crate::utils::polygon_intersects_polyline;
+
+/// Samples used to measure and search curved segments
+const CURVE_SAMPLES: usize = 32;
+/// Refinement steps for closest-point searches on curves
+const CLOSEST_POINT_ITERATIONS: usize = 24;
+
+/// One piece of an edge path
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum PathSegment {
+    Line {
+        from: XYPosition,
+        to: XYPosition,
+    },
+    Cubic {
+        from: XYPosition,
+        control1: XYPosition,
+        control2: XYPosition,
+        to: XYPosition,
+    },
+    /// Circular arc; angles are in radians and `sweep` is signed
+    /// (positive runs clockwise on screen)
+    Arc {
+        center: XYPosition,
+        radius: f64,
+        start_angle: f64,
+        sweep: f64,
+    },
+}
+
+impl PathSegment {
+    /// Quadratic curve expressed as the equivalent cubic
+    pub fn quadratic(from: XYPosition, control: XYPosition, to: XYPosition) -> Self {
+        PathSegment::Cubic {
+            from,
+            control1: from + (control - from) * (2.0 / 3.0),
+            control2: to + (control - to) * (2.0 / 3.0),
+            to,
+        }
+    }
+
+    pub fn start(&self) -> XYPosition {
+        self.point(0.0)
+    }
+
+    pub fn end(&self) -> XYPosition {
+        self.point(1.0)
+    }
+
+    /// Point at curve parameter `t` (0.0 to 1.0)
+    pub fn point(&self, t: f64) -> XYPosition {
+        match *self {
+            PathSegment::Line { from, to } => from + (to - from) * t,
+            PathSegment::Cubic {
+                from,
+                control1,
+                control2,
+                to,
+            } => {
+                let mt = 1.0 - t;
+                from * (mt * mt * mt)
+                    + control1 * (3.0 * mt * mt * t)
+                    + control2 * (3.0 * mt * t * t)
+                    + to * (t * t * t)
+            }
+            PathSegment::Arc {
+                center,
+                radius,
+                start_angle,
+                sweep,
+            } => {
+                let angle = start_angle + sweep * t;
+                XYPosition::new(
+                    center.x + radius * angle.cos(),
+                    center.y + radius * angle.sin(),
+                )
+            }
+        }
+    }
+
+    /// Derivative at curve parameter `t`
+    fn derivative(&self, t: f64) -> XYPosition {
+        match *self {
+            PathSegment::Line { from, to } => to - from,
+            PathSegment::Cubic {
+                from,
+                control1,
+                control2,
+                to,
+            } => {
+                let mt = 1.0 - t;
+                (control1 - from) * (3.0 * mt * mt)
+                    + (control2 - control1) * (6.0 * mt * t)
+                    + (to - control2) * (3.0 * t * t)
+            }
+            PathSegment::Arc {
+                radius,
+                start_angle,
+                sweep,
+                ..
+            } => {
+                let angle = start_angle + sweep * t;
+                XYPosition::new(-angle.sin(), angle.cos()) * (radius * sweep)
+            }
+        }
+    }
+
+    /// Unit direction of travel at curve parameter `t`
+    pub fn tangent(&self, t: f64) -> XYPosition {
+        normalize(self.derivative(t))
+    }
+
+    pub fn length(&self) -> f64 {
+        match *self {
+            PathSegment::Line { from, to } => from.distance_to(&to),
+            PathSegment::Arc { radius, sweep, .. } => (radius * sweep).abs(),
+            PathSegment::Cubic { .. } => self
+                .samples()
+                .windows(2)
+                .map(|pair| pair[0].1.distance_to(&pair[1].1))
+                .sum(),
+        }
+    }
+
+    /// Curve parameter at `distance` along the segment
+    pub fn t_at_length(&self, distance: f64) -> f64 {
+        let length = self.length();
+        if length <= 0.0 {
+            return 0.0;
+        }
+        if !matches!(self, PathSegment::Cubic { .. }) {
+            return (distance / length).clamp(0.0, 1.0);
+        }
+
+        let mut travelled = 0.0;
+        for pair in self.samples().windows(2) {
+            let ((t0, p0), (t1, p1)) = (pair[0], pair[1]);
+            let step = p0.distance_to(&p1);
+            if travelled + step >= distance && step > 0.0 {
+                return t0 + (t1 - t0) * (distance - travelled) / step;
+            }
+            travelled += step;
+        }
+        1.0
+    }
+
+    pub fn bounding_box(&self) -> Rect {
+        let mut points = vec![self.start(), self.end()];
+        match *self {
+            PathSegment::Line { .. } => {}
+            PathSegment::Cubic {
+                from,
+                control1,
+                control2,
+                to,
+            } => {
+                let x_roots = cubic_extrema(from.x, control1.x, control2.x, to.x);
+                let y_roots = cubic_extrema(from.y, control1.y, control2.y, to.y);
+                points.extend(x_roots.into_iter().chain(y_roots).map(|t| self.point(t)));
+            }
+            PathSegment::Arc {
+                start_angle, sweep, ..
+            } => {
+                // Add every axis extreme the arc passes through.
+                let (low, high) = if sweep >= 0.0 {
+                    (start_angle, start_angle + sweep)
+                } else {
+                    (start_angle + sweep, start_angle)
+                };
+                let quarter = std::f64::consts::FRAC_PI_2;
+                let mut angle = (low / quarter).ceil() * quarter;
+                while sweep != 0.0 && angle <= high {
+                    points.push(self.point((angle - start_angle) / sweep));
+                    angle += quarter;
+                }
+            }
+        }
+        bounds_of(&points)
+    }
+
+    /// Curve parameter of the point closest to `point`
+    pub fn closest_t(&self, point: XYPosition) -> f64 {
+        if let PathSegment::Line { from, to } = *self {
+            let direction = to - from;
+            let length_squared = direction.x * direction.x + direction.y * direction.y;
+            if length_squared == 0.0 {
+                return 0.0;
+            }
+            let offset = point - from;
+            return ((offset.x * direction.x + offset.y * direction.y) / length_squared)
+                .clamp(0.0, 1.0);
+        }
+
+        let distance_at = |t: f64| self.point(t).distance_to(&point);
+        let (mut best_t, _) = self
+            .samples()
+            .into_iter()
+            .map(|(t, sample)| (t, sample.distance_to(&point)))
+            .fold((0.0, f64::INFINITY), |best, candidate| {
+                if candidate.1 < best.1 {
+                    candidate
+                } else {
+                    best
+                }
+            });
+
+        // Golden-section search around the best sample.
+        let step = 1.0 / CURVE_SAMPLES as f64;
+        let (mut low, mut high) = ((best_t - step).max(0.0), (best_t + step).min(1.0));
+        let ratio = (5f64.sqrt() - 1.0) / 2.0;
+        for _ in 0..CLOSEST_POINT_ITERATIONS {
+            let a = high - (high - low) * ratio;
+            let b = low + (high - low) * ratio;
+            if distance_at(a) < distance_at(b) {
+                high = b;
+            } else {
+                low = a;
+            }
+        }
+        let refined = (low + high) / 2.0;
+        if distance_at(refined) < distance_at(best_t) {
+            best_t = refined;
+        }
+        best_t
+    }
+
+    pub fn intersects_rect(&self, rect: &Rect) -> bool {
+        if !rects_overlap(&self.bounding_box(), rect) {
+            return false;
+        }
+        match *self {
+            PathSegment::Line { from, to } => line_intersects_rect(from, to, rect),
+            _ => self
+                .samples()
+                .windows(2)
+                .any(|pair| line_intersects_rect(pair[0].1, pair[1].1, rect)),
+        }
+    }
+
+    fn samples(&self) -> Vec<(f64, XYPosition)> {
+        (0..=CURVE_SAMPLES)
+            .map(|step| {
+                let t = step as f64 / CURVE_SAMPLES as f64;
+                (t, self.point(t))
+            })
+            .collect()
+    }
+
+    fn svg_command(&self) -> String {
+        match *self {
+            PathSegment::Line { to, .. } => format!(" L{},{}", to.x, to.y),
+            PathSegment::Cubic {
+                control1,
+                control2,
+                to,
+                ..
+            } => format!(
+                " C{},{} {},{} {},{}",
+                control1.x, control1.y, control2.x, control2.y, to.x, to.y
+            ),
+            PathSegment::Arc { radius, sweep, .. } => {
+                let to = self.end();
+                let large_arc = u8::from(sweep.abs() > std::f64::consts::PI);
+                let sweep_flag = u8::from(sweep > 0.0);
+                format!(
+                    " A{radius},{radius} 0 {large_arc},{sweep_flag} {},{}",
+                    to.x, to.y
+                )
+            }
+        }
+    }
+}
+
+/// Closest point on an edge path
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub struct ClosestPoint {
+    pub point: XYPosition,
+    /// Distance from the queried point
+    pub distance: f64,
+    /// Distance along the path from the source
+    pub length: f64,
+}
+
+/// Edge path as typed segments, from source to target
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct EdgeGeometry {
+    pub segments: Vec<PathSegment>,
+}
+
+impl EdgeGeometry {
+    pub fn new(segments: Vec<PathSegment>) -> Self {
+        Self { segments }
+    }
+
+    /// Straight segments through `points`
+    pub fn polyline(points: &[XYPosition]) -> Self {
+        Self::new(
+            points
+                .windows(2)
+                .map(|pair| PathSegment::Line {
+                    from: pair[0],
+                    to: pair[1],
+                })
+                .collect(),
+        )
+    }
+
+    pub fn push(&mut self, segment: PathSegment) {
+        self.segments.push(segment);
+    }
+
+    pub fn start(&self) -> Option<XYPosition> {
+        self.segments.first().map(PathSegment::start)
+    }
+
+    pub fn end(&self) -> Option<XYPosition> {
+        self.segments.last().map(PathSegment::end)
+    }
+
+    pub fn length(&self) -> f64 {
+        self.segments.iter().map(PathSegment::length).sum()
+    }
+
+    /// Point at fraction `t` (0.0 to 1.0) of the path length
+    pub fn point_at(&self, t: f64) -> Option<XYPosition> {
+        self.point_at_length(t.clamp(0.0, 1.0) * self.length())
+    }
+
+    /// Point `distance` along the path, clamped to its ends
+    pub fn point_at_length(&self, distance: f64) -> Option<XYPosition> {
+        self.locate(distance).map(|(segment, t)| segment.point(t))
+    }
+
+    /// Unit direction of travel at fraction `t` of the path length
+    pub fn tangent_at(&self, t: f64) -> Option<XYPosition> {
+        self.tangent_at_length(t.clamp(0.0, 1.0) * self.length())
+    }
+
+    /// Unit direction of travel `distance` along the path
+    pub fn tangent_at_length(&self, distance: f64) -> Option<XYPosition> {
+        self.locate(distance).map(|(segment, t)| segment.tangent(t))
+    }
+
+    pub fn bounding_box(&self) -> Option<Rect> {
+        self.segments
+            .iter()
+            .map(PathSegment::bounding_box)
+            .reduce(|bounds, next| bounds.union(&next))
+    }
+
+    pub fn closest_point(&self, point: XYPosition) -> Option<ClosestPoint> {
+        let mut travelled = 0.0;
+        let mut best: Option<ClosestPoint> = None;
+        for segment in &self.segments {
+            let t = segment.closest_t(point);
+            let candidate = segment.point(t);
+            let distance = candidate.distance_to(&point);
+            if best.is_none_or(|best| distance < best.distance) {
+                best = Some(ClosestPoint {
+                    point: candidate,
+                    distance,
+                    length: travelled + length_to(segment, t),
+                });
+            }
+            travelled += segment.length();
+        }
+        best
+    }
+
+    pub fn intersects_rect(&self, rect: &Rect) -> bool {
+        self.segments
+            .iter()
+            .any(|segment| segment.intersects_rect(rect))
+    }
+
+    /// Whether the path crosses or lies inside `polygon`
+    pub fn intersects_polygon(&self, polygon: &[XYPosition]) -> bool {
+        self.segments.iter().any(|segment| {
+            let points: Vec<XYPosition> = match *segment {
+                PathSegment::Line { from, to } => vec![from, to],
+                _ => segment.samples().into_iter().map(|(_, point)| point).collect(),
+            };
+            polygon_intersects_polyline(polygon, &points)
+        })
+    }
+
+    /// SVG path data for the segments
+    pub fn to_svg_path(&self) -> String {
+        let mut path = String::new();
+        let mut cursor: Option<XYPosition> = None;
+        for segment in &self.segments {
+            let start = segment.start();
+            if cursor.is_none_or(|cursor| cursor.distance_to(&start) > 1e-9) {
+                path.push_str(&format!("M{},{}", start.x, start.y));
+            }
+            path.push_str(&segment.svg_command());
+            cursor = Some(segment.end());
+        }
+        path
+    }
+
+    /// Segment and curve parameter `distance` along the path
+    fn locate(&self, distance: f64) -> Option<(&PathSegment, f64)> {
+        let mut remaining = distance.max(0.0);
+        for segment in &self.segments {
+            let length = segment.length();
+            if remaining <= length {
+                return Some((segment, segment.t_at_length(remaining)));
+            }
+            remaining -= length;
+        }
+        self.segments.last().map(|segment| (segment, 1.0))
+    }
+}
+
+fn length_to(segment: &PathSegment, t: f64) -> f64 {
+    match *segment {
+        PathSegment::Cubic {
+            from,
+            control1,
+            control2,
+            to,
+        } => {
+            // Split at `t` (de Casteljau) and measure the first half.
+            let ab = from + (control1 - from) * t;
+            let bc = control1 + (control2 - control1) * t;
+            let cd = control2 + (to - control2) * t;
+            let abc = ab + (bc - ab) * t;
+            let bcd = bc + (cd - bc) * t;
+            PathSegment::Cubic {
+                from,
+                control1: ab,
+                control2: abc,
+                to: abc + (bcd - abc) * t,
+            }
+            .length()
+        }
+        _ => segment.length() * t,
+    }
+}
+
+fn normalize(vector: XYPosition) -> XYPosition {
+    let length = vector.x.hypot(vector.y);
+    if length == 0.0 {
+        XYPosition::new(0.0, 0.0)
+    } else {
+        vector * (1.0 / length)
+    }
+}
+
+/// Curve parameters in (0, 1) where one axis of a cubic turns around
+fn cubic_extrema(p0: f64, p1: f64, p2: f64, p3: f64) -> Vec<f64> {
+    // Derivative coefficients of a*t^2 + b*t + c.
+    let a = 3.0 * (-p0 + 3.0 * p1 - 3.0 * p2 + p3);
+    let b = 6.0 * (p0 - 2.0 * p1 + p2);
+    let c = 3.0 * (p1 - p0);
+    let roots = if a.abs() < 1e-12 {
+        if b.abs() < 1e-12 {
+            vec![]
+        } else {
+            vec![-c / b]
+        }
+    } else {
+        let discriminant = b * b - 4.0 * a * c;
+        if discriminant < 0.0 {
+            vec![]
+        } else {
+            let root = discriminant.sqrt();
+            vec![(-b + root) / (2.0 * a), (-b - root) / (2.0 * a)]
+        }
+    };
+    roots.into_iter().filter(|t| *t > 0.0 && *t < 1.0).collect()
+}
+
+fn bounds_of(points: &[XYPosition]) -> Rect {
+    let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+    let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+    for point in points {
+        min_x = min_x.min(point.x);
+        min_y = min_y.min(point.y);
+        max_x = max_x.max(point.x);
+        max_y = max_y.max(point.y);
+    }
+    Rect::new(min_x, min_y, max_x - min_x, max_y - min_y)
+}
+
+/// Overlap test that also accepts zero-width or zero-height boxes
+fn rects_overlap(a: &Rect, b: &Rect) -> bool {
+    a.x <= b.x + b.width && b.x <= a.x + a.width && a.y <= b.y + b.height && b.y <= a.y + a.height
+}
+
+/// Liang-Barsky clip of the segment against `rect`
+fn line_intersects_rect(from: XYPosition, to: XYPosition, rect: &Rect) -> bool {
+    let delta = to - from;
+    let checks = [
+        (-delta.x, from.x - rect.x),
+        (delta.x, rect.x + rect.width - from.x),
+        (-delta.y, from.y - rect.y),
+        (delta.y, rect.y + rect.height - from.y),
+    ];
+    let (mut enter, mut exit) = (0.0_f64, 1.0_f64);
+    for (p, q) in checks {
+        if p == 0.0 {
+            if q < 0.0 {
+                return false;
+            }
+        } else {
+            let r = q / p;
+            if p < 0.0 {
+                enter = enter.max(r);
+            } else {
+                exit = exit.min(r);
+            }
+        }
+    }
+    enter <= exit
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn p(x: f64, y: f64) -> XYPosition {
+        XYPosition::new(x, y)
+    }
+
+    fn close(a: f64, b: f64, tolerance: f64) -> bool {
+        (a - b).abs() <= tolerance
+    }
+
+    fn s_curve() -> PathSegment {
+        PathSegment::Cubic {
+            from: p(0.0, 50.0),
+            control1: p(50.0, -150.0),
+            control2: p(50.0, 250.0),
+            to: p(100.0, 50.0),
+        }
+    }
+
+    /// Points along `segment` at `count` even parameter steps
+    fn dense(segment: &PathSegment, count: usize) -> Vec<XYPosition> {
+        (0..=count)
+            .map(|step| segment.point(step as f64 / count as f64))
+            .collect()
+    }
+
+    #[test]
+    fn lines_and_cubics_have_their_length() {
+        let line = PathSegment::Line {
+            from: p(0.0, 0.0),
+            to: p(30.0, 40.0),
+        };
+        assert_eq!(line.length(), 50.0);
+
+        let straight_cubic = PathSegment::Cubic {
+            from: p(0.0, 0.0),
+            control1: p(100.0 / 3.0, 0.0),
+            control2: p(200.0 / 3.0, 0.0),
+            to: p(100.0, 0.0),
+        };
+        assert!(close(straight_cubic.length(), 100.0, 1e-9));
+
+        let curve = s_curve();
+        let exact: f64 = dense(&curve, 10_000)
+            .windows(2)
+            .map(|pair| pair[0].distance_to(&pair[1]))
+            .sum();
+        assert!(close(curve.length(), exact, exact * 0.005));
+    }
+
+    #[test]
+    fn point_at_length_walks_the_segments() {
+        let geometry = EdgeGeometry::polyline(&[p(0.0, 0.0), p(100.0, 0.0), p(100.0, 50.0)]);
+        assert_eq!(geometry.length(), 150.0);
+        assert_eq!(geometry.point_at_length(0.0), Some(p(0.0, 0.0)));
+        assert_eq!(geometry.point_at_length(75.0), Some(p(75.0, 0.0)));
+        assert_eq!(geometry.point_at_length(125.0), Some(p(100.0, 25.0)));
+        assert_eq!(geometry.point_at_length(150.0), Some(p(100.0, 50.0)));
+        assert_eq!(geometry.point_at_length(500.0), Some(p(100.0, 50.0)));
+        assert_eq!(geometry.point_at(0.5), Some(p(75.0, 0.0)));
+        assert_eq!(EdgeGeometry::default().point_at_length(10.0), None);
+
+        let curve = EdgeGeometry::new(vec![s_curve()]);
+        let middle = curve.point_at_length(curve.length() / 2.0).unwrap();
+        // The S-curve is point symmetric around its middle
+        assert!(close(middle.x, 50.0, 0.5) && close(middle.y, 50.0, 0.5));
+    }
+
+    #[test]
+    fn s_curve_bounds_include_the_overshoot() {
+        let curve = s_curve();
+        let bounds = curve.bounding_box();
+        let samples = dense(&curve, 100_000);
+        let min_y = samples
+            .iter()
+            .map(|point| point.y)
+            .fold(f64::INFINITY, f64::min);
+        let max_y = samples
+            .iter()
+            .map(|point| point.y)
+            .fold(f64::NEG_INFINITY, f64::max);
+        assert!(min_y < 0.0 && max_y > 100.0);
+        assert!(close(bounds.y, min_y, 1e-4));
+        assert!(close(bounds.y + bounds.height, max_y, 1e-4));
+        assert!(close(bounds.x, 0.0, 1e-9));
+        assert!(close(bounds.x + bounds.width, 100.0, 1e-9));
+    }
+
+    #[test]
+    fn closest_point_and_rect_hits() {
+        let geometry = EdgeGeometry::polyline(&[p(0.0, 0.0), p(100.0, 0.0), p(100.0, 50.0)]);
+        let closest = geometry.closest_point(p(110.0, 20.0)).unwrap();
+        assert_eq!(closest.point, p(100.0, 20.0));
+        assert_eq!(closest.distance, 10.0);
+        assert_eq!(closest.length, 120.0);
+
+        assert!(geometry.intersects_rect(&Rect::new(40.0, -10.0, 20.0, 20.0)));
+        assert!(!geometry.intersects_rect(&Rect::new(40.0, 10.0, 20.0, 20.0)));
+
+        let curve = EdgeGeometry::new(vec![s_curve()]);
+        let closest = curve.closest_point(s_curve().point(0.3)).unwrap();
+        assert!(closest.distance < 1e-3);
+    }
+}
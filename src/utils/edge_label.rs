@@ -0,0 +1,143 @@
+//! Edge label placement
+
+use crate::types::{Edge, EdgeLabelPosition, EdgePathResult, LabelAnchor, XYPosition};
+
+/// Distance from the path ends for start and end labels
+const END_LABEL_DISTANCE: f64 = 20.0;
+/// Rough glyph metrics of the 10px label font
+const LABEL_CHAR_WIDTH: f64 = 6.0;
+const LABEL_LINE_HEIGHT: f64 = 14.0;
+/// Relaxation passes used to push overlapping labels apart
+const COLLISION_PASSES: usize = 4;
+
+/// Positions of an edge's main `label` and its extra `labels` along `path`
+pub fn get_edge_label_positions<E: Clone + PartialEq + Default>(
+    edge: &Edge<E>,
+    path: &EdgePathResult,
+) -> Vec<EdgeLabelPosition> {
+    let geometry = &path.geometry;
+    let length = geometry.length();
+    let padding = edge.label_bg_padding.unwrap_or((6.0, 4.0));
+    let mut positions = Vec::new();
+
+    if let Some(text) = &edge.label {
+        let angle = if edge.label_rotate {
+            let center = XYPosition::new(path.label_x, path.label_y);
+            geometry
+                .closest_point(center)
+                .and_then(|closest| geometry.tangent_at_length(closest.length))
+                .map(readable_angle)
+                .unwrap_or(0.0)
+        } else {
+            0.0
+        };
+        positions.push(label_position(
+            edge,
+            None,
+            text,
+            XYPosition::new(path.label_x, path.label_y),
+            angle,
+            padding,
+        ));
+    }
+
+    for (index, label) in edge.labels.iter().enumerate() {
+        let distance = match label.anchor {
+            LabelAnchor::Start => END_LABEL_DISTANCE.min(length / 2.0),
+            LabelAnchor::Middle => length / 2.0,
+            LabelAnchor::End => length - END_LABEL_DISTANCE.min(length / 2.0),
+            LabelAnchor::Fraction(t) => t.clamp(0.0, 1.0) * length,
+        };
+        let Some(anchor) = geometry.point_at_length(distance) else {
+            continue;
+        };
+        let tangent = geometry
+            .tangent_at_length(distance)
+            .unwrap_or(XYPosition::new(1.0, 0.0));
+        let normal = XYPosition::new(-tangent.y, tangent.x);
+        let center = anchor + tangent * label.offset.x + normal * label.offset.y;
+        let angle = if label.rotate {
+            readable_angle(tangent)
+        } else {
+            0.0
+        };
+        positions.push(label_position(
+            edge,
+            Some(index),
+            &label.text,
+            center,
+            angle,
+            padding,
+        ));
+    }
+
+    positions
+}
+
+/// Nudges overlapping labels of different edges apart, e.g. on parallel edges
+pub fn resolve_label_collisions(positions: &mut [EdgeLabelPosition]) {
+    for _ in 0..COLLISION_PASSES {
+        let mut moved = false;
+        for i in 0..positions.len() {
+            for j in (i + 1)..positions.len() {
+                if positions[i].edge_id == positions[j].edge_id {
+                    continue;
+                }
+                let (a, b) = (&positions[i], &positions[j]);
+                let overlap_x = (a.width + b.width) / 2.0 - (a.x - b.x).abs();
+                let overlap_y = (a.height + b.height) / 2.0 - (a.y - b.y).abs();
+                if overlap_x <= 0.0 || overlap_y <= 0.0 {
+                    continue;
+                }
+                // Separate along the axis that needs the smaller move.
+                let (dx, dy) = if overlap_y <= overlap_x {
+                    let sign = if a.y <= b.y { -1.0 } else { 1.0 };
+                    (0.0, sign * overlap_y / 2.0)
+                } else {
+                    let sign = if a.x <= b.x { -1.0 } else { 1.0 };
+                    (sign * overlap_x / 2.0, 0.0)
+                };
+                positions[i].x += dx;
+                positions[i].y += dy;
+                positions[j].x -= dx;
+                positions[j].y -= dy;
+                moved = true;
+            }
+        }
+        if !moved {
+            break;
+        }
+    }
+}
+
+fn label_position<E: Clone + PartialEq + Default>(
+    edge: &Edge<E>,
+    index: Option<usize>,
+    text: &str,
+    center: XYPosition,
+    angle: f64,
+    padding: (f64, f64),
+) -> EdgeLabelPosition {
+    EdgeLabelPosition {
+        edge_id: edge.id.clone(),
+        index,
+        text: text.to_string(),
+        x: center.x,
+        y: center.y,
+        angle,
+        width: text.chars().count() as f64 * LABEL_CHAR_WIDTH + padding.0 * 2.0,
+        height: LABEL_LINE_HEIGHT + padding.1 * 2.0,
+    }
+}
+
+/// Angle of `tangent` in degrees, flipped so text never reads upside down
+fn readable_angle(tangent: XYPosition) -> f64 {
+    let angle = tangent.y.atan2(tangent.x).to_degrees();
+    if angle > 90.0 {
+        angle - 180.0
+    } else if angle < -90.0 {
+        angle + 180.0
+    } else {
+        angle
+    }
+}
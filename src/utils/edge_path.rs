@@ -1,11 +1,14 @@
 //! Edge path utilities
 
+use super::{EdgeGeometry, PathSegment};
 use crate::types::{EdgePathResult, Position, XYPosition};
 
 const DEFAULT_BEZIER_CURVATURE: f64 = 0.25;
 const DEFAULT_SMOOTH_STEP_RADIUS: f64 = 5.0;
 const DEFAULT_SMOOTH_STEP_OFFSET: f64 = 20.0;
 const DEFAULT_STEP_POSITION: f64 = 0.5;
+const DEFAULT_CATMULL_ROM_TENSION: f64 = 1.0;
+const DEFAULT_SELF_LOOP_SIZE: f64 = 40.0;
 
 pub fn get_bezier_path(
     source_x: f64,
@@ -45,11 +48,16 @@ pub fn get_bezier_path(
         target_control_y,
     );
 
+    let geometry = cubic_geometry(
+        XYPosition::new(source_x, source_y),
+        XYPosition::new(source_control_x, source_control_y),
+        XYPosition::new(target_control_x, target_control_y),
+        XYPosition::new(target_x, target_y),
+    );
+
     EdgePathResult {
-        path: format!(
-            "M{},{} C{},{} {},{} {},{}",
-            source_x, source_y, source_control_x, source_control_y, target_control_x, target_control_y, target_x, target_y
-        ),
+        path: geometry.to_svg_path(),
+        geometry,
         label_x,
         label_y,
         offset_x,
@@ -80,11 +88,16 @@ pub fn get_simple_bezier_path(
         target_control_y,
     );
 
+    let geometry = cubic_geometry(
+        XYPosition::new(source_x, source_y),
+        XYPosition::new(source_control_x, source_control_y),
+        XYPosition::new(target_control_x, target_control_y),
+        XYPosition::new(target_x, target_y),
+    );
+
     EdgePathResult {
-        path: format!(
-            "M{},{} C{},{} {},{} {},{}",
-            source_x, source_y, source_control_x, source_control_y, target_control_x, target_control_y, target_x, target_y
-        ),
+        path: geometry.to_svg_path(),
+        geometry,
         label_x,
         label_y,
         offset_x,
@@ -100,8 +113,13 @@ pub fn get_straight_path(
 ) -> EdgePathResult {
     let (label_x, label_y, offset_x, offset_y) =
         get_edge_center(source_x, source_y, target_x, target_y);
+    let geometry = EdgeGeometry::new(vec![PathSegment::Line {
+        from: XYPosition::new(source_x, source_y),
+        to: XYPosition::new(target_x, target_y),
+    }]);
     EdgePathResult {
-        path: format!("M {},{}L {},{}", source_x, source_y, target_x, target_y),
+        path: geometry.to_svg_path(),
+        geometry,
         label_x,
         label_y,
         offset_x,
@@ -179,20 +197,72 @@ fn smooth_step_path(
         step_position,
     );
 
-    let path = points.iter().enumerate().fold(String::new(), |mut res, (i, p)| {
-        let segment = if i > 0 && i < points.len() - 1 {
-            get_bend(points[i - 1], *p, points[i + 1], border_radius)
-        } else if i == 0 {
-            format!("M{} {}", p.x, p.y)
-        } else {
-            format!("L{} {}", p.x, p.y)
-        };
-        res.push_str(&segment);
-        res
-    });
+    let geometry = get_rounded_geometry(&points, border_radius);
+    EdgePathResult {
+        path: geometry.to_svg_path(),
+        geometry,
+        label_x,
+        label_y,
+        offset_x,
+        offset_y,
+    }
+}
+
+/// Bezier path bowed sideways so its midpoint sits `offset` away from the
+/// unbent curve, used to fan out parallel edges.
+pub fn get_offset_bezier_path(
+    source_x: f64,
+    source_y: f64,
+    target_x: f64,
+    target_y: f64,
+    source_position: Position,
+    target_position: Position,
+    offset: f64,
+) -> EdgePathResult {
+    let curvature = DEFAULT_BEZIER_CURVATURE;
+    let normal = get_normal(source_x, source_y, target_x, target_y);
+    // The curve midpoint moves by 3/4 of a shared control point shift.
+    let shift = normal * (offset * 4.0 / 3.0);
+
+    let (source_control_x, source_control_y) = get_control_with_curvature(
+        source_position,
+        source_x,
+        source_y,
+        target_x,
+        target_y,
+        curvature,
+    );
+    let (target_control_x, target_control_y) = get_control_with_curvature(
+        target_position,
+        target_x,
+        target_y,
+        source_x,
+        source_y,
+        curvature,
+    );
+    let source_control = XYPosition::new(source_control_x, source_control_y) + shift;
+    let target_control = XYPosition::new(target_control_x, target_control_y) + shift;
+    let (label_x, label_y, offset_x, offset_y) = get_bezier_edge_center(
+        source_x,
+        source_y,
+        target_x,
+        target_y,
+        source_control.x,
+        source_control.y,
+        target_control.x,
+        target_control.y,
+    );
+
+    let geometry = cubic_geometry(
+        XYPosition::new(source_x, source_y),
+        source_control,
+        target_control,
+        XYPosition::new(target_x, target_y),
+    );
 
     EdgePathResult {
-        path,
+        path: geometry.to_svg_path(),
+        geometry,
         label_x,
         label_y,
         offset_x,
@@ -200,6 +270,260 @@ fn smooth_step_path(
     }
 }
 
+/// Gentle arc between two points whose midpoint sits `offset` away from the
+/// straight line, used to fan out parallel straight edges.
+pub fn get_offset_straight_path(
+    source_x: f64,
+    source_y: f64,
+    target_x: f64,
+    target_y: f64,
+    offset: f64,
+) -> EdgePathResult {
+    let normal = get_normal(source_x, source_y, target_x, target_y);
+    let (center_x, center_y, offset_x, offset_y) =
+        get_edge_center(source_x, source_y, target_x, target_y);
+    let control = XYPosition::new(center_x, center_y) + normal * (offset * 2.0);
+    let label = XYPosition::new(center_x, center_y) + normal * offset;
+
+    let geometry = EdgeGeometry::new(vec![PathSegment::quadratic(
+        XYPosition::new(source_x, source_y),
+        control,
+        XYPosition::new(target_x, target_y),
+    )]);
+
+    EdgePathResult {
+        path: geometry.to_svg_path(),
+        geometry,
+        label_x: label.x,
+        label_y: label.y,
+        offset_x,
+        offset_y,
+    }
+}
+
+/// Loop leaving and re-entering the same node on side `position`.
+/// `offset` grows the loop outward, for stacking several loops on one node.
+pub fn get_self_loop_path(
+    source_x: f64,
+    source_y: f64,
+    target_x: f64,
+    target_y: f64,
+    position: Position,
+    offset: f64,
+) -> EdgePathResult {
+    let size = DEFAULT_SELF_LOOP_SIZE + offset;
+    let out = handle_direction(position) * (size * 4.0 / 3.0);
+    let source_control = XYPosition::new(source_x, source_y) + out;
+    let target_control = XYPosition::new(target_x, target_y) + out;
+    let (label_x, label_y, offset_x, offset_y) = get_bezier_edge_center(
+        source_x,
+        source_y,
+        target_x,
+        target_y,
+        source_control.x,
+        source_control.y,
+        target_control.x,
+        target_control.y,
+    );
+
+    let geometry = cubic_geometry(
+        XYPosition::new(source_x, source_y),
+        source_control,
+        target_control,
+        XYPosition::new(target_x, target_y),
+    );
+
+    EdgePathResult {
+        path: geometry.to_svg_path(),
+        geometry,
+        label_x,
+        label_y,
+        offset_x,
+        offset_y,
+    }
+}
+
+/// Straight segments through `points` (source first, target last).
+pub fn get_polyline_path(points: &[XYPosition]) -> EdgePathResult {
+    waypoint_path_result(EdgeGeometry::polyline(points), points)
+}
+
+/// Axis-aligned segments with rounded corners through `points`
+/// (source first, target last). The first segment leaves along the axis of
+/// `source_position`.
+pub fn get_orthogonal_path(
+    points: &[XYPosition],
+    source_position: Position,
+    border_radius: Option<f64>,
+) -> EdgePathResult {
+    let border_radius = border_radius.unwrap_or(DEFAULT_SMOOTH_STEP_RADIUS);
+    let Some(first) = points.first() else {
+        return waypoint_path_result(EdgeGeometry::default(), points);
+    };
+
+    let mut horizontal = source_position.is_horizontal();
+    let mut route = vec![*first];
+    for window in points.windows(2) {
+        let (a, b) = (window[0], window[1]);
+        if a.x != b.x && a.y != b.y {
+            let corner = if horizontal {
+                XYPosition::new(b.x, a.y)
+            } else {
+                XYPosition::new(a.x, b.y)
+            };
+            route.push(corner);
+            horizontal = !horizontal;
+        } else if a.x != b.x {
+            horizontal = true;
+        } else if a.y != b.y {
+            horizontal = false;
+        }
+        route.push(b);
+    }
+    route.dedup();
+
+    waypoint_path_result(get_rounded_geometry(&route, border_radius), &route)
+}
+
+/// Smooth Catmull-Rom curve through `points` (source first, target last).
+/// A `tension` of 1.0 gives a uniform Catmull-Rom spline; 0.0 gives straight segments.
+pub fn get_catmull_rom_path(points: &[XYPosition], tension: Option<f64>) -> EdgePathResult {
+    let tension = tension.unwrap_or(DEFAULT_CATMULL_ROM_TENSION);
+    let Some(first) = points.first() else {
+        return waypoint_path_result(EdgeGeometry::default(), points);
+    };
+
+    let last = points.len() - 1;
+    let mut geometry = EdgeGeometry::default();
+    if last == 0 {
+        geometry.push(PathSegment::Line {
+            from: *first,
+            to: *first,
+        });
+    }
+    for i in 0..last {
+        let p0 = points[i.saturating_sub(1)];
+        let p1 = points[i];
+        let p2 = points[i + 1];
+        let p3 = points[(i + 2).min(last)];
+        let c1 = p1 + (p2 - p0) * (tension / 6.0);
+        let c2 = p2 - (p3 - p1) * (tension / 6.0);
+        geometry.push(PathSegment::Cubic {
+            from: p1,
+            control1: c1,
+            control2: c2,
+            to: p2,
+        });
+    }
+    waypoint_path_result(geometry, points)
+}
+
+fn waypoint_path_result(geometry: EdgeGeometry, points: &[XYPosition]) -> EdgePathResult {
+    let (label_x, label_y) = get_polyline_center(points);
+    let (offset_x, offset_y) = match (points.first(), points.last()) {
+        (Some(source), Some(target)) => {
+            let (_, _, offset_x, offset_y) =
+                get_edge_center(source.x, source.y, target.x, target.y);
+            (offset_x, offset_y)
+        }
+        _ => (0.0, 0.0),
+    };
+    EdgePathResult {
+        path: geometry.to_svg_path(),
+        geometry,
+        label_x,
+        label_y,
+        offset_x,
+        offset_y,
+    }
+}
+
+/// Point halfway along a polyline.
+fn get_polyline_center(points: &[XYPosition]) -> (f64, f64) {
+    let total: f64 = points.windows(2).map(|w| distance(w[0], w[1])).sum();
+    let mut remaining = total / 2.0;
+    for window in points.windows(2) {
+        let length = distance(window[0], window[1]);
+        if length > 0.0 && remaining <= length {
+            let t = remaining / length;
+            let point = window[0] + (window[1] - window[0]) * t;
+            return (point.x, point.y);
+        }
+        remaining -= length;
+    }
+    points.first().map(|p| (p.x, p.y)).unwrap_or((0.0, 0.0))
+}
+
+fn cubic_geometry(
+    source: XYPosition,
+    source_control: XYPosition,
+    target_control: XYPosition,
+    target: XYPosition,
+) -> EdgeGeometry {
+    EdgeGeometry::new(vec![PathSegment::Cubic {
+        from: source,
+        control1: source_control,
+        control2: target_control,
+        to: target,
+    }])
+}
+
+/// Straight runs through `points` with each corner rounded off by `border_radius`
+fn get_rounded_geometry(points: &[XYPosition], border_radius: f64) -> EdgeGeometry {
+    let mut geometry = EdgeGeometry::default();
+    let Some(mut cursor) = points.first().copied() else {
+        return geometry;
+    };
+    let line_to = |geometry: &mut EdgeGeometry, cursor: XYPosition, to: XYPosition| {
+        if cursor != to {
+            geometry.push(PathSegment::Line { from: cursor, to });
+        }
+    };
+
+    for i in 1..points.len().saturating_sub(1) {
+        match get_bend(points[i - 1], points[i], points[i + 1], border_radius) {
+            Some((corner_start, corner_end)) if corner_start != corner_end => {
+                line_to(&mut geometry, cursor, corner_start);
+                geometry.push(corner_arc(corner_start, points[i], corner_end));
+                cursor = corner_end;
+            }
+            _ => {
+                line_to(&mut geometry, cursor, points[i]);
+                cursor = points[i];
+            }
+        }
+    }
+    if let Some(last) = points.last().copied() {
+        line_to(&mut geometry, cursor, last);
+    }
+    if geometry.segments.is_empty() {
+        geometry.push(PathSegment::Line {
+            from: cursor,
+            to: cursor,
+        });
+    }
+    geometry
+}
+
+/// Quarter circle from `from` to `to` rounding off the right-angled `corner`
+fn corner_arc(from: XYPosition, corner: XYPosition, to: XYPosition) -> PathSegment {
+    let center = from + to - corner;
+    let start_angle = (from.y - center.y).atan2(from.x - center.x);
+    let end_angle = (to.y - center.y).atan2(to.x - center.x);
+    let mut sweep = end_angle - start_angle;
+    if sweep > std::f64::consts::PI {
+        sweep -= std::f64::consts::TAU;
+    } else if sweep < -std::f64::consts::PI {
+        sweep += std::f64::consts::TAU;
+    }
+    PathSegment::Arc {
+        center,
+        radius: center.distance_to(&from),
+        start_angle,
+        sweep,
+    }
+}
+
 fn get_edge_center(
     source_x: f64,
     source_y: f64,
@@ -448,7 +772,13 @@ fn get_smooth_step_points(
     (path_points, center_x, center_y, default_offset_x, default_offset_y)
 }
 
-fn get_bend(a: XYPosition, b: XYPosition, c: XYPosition, size: f64) -> String {
+/// Start and end of the rounded corner at `b`, or `None` when `a`, `b` and `c` are collinear
+fn get_bend(
+    a: XYPosition,
+    b: XYPosition,
+    c: XYPosition,
+    size: f64,
+) -> Option<(XYPosition, XYPosition)> {
     let bend_size = (distance(a, b) / 2.0)
         .min(distance(b, c) / 2.0)
         .min(size);
@@ -456,34 +786,24 @@ fn get_bend(a: XYPosition, b: XYPosition, c: XYPosition, size: f64) -> String {
     let y = b.y;
 
     if (a.x == x && x == c.x) || (a.y == y && y == c.y) {
-        return format!("L{} {}", x, y);
+        return None;
     }
 
     if a.y == y {
         let x_dir = if a.x < c.x { -1.0 } else { 1.0 };
         let y_dir = if a.y < c.y { 1.0 } else { -1.0 };
-        return format!(
-            "L {} {}Q {} {} {} {}",
-            x + bend_size * x_dir,
-            y,
-            x,
-            y,
-            x,
-            y + bend_size * y_dir
-        );
+        return Some((
+            XYPosition::new(x + bend_size * x_dir, y),
+            XYPosition::new(x, y + bend_size * y_dir),
+        ));
     }
 
     let x_dir = if a.x < c.x { 1.0 } else { -1.0 };
     let y_dir = if a.y < c.y { -1.0 } else { 1.0 };
-    format!(
-        "L {} {}Q {} {} {} {}",
-        x,
-        y + bend_size * y_dir,
-        x,
-        y,
-        x + bend_size * x_dir,
-        y
-    )
+    Some((
+        XYPosition::new(x, y + bend_size * y_dir),
+        XYPosition::new(x + bend_size * x_dir, y),
+    ))
 }
 
 fn handle_direction(position: Position) -> XYPosition {
@@ -509,6 +829,17 @@ fn get_direction(source: XYPosition, source_position: Position, target: XYPositi
     }
 }
 
+/// Unit vector perpendicular to the line from source to target.
+fn get_normal(source_x: f64, source_y: f64, target_x: f64, target_y: f64) -> XYPosition {
+    let dx = target_x - source_x;
+    let dy = target_y - source_y;
+    let length = (dx * dx + dy * dy).sqrt();
+    if length == 0.0 {
+        return XYPosition::new(0.0, -1.0);
+    }
+    XYPosition::new(-dy / length, dx / length)
+}
+
 fn distance(a: XYPosition, b: XYPosition) -> f64 {
     ((b.x - a.x).powi(2) + (b.y - a.y).powi(2)).sqrt()
 }
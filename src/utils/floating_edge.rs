@@ -0,0 +1,48 @@
+//! Floating edge utilities
+
+use crate::types::{FloatingEdgeParams, Position, Rect, XYPosition};
+
+/// Edge endpoints where the line between the two rect centers crosses each rect.
+pub fn get_floating_edge_params(source: &Rect, target: &Rect) -> FloatingEdgeParams {
+    let (source_point, source_position) = get_rect_intersection(source, target.center());
+    let (target_point, target_position) = get_rect_intersection(target, source.center());
+    FloatingEdgeParams {
+        source_x: source_point.x,
+        source_y: source_point.y,
+        target_x: target_point.x,
+        target_y: target_point.y,
+        source_position,
+        target_position,
+    }
+}
+
+/// Point where the line from the center of `rect` towards `point` leaves the
+/// rect, and the side it leaves through.
+pub fn get_rect_intersection(rect: &Rect, point: XYPosition) -> (XYPosition, Position) {
+    let center = rect.center();
+    let dx = point.x - center.x;
+    let dy = point.y - center.y;
+    let half_width = rect.width / 2.0;
+    let half_height = rect.height / 2.0;
+
+    if dx == 0.0 && dy == 0.0 {
+        return (XYPosition::new(center.x + half_width, center.y), Position::Right);
+    }
+
+    let scale_x = if dx != 0.0 { half_width / dx.abs() } else { f64::INFINITY };
+    let scale_y = if dy != 0.0 { half_height / dy.abs() } else { f64::INFINITY };
+
+    if scale_x <= scale_y {
+        let position = if dx > 0.0 { Position::Right } else { Position::Left };
+        (
+            XYPosition::new(center.x + dx * scale_x, center.y + dy * scale_x),
+            position,
+        )
+    } else {
+        let position = if dy > 0.0 { Position::Bottom } else { Position::Top };
+        (
+            XYPosition::new(center.x + dx * scale_y, center.y + dy * scale_y),
+            position,
+        )
+    }
+}
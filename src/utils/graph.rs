@@ -1,7 +1,7 @@
 //! Graph utilities
 
 use crate::types::{Edge, InternalNode, Node, Rect};
-use std::collections::HashSet;
+use std::collections::{HashMap, HashSet};
 
 pub fn add_edge<E: Clone + PartialEq + Default>(
     edge: Edge<E>,
@@ -100,34 +100,46 @@ pub fn get_nodes_inside<N: Clone + PartialEq + Default>(
         .collect()
 }
 
+/// Source node of each edge into `node`, in edge order. Build a `GraphIndex`
+/// for repeated or transitive queries.
 pub fn get_incomers<N, E>(node: &Node<N>, nodes: &[Node<N>], edges: &[Edge<E>]) -> Vec<Node<N>>
 where
     N: Clone + PartialEq + Default,
     E: Clone + PartialEq + Default,
 {
-    let incoming_edges: Vec<_> = edges.iter().filter(|e| e.target == node.id).collect();
-
-    incoming_edges
+    let by_id = nodes_by_id(nodes);
+    edges
         .iter()
-        .filter_map(|edge| nodes.iter().find(|n| n.id == edge.source))
-        .cloned()
+        .filter(|e| e.target == node.id)
+        .filter_map(|edge| by_id.get(edge.source.as_str()))
+        .map(|n| (*n).clone())
         .collect()
 }
 
+/// Target node of each edge from `node`, in edge order
 pub fn get_outgoers<N, E>(node: &Node<N>, nodes: &[Node<N>], edges: &[Edge<E>]) -> Vec<Node<N>>
 where
     N: Clone + PartialEq + Default,
     E: Clone + PartialEq + Default,
 {
-    let outgoing_edges: Vec<_> = edges.iter().filter(|e| e.source == node.id).collect();
-
-    outgoing_edges
+    let by_id = nodes_by_id(nodes);
+    edges
         .iter()
-        .filter_map(|edge| nodes.iter().find(|n| n.id == edge.target))
-        .cloned()
+        .filter(|e| e.source == node.id)
+        .filter_map(|edge| by_id.get(edge.target.as_str()))
+        .map(|n| (*n).clone())
         .collect()
 }
 
+/// First node with each id
+fn nodes_by_id<N: Clone + PartialEq + Default>(nodes: &[Node<N>]) -> HashMap<&str, &Node<N>> {
+    let mut by_id = HashMap::with_capacity(nodes.len());
+    for node in nodes {
+        by_id.entry(node.id.as_str()).or_insert(node);
+    }
+    by_id
+}
+
 pub fn get_connected_edges<N, E>(nodes: &[Node<N>], edges: &[Edge<E>]) -> Vec<Edge<E>>
 where
     N: Clone + PartialEq + Default,
@@ -141,3 +153,50 @@ where
         .cloned()
         .collect()
 }
+
+/// Offsets that fan out edges sharing the same pair of nodes, keyed by edge id.
+///
+/// Offsets are perpendicular to each edge's own source-to-target direction, so
+/// edges running in opposite directions still spread to distinct sides.
+/// Self-loops get increasing offsets (0, spacing, 2 * spacing, ...) so stacked
+/// loops can grow outward. Edges without siblings are not included.
+pub fn get_parallel_edge_offsets<E: Clone + PartialEq + Default>(
+    edges: &[Edge<E>],
+    spacing: f64,
+) -> HashMap<String, f64> {
+    let mut groups: HashMap<(&str, &str), Vec<&Edge<E>>> = HashMap::new();
+    for edge in edges.iter().filter(|edge| !edge.hidden) {
+        let key = if edge.source <= edge.target {
+            (edge.source.as_str(), edge.target.as_str())
+        } else {
+            (edge.target.as_str(), edge.source.as_str())
+        };
+        groups.entry(key).or_default().push(edge);
+    }
+
+    let mut offsets = HashMap::new();
+    for ((first, second), group) in groups {
+        if first == second {
+            for (index, edge) in group.iter().enumerate().skip(1) {
+                offsets.insert(edge.id.clone(), index as f64 * spacing);
+            }
+            continue;
+        }
+        if group.len() < 2 {
+            continue;
+        }
+        let center = (group.len() - 1) as f64 / 2.0;
+        for (index, edge) in group.iter().enumerate() {
+            let offset = (index as f64 - center) * spacing;
+            let offset = if edge.source.as_str() == first {
+                offset
+            } else {
+                -offset
+            };
+            if offset != 0.0 {
+                offsets.insert(edge.id.clone(), offset);
+            }
+        }
+    }
+    offsets
+}
@@ -0,0 +1,554 @@
+//! Indexed graph queries
+
+use crate::types::{Edge, Node};
+use std::cmp::{Ordering, Reverse};
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+
+/// Edge as stored in a [`GraphIndex`]
+#[derive(Clone, PartialEq, Debug)]
+pub struct GraphEdge {
+    pub id: String,
+    pub source: String,
+    pub target: String,
+    pub source_handle: Option<String>,
+    pub target_handle: Option<String>,
+}
+
+/// Path found by [`GraphIndex::shortest_path`]
+#[derive(Clone, PartialEq, Debug)]
+pub struct GraphPath {
+    /// Node ids from start to end
+    pub nodes: Vec<String>,
+    /// Ids of the edges taken, one fewer than `nodes`
+    pub edges: Vec<String>,
+    /// Sum of the edge weights, the edge count when unweighted
+    pub cost: f64,
+}
+
+/// Adjacency index of a flow's nodes and edges. Build it once per graph change
+/// and run any number of queries, each linear in the part of the graph it visits.
+///
+/// Edges whose source or target is not a node are left out. Results are
+/// deterministic; each query documents its order.
+#[derive(Clone, PartialEq, Debug, Default)]
+pub struct GraphIndex {
+    nodes: Vec<String>,
+    positions: HashMap<String, usize>,
+    edges: Vec<GraphEdge>,
+    edge_positions: HashMap<String, usize>,
+    /// Indices into `edges` by source node position
+    outgoing: Vec<Vec<usize>>,
+    /// Indices into `edges` by target node position
+    incoming: Vec<Vec<usize>>,
+}
+
+impl GraphIndex {
+    pub fn new<N, E>(nodes: &[Node<N>], edges: &[Edge<E>]) -> Self
+    where
+        N: Clone + PartialEq + Default,
+        E: Clone + PartialEq + Default,
+    {
+        let mut index = Self::default();
+        for node in nodes {
+            if index.positions.contains_key(&node.id) {
+                continue;
+            }
+            index.positions.insert(node.id.clone(), index.nodes.len());
+            index.nodes.push(node.id.clone());
+        }
+        index.outgoing = vec![Vec::new(); index.nodes.len()];
+        index.incoming = vec![Vec::new(); index.nodes.len()];
+        for edge in edges {
+            let (Some(&source), Some(&target)) = (
+                index.positions.get(&edge.source),
+                index.positions.get(&edge.target),
+            ) else {
+                continue;
+            };
+            let position = index.edges.len();
+            index.outgoing[source].push(position);
+            index.incoming[target].push(position);
+            index.edge_positions.insert(edge.id.clone(), position);
+            index.edges.push(GraphEdge {
+                id: edge.id.clone(),
+                source: edge.source.clone(),
+                target: edge.target.clone(),
+                source_handle: edge.source_handle.clone(),
+                target_handle: edge.target_handle.clone(),
+            });
+        }
+        index
+    }
+
+    pub fn contains(&self, node_id: &str) -> bool {
+        self.positions.contains_key(node_id)
+    }
+
+    pub fn node_ids(&self) -> &[String] {
+        &self.nodes
+    }
+
+    pub fn edges(&self) -> &[GraphEdge] {
+        &self.edges
+    }
+
+    pub fn edge(&self, edge_id: &str) -> Option<&GraphEdge> {
+        self.edge_positions
+            .get(edge_id)
+            .map(|&position| &self.edges[position])
+    }
+
+    /// Edges leaving `node_id`
+    pub fn outgoing_edges(&self, node_id: &str) -> Vec<&GraphEdge> {
+        self.positions
+            .get(node_id)
+            .map(|&node| self.edges_at(&self.outgoing[node]))
+            .unwrap_or_default()
+    }
+
+    /// Edges entering `node_id`
+    pub fn incoming_edges(&self, node_id: &str) -> Vec<&GraphEdge> {
+        self.positions
+            .get(node_id)
+            .map(|&node| self.edges_at(&self.incoming[node]))
+            .unwrap_or_default()
+    }
+
+    /// Edges entering or leaving `node_id`, a self-loop once
+    pub fn connected_edges(&self, node_id: &str) -> Vec<&GraphEdge> {
+        let mut edges = self.outgoing_edges(node_id);
+        edges.extend(
+            self.incoming_edges(node_id)
+                .into_iter()
+                .filter(|edge| edge.source != node_id),
+        );
+        edges
+    }
+
+    /// Edges leaving `node_id` through its source handle `handle_id`
+    pub fn edges_from_handle(&self, node_id: &str, handle_id: Option<&str>) -> Vec<&GraphEdge> {
+        self.outgoing_edges(node_id)
+            .into_iter()
+            .filter(|edge| edge.source_handle.as_deref() == handle_id)
+            .collect()
+    }
+
+    /// Edges entering `node_id` through its target handle `handle_id`
+    pub fn edges_to_handle(&self, node_id: &str, handle_id: Option<&str>) -> Vec<&GraphEdge> {
+        self.incoming_edges(node_id)
+            .into_iter()
+            .filter(|edge| edge.target_handle.as_deref() == handle_id)
+            .collect()
+    }
+
+    /// Targets of the edges leaving `node_id`, each once, in edge order
+    pub fn outgoers(&self, node_id: &str) -> Vec<String> {
+        let Some(&node) = self.positions.get(node_id) else {
+            return Vec::new();
+        };
+        self.neighbors(node, true)
+            .into_iter()
+            .map(|node| self.nodes[node].clone())
+            .collect()
+    }
+
+    /// Sources of the edges entering `node_id`, each once, in edge order
+    pub fn incomers(&self, node_id: &str) -> Vec<String> {
+        let Some(&node) = self.positions.get(node_id) else {
+            return Vec::new();
+        };
+        self.neighbors(node, false)
+            .into_iter()
+            .map(|node| self.nodes[node].clone())
+            .collect()
+    }
+
+    /// Nodes reachable from `node_id`, nearest first and in edge order at the
+    /// same distance. Includes `node_id` only when it is on a cycle.
+    pub fn descendants(&self, node_id: &str) -> Vec<String> {
+        self.reachable(node_id, true)
+    }
+
+    /// Nodes that reach `node_id`, nearest first and in edge order at the same
+    /// distance. Includes `node_id` only when it is on a cycle.
+    pub fn ancestors(&self, node_id: &str) -> Vec<String> {
+        self.reachable(node_id, false)
+    }
+
+    /// Orders the nodes so every edge points forward, or returns a cycle
+    /// (see [`Self::find_cycle`]) when there is none. Of the nodes whose
+    /// sources are all placed, the one given first comes next.
+    pub fn topological_sort(&self) -> Result<Vec<String>, Vec<String>> {
+        let mut in_degree: Vec<usize> = self.incoming.iter().map(Vec::len).collect();
+        let mut ready: BinaryHeap<Reverse<usize>> = in_degree
+            .iter()
+            .enumerate()
+            .filter(|(_, degree)| **degree == 0)
+            .map(|(node, _)| Reverse(node))
+            .collect();
+        let mut order = Vec::with_capacity(self.nodes.len());
+        while let Some(Reverse(node)) = ready.pop() {
+            order.push(self.nodes[node].clone());
+            for &edge in &self.outgoing[node] {
+                let target = self.positions[&self.edges[edge].target];
+                in_degree[target] -= 1;
+                if in_degree[target] == 0 {
+                    ready.push(Reverse(target));
+                }
+            }
+        }
+        if order.len() == self.nodes.len() {
+            Ok(order)
+        } else {
+            Err(self.find_cycle().unwrap_or_default())
+        }
+    }
+
+    /// A directed cycle as node ids in edge order, the last linking back to the
+    /// first. A self-loop is a cycle of one node.
+    pub fn find_cycle(&self) -> Option<Vec<String>> {
+        #[derive(Clone, Copy, PartialEq)]
+        enum Mark {
+            New,
+            Active,
+            Done,
+        }
+        let mut marks = vec![Mark::New; self.nodes.len()];
+        for root in 0..self.nodes.len() {
+            if marks[root] != Mark::New {
+                continue;
+            }
+            // Depth-first path with the next outgoing edge to try at each node
+            let mut path: Vec<(usize, usize)> = vec![(root, 0)];
+            marks[root] = Mark::Active;
+            while let Some((node, next)) = path.last_mut() {
+                let node = *node;
+                let Some(&edge) = self.outgoing[node].get(*next) else {
+                    marks[node] = Mark::Done;
+                    path.pop();
+                    continue;
+                };
+                *next += 1;
+                let target = self.positions[&self.edges[edge].target];
+                match marks[target] {
+                    Mark::New => {
+                        marks[target] = Mark::Active;
+                        path.push((target, 0));
+                    }
+                    Mark::Active => {
+                        let start = path.iter().position(|(node, _)| *node == target)?;
+                        return Some(
+                            path[start..]
+                                .iter()
+                                .map(|(node, _)| self.nodes[*node].clone())
+                                .collect(),
+                        );
+                    }
+                    Mark::Done => {}
+                }
+            }
+        }
+        None
+    }
+
+    pub fn has_cycle(&self) -> bool {
+        self.find_cycle().is_some()
+    }
+
+    /// Groups of nodes that all reach each other, upstream groups first. Nodes
+    /// on no cycle form groups of one. Members are in the order given; groups
+    /// with no path between them follow the search order, not the order given.
+    pub fn strongly_connected_components(&self) -> Vec<Vec<String>> {
+        // Iterative Tarjan
+        let count = self.nodes.len();
+        let mut order: Vec<Option<usize>> = vec![None; count];
+        let mut low = vec![0; count];
+        let mut on_stack = vec![false; count];
+        let mut stack: Vec<usize> = Vec::new();
+        let mut components: Vec<Vec<String>> = Vec::new();
+        let mut visited = 0;
+
+        for root in 0..count {
+            if order[root].is_some() {
+                continue;
+            }
+            let mut path: Vec<(usize, usize)> = vec![(root, 0)];
+            order[root] = Some(visited);
+            low[root] = visited;
+            visited += 1;
+            stack.push(root);
+            on_stack[root] = true;
+
+            while let Some(&(node, next)) = path.last() {
+                if let Some(&edge) = self.outgoing[node].get(next) {
+                    let last = path.len() - 1;
+                    path[last].1 += 1;
+                    let target = self.positions[&self.edges[edge].target];
+                    match order[target] {
+                        None => {
+                            order[target] = Some(visited);
+                            low[target] = visited;
+                            visited += 1;
+                            stack.push(target);
+                            on_stack[target] = true;
+                            path.push((target, 0));
+                        }
+                        Some(target_order) if on_stack[target] => {
+                            low[node] = low[node].min(target_order);
+                        }
+                        Some(_) => {}
+                    }
+                    continue;
+                }
+                path.pop();
+                if let Some(&(parent, _)) = path.last() {
+                    low[parent] = low[parent].min(low[node]);
+                }
+                if Some(low[node]) == order[node] {
+                    let mut component = Vec::new();
+                    while let Some(member) = stack.pop() {
+                        on_stack[member] = false;
+                        component.push(member);
+                        if member == node {
+                            break;
+                        }
+                    }
+                    component.sort_unstable();
+                    components.push(
+                        component
+                            .into_iter()
+                            .map(|member| self.nodes[member].clone())
+                            .collect(),
+                    );
+                }
+            }
+        }
+        // Tarjan finishes downstream components first
+        components.reverse();
+        components
+    }
+
+    /// Path from `from` to `to` with the fewest edges
+    pub fn shortest_path(&self, from: &str, to: &str) -> Option<GraphPath> {
+        self.shortest_path_by(from, to, |_| 1.0)
+    }
+
+    /// Path from `from` to `to` with the lowest total `weight`. Edges with a
+    /// negative or non-finite weight are not taken.
+    pub fn shortest_path_by(
+        &self,
+        from: &str,
+        to: &str,
+        weight: impl Fn(&GraphEdge) -> f64,
+    ) -> Option<GraphPath> {
+        let start = *self.positions.get(from)?;
+        let end = *self.positions.get(to)?;
+        let mut cost = vec![f64::INFINITY; self.nodes.len()];
+        // Edge each node was reached through
+        let mut via: Vec<Option<usize>> = vec![None; self.nodes.len()];
+        let mut queue = BinaryHeap::new();
+        cost[start] = 0.0;
+        queue.push(Reverse((Cost(0.0), start)));
+
+        while let Some(Reverse((Cost(node_cost), node))) = queue.pop() {
+            if node == end {
+                break;
+            }
+            if node_cost > cost[node] {
+                continue;
+            }
+            for &edge in &self.outgoing[node] {
+                let edge_weight = weight(&self.edges[edge]);
+                if !edge_weight.is_finite() || edge_weight < 0.0 {
+                    continue;
+                }
+                let target = self.positions[&self.edges[edge].target];
+                let next_cost = node_cost + edge_weight;
+                if next_cost < cost[target] {
+                    cost[target] = next_cost;
+                    via[target] = Some(edge);
+                    queue.push(Reverse((Cost(next_cost), target)));
+                }
+            }
+        }
+        if !cost[end].is_finite() {
+            return None;
+        }
+
+        let mut nodes = vec![self.nodes[end].clone()];
+        let mut edges = Vec::new();
+        let mut node = end;
+        while node != start {
+            let edge = &self.edges[via[node]?];
+            edges.push(edge.id.clone());
+            node = self.positions[&edge.source];
+            nodes.push(self.nodes[node].clone());
+        }
+        nodes.reverse();
+        edges.reverse();
+        Some(GraphPath {
+            nodes,
+            edges,
+            cost: cost[end],
+        })
+    }
+
+    fn edges_at(&self, positions: &[usize]) -> Vec<&GraphEdge> {
+        positions.iter().map(|&edge| &self.edges[edge]).collect()
+    }
+
+    /// Positions of the nodes at the other end of `node`'s edges, each once
+    fn neighbors(&self, node: usize, outgoing: bool) -> Vec<usize> {
+        let edges = if outgoing {
+            &self.outgoing[node]
+        } else {
+            &self.incoming[node]
+        };
+        let mut seen = HashSet::new();
+        edges
+            .iter()
+            .map(|&edge| {
+                let edge = &self.edges[edge];
+                let end = if outgoing { &edge.target } else { &edge.source };
+                self.positions[end]
+            })
+            .filter(|neighbor| seen.insert(*neighbor))
+            .collect()
+    }
+
+    fn reachable(&self, node_id: &str, outgoing: bool) -> Vec<String> {
+        let mut seen = vec![false; self.nodes.len()];
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        let mut result = Vec::new();
+        queue.push_back(match self.positions.get(node_id) {
+            Some(&node) => node,
+            None => return result,
+        });
+        while let Some(node) = queue.pop_front() {
+            for neighbor in self.neighbors(node, outgoing) {
+                if !seen[neighbor] {
+                    seen[neighbor] = true;
+                    result.push(self.nodes[neighbor].clone());
+                    queue.push_back(neighbor);
+                }
+            }
+        }
+        result
+    }
+}
+
+/// Path cost ordered with `total_cmp`, for the search queue
+#[derive(Clone, Copy, PartialEq)]
+struct Cost(f64);
+
+impl Eq for Cost {}
+
+impl PartialOrd for Cost {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        Some(self.cmp(other))
+    }
+}
+
+impl Ord for Cost {
+    fn cmp(&self, other: &Self) -> Ordering {
+        self.0.total_cmp(&other.0)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::types::XYPosition;
+
+    /// Index over nodes `ids` and edges `(source, target)`, edge ids `source-target`
+    fn index(ids: &[&str], edges: &[(&str, &str)]) -> GraphIndex {
+        let nodes: Vec<Node> = ids
+            .iter()
+            .map(|id| Node::new(*id, XYPosition::new(0.0, 0.0)))
+            .collect();
+        let edges: Vec<Edge> = edges
+            .iter()
+            .map(|(source, target)| Edge::new(format!("{source}-{target}"), *source, *target))
+            .collect();
+        GraphIndex::new(&nodes, &edges)
+    }
+
+    #[test]
+    fn topological_sort_places_the_first_given_ready_node_next() {
+        let graph = index(&["d", "c", "b", "a"], &[("a", "b"), ("c", "b")]);
+        assert_eq!(
+            graph.topological_sort(),
+            Ok(vec![
+                "d".to_string(),
+                "c".to_string(),
+                "a".to_string(),
+                "b".to_string()
+            ])
+        );
+
+        let graph = index(&["a", "b", "c"], &[("a", "b"), ("b", "c"), ("c", "b")]);
+        assert_eq!(
+            graph.topological_sort(),
+            Err(vec!["b".to_string(), "c".to_string()])
+        );
+    }
+
+    #[test]
+    fn components_come_upstream_first() {
+        let graph = index(
+            &["d", "c", "b", "a"],
+            &[("a", "b"), ("b", "c"), ("c", "b"), ("c", "d")],
+        );
+        assert_eq!(
+            graph.strongly_connected_components(),
+            vec![
+                vec!["a".to_string()],
+                vec!["c".to_string(), "b".to_string()],
+                vec!["d".to_string()],
+            ]
+        );
+    }
+
+    #[test]
+    fn weights_change_the_shortest_path() {
+        let graph = index(
+            &["a", "b", "c", "d"],
+            &[("a", "d"), ("a", "b"), ("b", "c"), ("c", "d")],
+        );
+        let path = graph.shortest_path("a", "d").unwrap();
+        assert_eq!(path.nodes, ["a", "d"]);
+        assert_eq!(path.edges, ["a-d"]);
+        assert_eq!(path.cost, 1.0);
+
+        let path = graph
+            .shortest_path_by("a", "d", |edge| if edge.id == "a-d" { 10.0 } else { 2.0 })
+            .unwrap();
+        assert_eq!(path.nodes, ["a", "b", "c", "d"]);
+        assert_eq!(path.cost, 6.0);
+
+        let path = graph
+            .shortest_path_by(
+                "a",
+                "d",
+                |edge| if edge.id == "a-d" { f64::NAN } else { 1.0 },
+            )
+            .unwrap();
+        assert_eq!(path.edges, ["a-b", "b-c", "c-d"]);
+        assert_eq!(graph.shortest_path("d", "a"), None);
+        assert_eq!(graph.shortest_path("a", "missing"), None);
+    }
+
+    #[test]
+    fn self_loops_are_cycles_of_one() {
+        let graph = index(&["a", "b"], &[("a", "b"), ("b", "b")]);
+        assert_eq!(graph.find_cycle(), Some(vec!["b".to_string()]));
+        assert_eq!(graph.connected_edges("b").len(), 2);
+        assert_eq!(graph.descendants("b"), ["b"]);
+        assert_eq!(graph.descendants("a"), ["b"]);
+        assert_eq!(graph.topological_sort(), Err(vec!["b".to_string()]));
+
+        let graph = index(&["a", "b", "c"], &[("a", "b"), ("b", "c")]);
+        assert_eq!(graph.find_cycle(), None);
+        assert!(!graph.has_cycle());
+    }
+}
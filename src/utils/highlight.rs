@@ -0,0 +1,80 @@
+//! Highlighting of related nodes and edges
+
+use super::GraphIndex;
+use crate::types::{Highlight, HighlightMode};
+use std::collections::HashSet;
+
+/// Elements `mode` highlights around the given nodes and edges
+pub fn get_highlight(
+    index: &GraphIndex,
+    mode: HighlightMode,
+    nodes: &[String],
+    edges: &[String],
+) -> Highlight {
+    let mut highlight = Highlight::default();
+    if mode == HighlightMode::None {
+        return highlight;
+    }
+    let mut upstream_from: Vec<String> = Vec::new();
+    let mut downstream_from: Vec<String> = Vec::new();
+    for id in nodes.iter().filter(|id| index.contains(id)) {
+        highlight.nodes.insert(id.clone());
+        upstream_from.push(id.clone());
+        downstream_from.push(id.clone());
+    }
+    for edge in edges.iter().filter_map(|id| index.edge(id)) {
+        highlight.edges.insert(edge.id.clone());
+        highlight.nodes.insert(edge.source.clone());
+        highlight.nodes.insert(edge.target.clone());
+        upstream_from.push(edge.source.clone());
+        downstream_from.push(edge.target.clone());
+    }
+
+    if mode == HighlightMode::Connected {
+        for id in nodes {
+            for edge in index.connected_edges(id) {
+                highlight.edges.insert(edge.id.clone());
+                highlight.nodes.insert(edge.source.clone());
+                highlight.nodes.insert(edge.target.clone());
+            }
+        }
+        return highlight;
+    }
+    if matches!(mode, HighlightMode::Upstream | HighlightMode::Lineage) {
+        add_reachable(&mut highlight, index, upstream_from, false);
+    }
+    if matches!(mode, HighlightMode::Downstream | HighlightMode::Lineage) {
+        add_reachable(&mut highlight, index, downstream_from, true);
+    }
+    highlight
+}
+
+/// Adds the nodes reachable from `start` and the edges between them
+fn add_reachable(
+    highlight: &mut Highlight,
+    index: &GraphIndex,
+    start: Vec<String>,
+    downstream: bool,
+) {
+    let mut reached: HashSet<String> = start.iter().cloned().collect();
+    let mut pending = start;
+    while let Some(id) = pending.pop() {
+        let edges = if downstream {
+            index.outgoing_edges(&id)
+        } else {
+            index.incoming_edges(&id)
+        };
+        for edge in edges {
+            highlight.edges.insert(edge.id.clone());
+            let next = if downstream {
+                &edge.target
+            } else {
+                &edge.source
+            };
+            if reached.insert(next.clone()) {
+                pending.push(next.clone());
+            }
+        }
+    }
+    highlight.nodes.extend(reached);
+}
@@ -1,9 +1,24 @@
 //! Utility functions for Dioxus Flow
 
+pub(crate) mod browser;
+mod edge_geometry;
+mod edge_label;
 mod edge_path;
+mod floating_edge;
 mod graph;
+mod graph_index;
+mod highlight;
+mod polygon;
+mod validator;
 mod viewport;
 
+pub use edge_geometry::*;
+pub use edge_label::*;
 pub use edge_path::*;
+pub use floating_edge::*;
 pub use graph::*;
+pub use graph_index::*;
+pub use highlight::*;
+pub use polygon::*;
+pub use validator::*;
 pub use viewport::*;
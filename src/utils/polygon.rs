@@ -0,0 +1,113 @@
+//! Polygon hit testing for lasso selection
+
+use crate::types::{Rect, XYPosition};
+
+/// Whether `point` lies inside `polygon` (even-odd rule); the polygon is implicitly closed
+pub fn polygon_contains_point(polygon: &[XYPosition], point: XYPosition) -> bool {
+    if polygon.len() < 3 {
+        return false;
+    }
+    let mut inside = false;
+    let mut j = polygon.len() - 1;
+    for i in 0..polygon.len() {
+        let (a, b) = (polygon[i], polygon[j]);
+        if (a.y > point.y) != (b.y > point.y)
+            && point.x < (b.x - a.x) * (point.y - a.y) / (b.y - a.y) + a.x
+        {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+/// Whether the segment `from`-`to` crosses any side of `polygon`
+pub fn polygon_intersects_segment(
+    polygon: &[XYPosition],
+    from: XYPosition,
+    to: XYPosition,
+) -> bool {
+    polygon_sides(polygon).any(|(a, b)| segments_intersect(from, to, a, b))
+}
+
+/// Whether the polyline through `points` touches `polygon`
+pub fn polygon_intersects_polyline(polygon: &[XYPosition], points: &[XYPosition]) -> bool {
+    if polygon.len() < 3 {
+        return false;
+    }
+    points
+        .iter()
+        .any(|point| polygon_contains_point(polygon, *point))
+        || points
+            .windows(2)
+            .any(|pair| polygon_intersects_segment(polygon, pair[0], pair[1]))
+}
+
+/// Whether `rect` lies entirely inside `polygon`
+pub fn polygon_contains_rect(polygon: &[XYPosition], rect: &Rect) -> bool {
+    let corners = rect_corners(rect);
+    corners
+        .iter()
+        .all(|corner| polygon_contains_point(polygon, *corner))
+        && !(0..4).any(|i| polygon_intersects_segment(polygon, corners[i], corners[(i + 1) % 4]))
+}
+
+/// Whether `rect` and `polygon` overlap at all
+pub fn polygon_intersects_rect(polygon: &[XYPosition], rect: &Rect) -> bool {
+    if polygon.len() < 3 {
+        return false;
+    }
+    let mut corners = rect_corners(rect).to_vec();
+    corners.push(corners[0]);
+    polygon_intersects_polyline(polygon, &corners)
+        || polygon.iter().any(|point| rect.contains(point))
+}
+
+/// Bounding box of `polygon`
+pub fn get_polygon_bounds(polygon: &[XYPosition]) -> Option<Rect> {
+    let first = polygon.first()?;
+    let (mut min_x, mut min_y, mut max_x, mut max_y) = (first.x, first.y, first.x, first.y);
+    for point in polygon.iter().skip(1) {
+        min_x = min_x.min(point.x);
+        min_y = min_y.min(point.y);
+        max_x = max_x.max(point.x);
+        max_y = max_y.max(point.y);
+    }
+    Some(Rect::new(min_x, min_y, max_x - min_x, max_y - min_y))
+}
+
+fn polygon_sides(polygon: &[XYPosition]) -> impl Iterator<Item = (XYPosition, XYPosition)> + '_ {
+    let count = if polygon.len() < 2 { 0 } else { polygon.len() };
+    (0..count).map(move |i| (polygon[i], polygon[(i + 1) % polygon.len()]))
+}
+
+fn rect_corners(rect: &Rect) -> [XYPosition; 4] {
+    [
+        XYPosition::new(rect.x, rect.y),
+        XYPosition::new(rect.x + rect.width, rect.y),
+        XYPosition::new(rect.x + rect.width, rect.y + rect.height),
+        XYPosition::new(rect.x, rect.y + rect.height),
+    ]
+}
+
+fn segments_intersect(p1: XYPosition, p2: XYPosition, q1: XYPosition, q2: XYPosition) -> bool {
+    let cross = |o: XYPosition, a: XYPosition, b: XYPosition| {
+        (a.x - o.x) * (b.y - o.y) - (a.y - o.y) * (b.x - o.x)
+    };
+    let on_segment = |a: XYPosition, b: XYPosition, p: XYPosition| {
+        p.x >= a.x.min(b.x) && p.x <= a.x.max(b.x) && p.y >= a.y.min(b.y) && p.y <= a.y.max(b.y)
+    };
+    let d1 = cross(q1, q2, p1);
+    let d2 = cross(q1, q2, p2);
+    let d3 = cross(p1, p2, q1);
+    let d4 = cross(p1, p2, q2);
+    if ((d1 > 0.0 && d2 < 0.0) || (d1 < 0.0 && d2 > 0.0))
+        && ((d3 > 0.0 && d4 < 0.0) || (d3 < 0.0 && d4 > 0.0))
+    {
+        return true;
+    }
+    (d1 == 0.0 && on_segment(q1, q2, p1))
+        || (d2 == 0.0 && on_segment(q1, q2, p2))
+        || (d3 == 0.0 && on_segment(p1, p2, q1))
+        || (d4 == 0.0 && on_segment(p1, p2, q2))
+}
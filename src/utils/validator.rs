@@ -0,0 +1,56 @@
+//! Graph validators
+
+use super::GraphIndex;
+use crate::types::{Diagnostic, Edge, Node};
+use std::rc::Rc;
+
+/// Checks the graph after each change batch, or once a drag ends, and reports
+/// diagnostics shown on the canvas. Closures with the same signature are
+/// validators.
+pub trait Validator<N: Clone + PartialEq + Default, E: Clone + PartialEq + Default> {
+    fn validate(&self, nodes: &[Node<N>], edges: &[Edge<E>], graph: &GraphIndex)
+    -> Vec<Diagnostic>;
+}
+
+impl<N, E, F> Validator<N, E> for F
+where
+    N: Clone + PartialEq + Default,
+    E: Clone + PartialEq + Default,
+    F: Fn(&[Node<N>], &[Edge<E>], &GraphIndex) -> Vec<Diagnostic>,
+{
+    fn validate(
+        &self,
+        nodes: &[Node<N>],
+        edges: &[Edge<E>],
+        graph: &GraphIndex,
+    ) -> Vec<Diagnostic> {
+        self(nodes, edges, graph)
+    }
+}
+
+/// Validator passed to `DioxusFlow`, compared by identity
+pub struct SharedValidator<N: Clone + PartialEq + Default = (), E: Clone + PartialEq + Default = ()>(
+    pub Rc<dyn Validator<N, E>>,
+);
+
+impl<N: Clone + PartialEq + Default, E: Clone + PartialEq + Default> SharedValidator<N, E> {
+    pub fn new(validator: impl Validator<N, E> + 'static) -> Self {
+        Self(Rc::new(validator))
+    }
+}
+
+impl<N: Clone + PartialEq + Default, E: Clone + PartialEq + Default> Clone
+    for SharedValidator<N, E>
+{
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+impl<N: Clone + PartialEq + Default, E: Clone + PartialEq + Default> PartialEq
+    for SharedValidator<N, E>
+{
+    fn eq(&self, other: &Self) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}